@@ -0,0 +1,55 @@
+use rendium::camera::Camera2D;
+use rendium::input::{Input, Key};
+use rendium::shapes::DrawShape;
+use rendium::types::{Color, Vector2};
+
+fn main() -> anyhow::Result<()> {
+    let mut camera = Camera2D::new(Vector2(0.0, 0.0), Vector2(400.0, 300.0));
+
+    rendium::init()
+        .with_size(800, 600)
+        .with_title("Example 7")
+        .run(move |rd| {
+            let speed = 300.0 * rd.delta_time().as_secs_f32() / camera.zoom;
+            if rd.is_key_down(Key::W) {
+                camera.target.1 -= speed;
+            }
+            if rd.is_key_down(Key::S) {
+                camera.target.1 += speed;
+            }
+            if rd.is_key_down(Key::A) {
+                camera.target.0 -= speed;
+            }
+            if rd.is_key_down(Key::D) {
+                camera.target.0 += speed;
+            }
+            if rd.is_key_down(Key::Q) {
+                camera.rotation -= rd.delta_time().as_secs_f32();
+            }
+            if rd.is_key_down(Key::E) {
+                camera.rotation += rd.delta_time().as_secs_f32();
+            }
+            camera.zoom = (camera.zoom * (1.0 + rd.get_scroll_delta() * 0.1)).clamp(0.1, 10.0);
+
+            let mouse_world = camera.screen_to_world(rd, rd.get_mouse_pos());
+
+            rd.draw(Color::BLACK, |d| {
+                d.with_camera(&camera, |d| {
+                    for x in -10..10 {
+                        for y in -10..10 {
+                            let col = if (x + y) % 2 == 0 {
+                                Color::rgb(60, 60, 60)
+                            } else {
+                                Color::rgb(90, 90, 90)
+                            };
+                            d.draw_rect((x as f32 * 50.0, y as f32 * 50.0).into(), 50, 50, col);
+                        }
+                    }
+                    d.draw_circle(mouse_world, 10, Color::RED, 20);
+                });
+
+                // UI stays in screen space
+                d.draw_rect((10.0, 10.0).into(), 200, 20, Color::BLUE);
+            });
+        })
+}
@@ -0,0 +1,62 @@
+use rendium::curves::{CubicBezier, DrawCurve};
+use rendium::input::Input;
+use rendium::shapes::DrawShape;
+use rendium::types::{Color, Vector2, css};
+
+fn main() -> anyhow::Result<()> {
+    let mut time: f32 = 0.0;
+
+    rendium::init()
+        .with_size(700, 700)
+        .with_title("Example 10")
+        .run(move |rd| {
+            time += rd.delta_time().as_secs_f32();
+            let mouse = rd.get_mouse_pos();
+
+            // A node editor style wire from a fixed socket to the mouse
+            let socket = Vector2(100.0, 100.0);
+            let wire = CubicBezier::new(
+                socket,
+                socket + Vector2(150.0, 0.0),
+                mouse - Vector2(150.0, 0.0),
+                mouse,
+            );
+
+            // Something moving along the wire, the same speed however long it is
+            let along = (time * 200.0) % wire.arc_length();
+
+            let points = [
+                Vector2(80.0, 500.0),
+                Vector2(200.0, 400.0),
+                Vector2(320.0, 600.0),
+                Vector2(440.0, 420.0),
+                Vector2(620.0, 560.0),
+            ];
+
+            rd.draw(Color::BLACK, |d| {
+                d.draw_bezier_cubic(
+                    wire.from,
+                    wire.control1,
+                    wire.control2,
+                    wire.to,
+                    4.0,
+                    css::ORANGE,
+                );
+                d.draw_ellipse(wire.point_at_length(along), Vector2(8.0, 8.0), Color::WHITE);
+
+                d.draw_bezier_quad(
+                    Vector2(400.0, 80.0),
+                    Vector2(650.0, 80.0),
+                    Vector2(650.0, 300.0),
+                    6.0,
+                    css::DEEPSKYBLUE,
+                );
+
+                d.draw_catmull_rom(&points, 3.0, css::LIME);
+                d.draw_b_spline(&points, 3.0, css::HOTPINK);
+                for p in points {
+                    d.draw_ellipse(p, Vector2(5.0, 5.0), Color::GRAY);
+                }
+            });
+        })
+}
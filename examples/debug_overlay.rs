@@ -0,0 +1,18 @@
+use rendium::input::Key;
+use rendium::shapes::DrawShape;
+use rendium::types::Color;
+
+fn main() -> anyhow::Result<()> {
+    rendium::init()
+        .with_size(700, 700)
+        .with_title("Example 5")
+        .with_debug_overlay_key(Key::Tab)
+        .run(|rd| {
+            rd.draw(Color::BLACK, |d| {
+                for i in 0..20 {
+                    let x = 50.0 + i as f32 * 30.0;
+                    d.draw_circle((x, 500.0).into(), 12, Color::WHITE, 20);
+                }
+            });
+        })
+}
@@ -0,0 +1,57 @@
+use rendium::gradient::{ColorStop, DrawGradient};
+use rendium::types::{Color, Vector2, css};
+
+fn main() -> anyhow::Result<()> {
+    let mut time: f32 = 0.0;
+
+    rendium::init()
+        .with_size(700, 700)
+        .with_title("Example 9")
+        .run(move |rd| {
+            time += rd.delta_time().as_secs_f32();
+
+            let sky = [
+                ColorStop::new(0.0, css::MIDNIGHTBLUE),
+                ColorStop::new(0.6, css::ROYALBLUE),
+                ColorStop::new(1.0, css::LIGHTSALMON),
+            ];
+            let sun = [
+                ColorStop::new(0.0, css::LIGHTYELLOW),
+                ColorStop::new(0.5, css::GOLD),
+                ColorStop::new(1.0, css::ORANGE.with_alpha(0)),
+            ];
+
+            // A health bar that goes from red to green as it fills up
+            let health = (time.sin() + 1.0) / 2.0;
+            let bar = [
+                ColorStop::new(0.0, css::RED),
+                ColorStop::new(0.5, css::YELLOW),
+                ColorStop::new(1.0, css::LIME),
+            ];
+
+            rd.draw(Color::BLACK, |d| {
+                d.draw_rect_gradient_v(Vector2(0.0, 0.0), Vector2(700.0, 700.0), &sky);
+                d.draw_circle_gradient(Vector2(350.0, 450.0), 150.0, &sun);
+
+                d.draw_rect_gradient_h(Vector2(50.0, 50.0), Vector2(600.0 * health, 30.0), &bar);
+
+                d.draw_rect_gradient(
+                    Vector2(50.0, 550.0),
+                    Vector2(100.0, 100.0),
+                    Color::RED,
+                    Color::GREEN,
+                    Color::BLUE,
+                    Color::WHITE,
+                );
+                d.draw_line_gradient(
+                    Vector2(200.0, 600.0),
+                    Vector2(650.0, 600.0),
+                    10.0,
+                    &[
+                        ColorStop::new(0.0, Color::TRANSPARENT),
+                        ColorStop::new(1.0, Color::WHITE),
+                    ],
+                );
+            });
+        })
+}
@@ -2,7 +2,7 @@ use rendium::input::{Input, Key};
 use rendium::shapes::DrawShape;
 use rendium::types::Color;
 
-fn main() {
+fn main() -> anyhow::Result<()> {
     rendium::init()
         .with_size(600, 600)
         .with_title("Example 3")
@@ -18,5 +18,5 @@ fn main() {
             rd.draw(Color::BLACK, |d| {
                 d.draw_circle(pos, 100, color, 40);
             });
-        });
+        })
 }
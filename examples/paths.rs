@@ -0,0 +1,74 @@
+use rendium::path::{DrawPath, Path};
+use rendium::stroke::{LineCap, LineJoin, StrokeStyle};
+use rendium::tessellate::FillRule;
+use rendium::types::{Color, Vector2, css};
+
+fn main() -> anyhow::Result<()> {
+    let mut time: f32 = 0.0;
+
+    // A heart out of two arcs and two curves
+    let mut heart = Path::new();
+    heart
+        .move_to(Vector2(350.0, 200.0))
+        .arc_to(Vector2(60.0, 60.0), 0.0, false, true, Vector2(230.0, 200.0))
+        .cubic_to(
+            Vector2(230.0, 280.0),
+            Vector2(300.0, 320.0),
+            Vector2(350.0, 380.0),
+        )
+        .cubic_to(
+            Vector2(400.0, 320.0),
+            Vector2(470.0, 280.0),
+            Vector2(470.0, 200.0),
+        )
+        .arc_to(Vector2(60.0, 60.0), 0.0, false, true, Vector2(350.0, 200.0))
+        .close();
+
+    // A star, the rule decides whether the middle is filled
+    let mut star = Path::new();
+    for i in 0..5 {
+        let angle = i as f32 * std::f32::consts::TAU * 2.0 / 5.0 - std::f32::consts::FRAC_PI_2;
+        star.line_to(Vector2(angle.cos(), angle.sin()) * 80.0);
+    }
+    star.close();
+
+    // A chart line
+    let mut chart = Path::new();
+    for i in 0..=12 {
+        let x = 50.0 + i as f32 * 50.0;
+        chart.line_to(Vector2(x, 620.0 - (i as f32 * 1.3).sin().abs() * 120.0));
+    }
+
+    rendium::init()
+        .with_size(700, 700)
+        .with_title("Example 11")
+        .run(move |rd| {
+            time += rd.delta_time().as_secs_f32();
+
+            rd.draw(Color::BLACK, |d| {
+                d.fill_path(&heart, FillRule::NonZero, css::CRIMSON);
+                d.stroke_path(
+                    &heart,
+                    &StrokeStyle::new(6.0)
+                        .with_join(LineJoin::Round)
+                        .with_dash(&[20.0, 10.0], time * 40.0),
+                    Color::WHITE,
+                );
+
+                for (x, rule) in [(150.0, FillRule::NonZero), (550.0, FillRule::EvenOdd)] {
+                    d.push_transform();
+                    d.translate(Vector2(x, 180.0));
+                    d.fill_path(&star, rule, css::GOLD);
+                    d.pop_transform();
+                }
+
+                d.stroke_path(
+                    &chart,
+                    &StrokeStyle::new(4.0)
+                        .with_join(LineJoin::Round)
+                        .with_cap(LineCap::Round),
+                    css::DEEPSKYBLUE,
+                );
+            });
+        })
+}
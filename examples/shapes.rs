@@ -1,11 +1,16 @@
 use rendium::shapes::*;
+use rendium::stroke::{LineCap, LineJoin, StrokeAlign, StrokeStyle};
 use rendium::types::Color;
 
-fn main() {
+fn main() -> anyhow::Result<()> {
+    let mut time: f32 = 0.0;
+
     rendium::init()
         .with_size(700, 700)
         .with_title("Example 2")
-        .run(|rd| {
+        .run(move |rd| {
+            time += rd.delta_time().as_secs_f32();
+
             rd.draw(Color::BLACK, |d| {
                 d.draw_rect((250.0, 250.0).into(), 200, 200, Color::BLUE);
                 d.draw_circle((100.0, 100.0).into(), 100, Color::WHITE, 20);
@@ -15,6 +20,119 @@ fn main() {
                     10,
                     Color(255, 0, 0, 100),
                 );
+
+                d.draw_rect_rounded(
+                    (480.0, 40.0).into(),
+                    (180.0, 80.0).into(),
+                    [20.0, 20.0, 0.0, 40.0],
+                    Color::GREEN,
+                );
+                d.draw_rect_rounded_lines(
+                    (480.0, 140.0).into(),
+                    (180.0, 80.0).into(),
+                    [16.0; 4],
+                    4.0,
+                    StrokeAlign::Inner,
+                    Color::WHITE,
+                );
+                d.draw_ellipse(
+                    (100.0, 400.0).into(),
+                    (80.0, 40.0).into(),
+                    Color::rgb(255, 128, 0),
+                );
+                d.draw_ring((600.0, 400.0).into(), 40.0, 60.0, Color::rgb(0, 200, 255));
+                // Translucent outlines on the inside, middle and outside of the edge
+                for (i, align) in [StrokeAlign::Inner, StrokeAlign::Center, StrokeAlign::Outer]
+                    .into_iter()
+                    .enumerate()
+                {
+                    let x = 230.0 + i as f32 * 90.0;
+                    d.draw_triangle(
+                        (x, 650.0).into(),
+                        (x + 60.0, 650.0).into(),
+                        (x + 30.0, 600.0).into(),
+                        Color::GRAY,
+                    );
+                    d.draw_triangle_lines(
+                        [
+                            (x, 650.0).into(),
+                            (x + 60.0, 650.0).into(),
+                            (x + 30.0, 600.0).into(),
+                        ],
+                        8.0,
+                        align,
+                        Color(255, 255, 255, 128),
+                    );
+                }
+                d.draw_ellipse_lines(
+                    (100.0, 400.0).into(),
+                    (80.0, 40.0).into(),
+                    10.0,
+                    StrokeAlign::Outer,
+                    Color(255, 255, 255, 128),
+                );
+                d.draw_sector(
+                    (350.0, 120.0).into(),
+                    80.0,
+                    0.5,
+                    5.5,
+                    Color::rgb(255, 220, 0),
+                );
+                d.draw_arc(
+                    (350.0, 350.0).into(),
+                    150.0,
+                    -2.5,
+                    -0.6,
+                    8.0,
+                    Color(255, 255, 255, 160),
+                );
+
+                // Translucent so the joins show they're only drawn once
+                let zigzag: Vec<_> = (0..6)
+                    .map(|i| {
+                        (
+                            60.0 + i as f32 * 40.0,
+                            if i % 2 == 0 { 480.0 } else { 540.0 },
+                        )
+                            .into()
+                    })
+                    .collect();
+                d.draw_polyline(
+                    &zigzag,
+                    16.0,
+                    LineJoin::Round,
+                    LineCap::Round,
+                    false,
+                    Color(255, 0, 255, 128),
+                );
+                d.draw_polyline(
+                    &[
+                        (420.0, 480.0).into(),
+                        (620.0, 500.0).into(),
+                        (560.0, 560.0).into(),
+                    ],
+                    10.0,
+                    LineJoin::default(),
+                    LineCap::Butt,
+                    true,
+                    Color(0, 255, 128, 160),
+                );
+
+                // Marching ants round a selection, and a dotted circle
+                d.draw_rect_lines_styled(
+                    (230.0, 230.0).into(),
+                    (240.0, 240.0).into(),
+                    &StrokeStyle::new(2.0).with_dash(&[8.0, 6.0], -time * 30.0),
+                    Color::WHITE,
+                );
+                d.draw_circle_lines_styled(
+                    (100.0, 100.0).into(),
+                    112.0,
+                    &StrokeStyle::new(4.0)
+                        .with_cap(LineCap::Round)
+                        .with_dash(&[0.0, 12.0], 0.0),
+                    Color::YELLOW,
+                );
             });
-        });
+        })
 }
@@ -0,0 +1,33 @@
+use rendium::sprite::{DrawSprite, Sprite};
+use rendium::texture::TextureLoad;
+use rendium::types::{Color, Vector2};
+
+fn main() -> anyhow::Result<()> {
+    let mut time = 0.0;
+
+    rendium::init()
+        .with_size(800, 800)
+        .with_title("Example 6")
+        .setup(|rd| {
+            rd.load_texture("logo.png", "logo")
+                .expect("failed to load logo.png");
+        })
+        .run(move |rd| {
+            time += rd.delta_time().as_secs_f32();
+
+            let sprites: Vec<Sprite> = (0..20_000)
+                .map(|i| {
+                    let angle = i as f32 * 0.01 + time;
+                    let radius = (i % 400) as f32;
+                    let pos = Vector2(400.0 + angle.cos() * radius, 400.0 + angle.sin() * radius);
+                    Sprite::new(pos, Vector2(8.0, 8.0))
+                        .with_rotation(angle)
+                        .with_color(Color::rgba(255, 255, 255, 180))
+                })
+                .collect();
+
+            rd.draw(Color::BLACK, |d| {
+                d.draw_sprites("logo", &sprites);
+            });
+        })
+}
@@ -0,0 +1,41 @@
+use rendium::svg::{DrawSvg, Svg};
+use rendium::types::{Color, Vector2};
+
+const BADGE: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100">
+  <defs>
+    <linearGradient id="sky" x1="0" y1="0" x2="0" y2="1">
+      <stop offset="0" stop-color="#4fc3f7"/>
+      <stop offset="1" stop-color="#1a237e"/>
+    </linearGradient>
+  </defs>
+  <circle cx="50" cy="50" r="46" fill="url(#sky)" stroke="white" stroke-width="4"/>
+  <path d="M20 70 L40 45 L52 58 L64 38 L80 70 Z" fill="#eceff1"/>
+  <circle cx="70" cy="28" r="7" fill="gold"/>
+  <path d="M14 80 Q50 60 86 80" fill="none" stroke="white" stroke-width="3"
+        stroke-dasharray="6 4" stroke-linecap="round"/>
+</svg>"##;
+
+fn main() -> anyhow::Result<()> {
+    let badge = Svg::parse(BADGE)?;
+    let mut time: f32 = 0.0;
+
+    rendium::init()
+        .with_size(700, 500)
+        .with_title("Example 12")
+        .run(move |rd| {
+            time += rd.delta_time().as_secs_f32();
+
+            rd.draw(Color::BLACK, |d| {
+                // The same file at three sizes, sharp at each of them
+                d.draw_svg(&badge, Vector2(30.0, 200.0), Vector2(64.0, 64.0));
+                d.draw_svg(&badge, Vector2(120.0, 168.0), Vector2(128.0, 128.0));
+
+                let size = 256.0 + time.sin() * 40.0;
+                d.draw_svg(
+                    &badge,
+                    Vector2(480.0 - size / 2.0, 250.0 - size / 2.0),
+                    Vector2(size, size),
+                );
+            });
+        })
+}
@@ -1,11 +1,25 @@
+use rendium::image::Image;
 use rendium::{texture::*, types::Color};
 
-fn main() {
+fn main() -> anyhow::Result<()> {
     rendium::init()
         .with_size(600, 600)
         .with_title("Example 4")
         .setup(|rd| {
-            rd.load_texture("../logo.png", "logo");
+            // Built into the program, so it runs from any directory
+            rd.load_texture_from_memory(include_bytes!("../logo.png"), "logo")
+                .expect("failed to load logo.png");
+
+            // A checkerboard made in code, with the logo stamped on it
+            let mut board = Image::new(128, 128, Color::WHITE);
+            for y in 0..8 {
+                for x in (y % 2..8).step_by(2) {
+                    board.draw_rect(x * 16, y * 16, 16, 16, Color::GRAY);
+                }
+            }
+            board.draw_circle(64, 64, 30, Color(255, 0, 0, 160));
+            rd.load_texture_from_image(&board, "board")
+                .expect("failed to upload the checkerboard");
         })
         .run(|rd| {
             rd.draw(Color::BLACK, |d| {
@@ -15,6 +29,12 @@ fn main() {
                     (128.0, 128.0).into(),
                     Color::WHITE,
                 );
+                d.draw_texture(
+                    "board",
+                    (100.0, 300.0).into(),
+                    (128.0, 128.0).into(),
+                    Color::WHITE,
+                );
             });
-        });
+        })
 }
@@ -0,0 +1,55 @@
+use rendium::shapes::DrawShape;
+use rendium::types::{Color, Rect, Vector2};
+
+fn main() -> anyhow::Result<()> {
+    let mut time: f32 = 0.0;
+
+    rendium::init()
+        .with_size(700, 700)
+        .with_title("Example 8")
+        .run(move |rd| {
+            time += rd.delta_time().as_secs_f32();
+
+            rd.draw(Color::BLACK, |d| {
+                // The tank
+                d.push_transform();
+                d.translate(Vector2(350.0, 350.0));
+                d.rotate(time * 0.5);
+                d.draw_rect(Vector2(-60.0, -40.0), 120, 80, Color::GREEN);
+
+                // The turret, relative to the tank
+                d.push_transform();
+                d.rotate(time * 2.0);
+                d.draw_circle(Vector2(0.0, 0.0), 25, Color::rgb(0, 150, 0), 24);
+                d.draw_rect(Vector2(0.0, -6.0), 70, 12, Color::rgb(0, 150, 0));
+                d.pop_transform();
+
+                d.pop_transform();
+
+                // The same kind of thing without a transform, drifting smoothly below a pixel
+                let pos = Vector2(120.0 + time.sin() * 0.5, 600.0);
+                d.draw_rect_pro(
+                    Rect::new(pos, Vector2(80.0, 30.0)),
+                    Vector2(40.0, 15.0),
+                    time,
+                    Color::YELLOW,
+                );
+                d.draw_ellipse_pro(
+                    Vector2(350.0, 600.0),
+                    Vector2(50.0, 20.0),
+                    -time,
+                    Color::CYAN,
+                );
+                d.draw_triangle_pro(
+                    Vector2(580.0, 600.0),
+                    [
+                        Vector2(0.0, -40.0),
+                        Vector2(35.0, 20.0),
+                        Vector2(-35.0, 20.0),
+                    ],
+                    time * 1.5,
+                    Color::MAGENTA,
+                );
+            });
+        })
+}
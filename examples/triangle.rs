@@ -1,6 +1,6 @@
 use rendium::types::Color;
 
-fn main() {
+fn main() -> anyhow::Result<()> {
     rendium::init()
         .with_size(700, 700)
         .with_title("Example 1")
@@ -14,5 +14,5 @@ fn main() {
                 d.add_index(1);
                 d.add_index(2);
             });
-        });
+        })
 }
@@ -0,0 +1,173 @@
+// This module has a 2D camera for scrolling, zooming and rotating the world
+use crate::RendiumInstance;
+use crate::types::{Matrix3, Vector2};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera2D {
+    // The point in the world the camera looks at
+    pub target: Vector2,
+    // Where on the screen the target ends up, usually the center of the window
+    pub offset: Vector2,
+    pub zoom: f32,
+    // In radians, around the target
+    pub rotation: f32,
+}
+
+impl Default for Camera2D {
+    fn default() -> Self {
+        Self::new(Vector2::zero(), Vector2::zero())
+    }
+}
+
+impl Camera2D {
+    pub fn new(target: Vector2, offset: Vector2) -> Self {
+        Self {
+            target,
+            offset,
+            zoom: 1.0,
+            rotation: 0.0,
+        }
+    }
+
+    pub fn with_zoom(mut self, zoom: f32) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    // Both take window positions like `Input::get_mouse_pos`, which stay y-down with `y_up`,
+    // so the window flip is folded in and the two are always inverses of each other.
+    pub fn world_to_screen(&self, rd: &RendiumInstance, pos: Vector2) -> Vector2 {
+        self.window_matrix(rd).transform_point(pos)
+    }
+
+    // Finds what's under the cursor
+    pub fn screen_to_world(&self, rd: &RendiumInstance, pos: Vector2) -> Vector2 {
+        match self.window_matrix(rd).inverse() {
+            Some(inverse) => inverse.transform_point(pos),
+            // A zoom of zero squashes the whole world into the target
+            None => self.target,
+        }
+    }
+
+    // World to window pixels, y-down whichever way the screen space goes
+    fn window_matrix(&self, rd: &RendiumInstance) -> Matrix3 {
+        if rd.is_y_up() {
+            let height = rd.get_window_size().1 as f32;
+            Matrix3::translation(Vector2(0.0, height))
+                * Matrix3::scale(Vector2(1.0, -1.0))
+                * self.matrix()
+        } else {
+            self.matrix()
+        }
+    }
+
+    // World to screen pixels
+    pub fn matrix(&self) -> Matrix3 {
+        Matrix3::translation(self.offset)
+            * Matrix3::rotation(self.rotation)
+            * Matrix3::scale(Vector2(self.zoom, self.zoom))
+            * Matrix3::translation(-self.target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+    use winit::dpi::PhysicalSize;
+
+    fn instance(y_up: bool) -> RendiumInstance {
+        let mut rd =
+            RendiumInstance::new(PhysicalSize::new(800, 600), String::new(), Box::new(|_| {}));
+        rd.set_y_up(y_up);
+        rd
+    }
+
+    fn assert_near(a: Vector2, b: Vector2) {
+        assert!(a.approx_eq(b, 1e-3), "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn round_trips_with_zoom_rotation_and_offset() {
+        let camera = Camera2D::new(Vector2(120.0, -40.0), Vector2(400.0, 300.0))
+            .with_zoom(2.5)
+            .with_rotation(0.7);
+        for y_up in [false, true] {
+            let rd = instance(y_up);
+            for p in [
+                Vector2(0.0, 0.0),
+                Vector2(13.0, 250.0),
+                Vector2(-300.0, 77.5),
+            ] {
+                assert_near(
+                    camera.screen_to_world(&rd, camera.world_to_screen(&rd, p)),
+                    p,
+                );
+                assert_near(
+                    camera.world_to_screen(&rd, camera.screen_to_world(&rd, p)),
+                    p,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn target_lands_on_offset() {
+        let camera = Camera2D::new(Vector2(120.0, -40.0), Vector2(400.0, 100.0))
+            .with_zoom(3.0)
+            .with_rotation(1.0);
+        assert_near(
+            camera.world_to_screen(&instance(false), camera.target),
+            camera.offset,
+        );
+        // With y going up the offset is measured from the bottom, the mouse from the top
+        assert_near(
+            camera.world_to_screen(&instance(true), camera.target),
+            Vector2(400.0, 500.0),
+        );
+    }
+
+    #[test]
+    fn zoom_and_rotation_turn_around_the_target() {
+        let camera = Camera2D::new(Vector2(10.0, 10.0), Vector2(100.0, 100.0))
+            .with_zoom(2.0)
+            .with_rotation(FRAC_PI_2);
+        // One unit right of the target ends up two pixels below the offset
+        assert_near(
+            camera.world_to_screen(&instance(false), Vector2(11.0, 10.0)),
+            Vector2(100.0, 102.0),
+        );
+    }
+
+    #[test]
+    fn mouse_positions_flip_with_y_up() {
+        let camera = Camera2D::new(Vector2::zero(), Vector2(400.0, 300.0));
+        // Drawn at world (0, 100), which is 100 pixels above the offset with y going up
+        let mouse = Vector2(400.0, 200.0);
+        assert_near(
+            camera.screen_to_world(&instance(true), mouse),
+            Vector2(0.0, 100.0),
+        );
+        assert_near(
+            camera.screen_to_world(&instance(false), mouse),
+            Vector2(0.0, -100.0),
+        );
+    }
+
+    #[test]
+    fn zero_zoom_falls_back_to_the_target() {
+        let camera = Camera2D::new(Vector2(5.0, -7.0), Vector2(400.0, 300.0)).with_zoom(0.0);
+        for y_up in [false, true] {
+            let rd = instance(y_up);
+            assert_eq!(
+                camera.screen_to_world(&rd, Vector2(123.0, 45.0)),
+                camera.target
+            );
+        }
+    }
+}
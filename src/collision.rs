@@ -0,0 +1,243 @@
+// This module has overlap and intersection tests for 2D shapes.
+// Together with `Input::get_mouse_pos` they work as hover tests.
+use crate::types::{Rect, Vector2};
+
+pub fn point_in_circle(p: Vector2, center: Vector2, radius: f32) -> bool {
+    (p - center).length_squared() <= radius * radius
+}
+
+pub fn circle_circle(c1: Vector2, r1: f32, c2: Vector2, r2: f32) -> bool {
+    let r = r1 + r2;
+    (c2 - c1).length_squared() <= r * r
+}
+
+pub fn circle_rect(center: Vector2, radius: f32, rect: &Rect) -> bool {
+    // The closest point of the rect is inside the circle
+    point_in_circle(rect.clamp_point(center), center, radius)
+}
+
+// Points on the edges count as inside, the winding doesn't matter
+pub fn point_in_triangle(p: Vector2, a: Vector2, b: Vector2, c: Vector2) -> bool {
+    let d1 = (b - a).cross(p - a);
+    let d2 = (c - b).cross(p - b);
+    let d3 = (a - c).cross(p - c);
+
+    let has_negative = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+    let has_positive = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+    !(has_negative && has_positive)
+}
+
+// Uses the even-odd rule, so it works for concave and self-intersecting polygons.
+// The polygon is closed automatically.
+pub fn point_in_polygon(p: Vector2, points: &[Vector2]) -> bool {
+    if points.len() < 3 {
+        return false;
+    }
+
+    let mut inside = false;
+    let mut prev = points[points.len() - 1];
+    for &cur in points {
+        // Count the edges crossing a ray going to the right of `p`
+        if (cur.1 > p.1) != (prev.1 > p.1) {
+            let x = cur.0 + (p.1 - cur.1) / (prev.1 - cur.1) * (prev.0 - cur.0);
+            if p.0 < x {
+                inside = !inside;
+            }
+        }
+        prev = cur;
+    }
+    inside
+}
+
+// Where the segments a1-a2 and b1-b2 cross. Parallel segments never intersect, even when they overlap.
+pub fn segment_intersection(a1: Vector2, a2: Vector2, b1: Vector2, b2: Vector2) -> Option<Vector2> {
+    let da = a2 - a1;
+    let db = b2 - b1;
+    let denom = da.cross(db);
+    if denom == 0.0 {
+        return None;
+    }
+
+    let t = (b1 - a1).cross(db) / denom;
+    let u = (b1 - a1).cross(da) / denom;
+    ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then(|| a1 + da * t)
+}
+
+pub fn segments_intersect(a1: Vector2, a2: Vector2, b1: Vector2, b2: Vector2) -> bool {
+    segment_intersection(a1, a2, b1, b2).is_some()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SweepHit {
+    // How far along the movement the hit happens, from 0 to 1
+    pub time: f32,
+    // The side of the target that was hit, pointing away from it
+    pub normal: Vector2,
+}
+
+// Moves `moving` by `velocity` and finds the first time it touches `target`.
+// Rects that already overlap hit at time 0, with no normal.
+pub fn swept_aabb(moving: &Rect, velocity: Vector2, target: &Rect) -> Option<SweepHit> {
+    if moving.intersects(target) {
+        return Some(SweepHit {
+            time: 0.0,
+            normal: Vector2::zero(),
+        });
+    }
+
+    // Sweeping a rect against a rect is the same as a point against the target grown by its size
+    let expanded = Rect::new(target.pos - moving.size, target.size + moving.size);
+    let origin = moving.pos;
+
+    let mut entry = f32::NEG_INFINITY;
+    let mut exit = f32::INFINITY;
+    let mut normal = Vector2::zero();
+
+    for axis in 0..2 {
+        let (min, max) = (expanded.min()[axis], expanded.max()[axis]);
+        if velocity[axis] == 0.0 {
+            // Moving parallel to this axis, it has to already be in between
+            if origin[axis] <= min || origin[axis] >= max {
+                return None;
+            }
+            continue;
+        }
+
+        let t1 = (min - origin[axis]) / velocity[axis];
+        let t2 = (max - origin[axis]) / velocity[axis];
+        let (near, far) = if t1 < t2 { (t1, t2) } else { (t2, t1) };
+
+        if near > entry {
+            entry = near;
+            normal = Vector2::zero();
+            normal[axis] = -velocity[axis].signum();
+        }
+        exit = exit.min(far);
+    }
+
+    (entry < exit && (0.0..=1.0).contains(&entry)).then_some(SweepHit {
+        time: entry,
+        normal,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circles_touching_overlap() {
+        assert!(point_in_circle(Vector2(3.0, 4.0), Vector2::zero(), 5.0));
+        assert!(!point_in_circle(Vector2(3.0, 4.1), Vector2::zero(), 5.0));
+        assert!(circle_circle(Vector2::zero(), 2.0, Vector2(5.0, 0.0), 3.0));
+        assert!(!circle_circle(Vector2::zero(), 2.0, Vector2(5.1, 0.0), 3.0));
+        // A zero radius circle is a point
+        assert!(circle_circle(Vector2::zero(), 0.0, Vector2::zero(), 0.0));
+    }
+
+    #[test]
+    fn circle_rect_edges_and_corners() {
+        let r = Rect::new(Vector2(0.0, 0.0), Vector2(10.0, 10.0));
+        // Inside, touching an edge and touching a corner
+        assert!(circle_rect(Vector2(5.0, 5.0), 1.0, &r));
+        assert!(circle_rect(Vector2(12.0, 5.0), 2.0, &r));
+        assert!(circle_rect(Vector2(13.0, 14.0), 5.0, &r));
+        assert!(!circle_rect(Vector2(12.0, 5.0), 1.9, &r));
+        // Near a corner but past it on the diagonal
+        assert!(!circle_rect(Vector2(12.0, 12.0), 2.0, &r));
+        // A zero-size rect works like a point
+        let point = Rect::new(Vector2(1.0, 1.0), Vector2::zero());
+        assert!(circle_rect(Vector2(1.0, 2.0), 1.0, &point));
+        assert!(!circle_rect(Vector2(1.0, 2.1), 1.0, &point));
+    }
+
+    #[test]
+    fn point_in_triangle_edges() {
+        let (a, b, c) = (Vector2(0.0, 0.0), Vector2(4.0, 0.0), Vector2(0.0, 4.0));
+        assert!(point_in_triangle(Vector2(1.0, 1.0), a, b, c));
+        // Either winding
+        assert!(point_in_triangle(Vector2(1.0, 1.0), a, c, b));
+        // Edges and vertices are inside
+        assert!(point_in_triangle(Vector2(2.0, 0.0), a, b, c));
+        assert!(point_in_triangle(Vector2(2.0, 2.0), a, b, c));
+        assert!(point_in_triangle(c, a, b, c));
+        assert!(!point_in_triangle(Vector2(2.1, 2.0), a, b, c));
+        assert!(!point_in_triangle(Vector2(-0.1, 1.0), a, b, c));
+    }
+
+    #[test]
+    fn point_in_polygon_even_odd() {
+        // A U shape, the notch is outside
+        let u = [
+            Vector2(0.0, 0.0),
+            Vector2(3.0, 0.0),
+            Vector2(3.0, 3.0),
+            Vector2(2.0, 3.0),
+            Vector2(2.0, 1.0),
+            Vector2(1.0, 1.0),
+            Vector2(1.0, 3.0),
+            Vector2(0.0, 3.0),
+        ];
+        assert!(point_in_polygon(Vector2(0.5, 2.0), &u));
+        assert!(point_in_polygon(Vector2(2.5, 2.0), &u));
+        assert!(point_in_polygon(Vector2(1.5, 0.5), &u));
+        assert!(!point_in_polygon(Vector2(1.5, 2.0), &u));
+        assert!(!point_in_polygon(Vector2(4.0, 0.5), &u));
+
+        // Too few points is never a polygon
+        assert!(!point_in_polygon(Vector2::zero(), &u[..2]));
+    }
+
+    #[test]
+    fn segments() {
+        let hit = segment_intersection(
+            Vector2(0.0, 0.0),
+            Vector2(2.0, 2.0),
+            Vector2(0.0, 2.0),
+            Vector2(2.0, 0.0),
+        );
+        assert_eq!(hit, Some(Vector2(1.0, 1.0)));
+        // Touching at an end point counts
+        assert!(segments_intersect(
+            Vector2(0.0, 0.0),
+            Vector2(1.0, 0.0),
+            Vector2(1.0, 0.0),
+            Vector2(1.0, 5.0),
+        ));
+        assert!(!segments_intersect(
+            Vector2(0.0, 0.0),
+            Vector2(1.0, 0.0),
+            Vector2(1.1, -1.0),
+            Vector2(1.1, 1.0),
+        ));
+        // Overlapping but parallel
+        assert!(!segments_intersect(
+            Vector2(0.0, 0.0),
+            Vector2(2.0, 0.0),
+            Vector2(1.0, 0.0),
+            Vector2(3.0, 0.0),
+        ));
+    }
+
+    #[test]
+    fn swept_aabb_hits() {
+        let moving = Rect::new(Vector2(0.0, 0.0), Vector2(2.0, 2.0));
+        let wall = Rect::new(Vector2(10.0, -5.0), Vector2(2.0, 10.0));
+
+        let hit = swept_aabb(&moving, Vector2(16.0, 0.0), &wall).unwrap();
+        assert_eq!(hit.time, 0.5);
+        assert_eq!(hit.normal, Vector2(-1.0, 0.0));
+
+        // Stopping short, moving away, or sliding past along the edge
+        assert_eq!(swept_aabb(&moving, Vector2(7.0, 0.0), &wall), None);
+        assert_eq!(swept_aabb(&moving, Vector2(-16.0, 0.0), &wall), None);
+        let above = Rect::new(Vector2(0.0, -7.0), Vector2(2.0, 2.0));
+        assert_eq!(swept_aabb(&above, Vector2(20.0, 0.0), &wall), None);
+
+        // Already overlapping
+        let inside = Rect::new(Vector2(10.5, 0.0), Vector2(1.0, 1.0));
+        let hit = swept_aabb(&inside, Vector2(5.0, 0.0), &wall).unwrap();
+        assert_eq!(hit.time, 0.0);
+        assert_eq!(hit.normal, Vector2::zero());
+    }
+}
@@ -0,0 +1,790 @@
+// Colors. `Color` is 8 bit sRGB like image files and CSS, `LinearColor` is float linear light,
+// which is what blending and lighting math needs and what can go above 1.0 for HDR.
+use std::ops::{Add, Mul};
+
+use anyhow::{Context, bail};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Color(pub u8, pub u8, pub u8, pub u8);
+
+impl Color {
+    pub const WHITE: Self = Self(255, 255, 255, 255);
+    pub const BLACK: Self = Self(0, 0, 0, 255);
+    pub const RED: Self = Self(255, 0, 0, 255);
+    pub const GREEN: Self = Self(0, 255, 0, 255);
+    pub const BLUE: Self = Self(0, 0, 255, 255);
+    pub const YELLOW: Self = Self(255, 255, 0, 255);
+    pub const CYAN: Self = Self(0, 255, 255, 255);
+    pub const MAGENTA: Self = Self(255, 0, 255, 255);
+    pub const GRAY: Self = Self(128, 128, 128, 255);
+    pub const TRANSPARENT: Self = Self(0, 0, 0, 0);
+
+    pub fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self(r, g, b, a)
+    }
+
+    pub fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self(r, g, b, 255)
+    }
+
+    // Accepts "#rgb", "#rgba", "#rrggbb" and "#rrggbbaa", the '#' is optional
+    pub fn from_hex(hex: &str) -> anyhow::Result<Self> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        // from_str_radix alone would take a sign, like "+f"
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            bail!("Invalid hex color {hex:?}");
+        }
+
+        let channel = |i: usize, len: usize| -> anyhow::Result<u8> {
+            let v = u8::from_str_radix(&digits[i * len..(i + 1) * len], 16)
+                .with_context(|| format!("Invalid hex color {hex:?}"))?;
+            // A single digit is repeated, so "f" means "ff"
+            Ok(if len == 1 { v * 17 } else { v })
+        };
+
+        match digits.len() {
+            3 => Ok(Self(channel(0, 1)?, channel(1, 1)?, channel(2, 1)?, 255)),
+            4 => Ok(Self(
+                channel(0, 1)?,
+                channel(1, 1)?,
+                channel(2, 1)?,
+                channel(3, 1)?,
+            )),
+            6 => Ok(Self(channel(0, 2)?, channel(1, 2)?, channel(2, 2)?, 255)),
+            8 => Ok(Self(
+                channel(0, 2)?,
+                channel(1, 2)?,
+                channel(2, 2)?,
+                channel(3, 2)?,
+            )),
+            _ => bail!("Invalid hex color {hex:?}, expected 3, 4, 6 or 8 digits"),
+        }
+    }
+
+    // "#rrggbb", or "#rrggbbaa" when the color isn't opaque
+    pub fn to_hex(self) -> String {
+        if self.3 == 255 {
+            format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", self.0, self.1, self.2, self.3)
+        }
+    }
+
+    // Hue in degrees, saturation and value in 0..1. Like everywhere else, this works on the
+    // sRGB values, so it matches color pickers.
+    pub fn from_hsv(h: f32, s: f32, v: f32) -> Self {
+        let s = s.clamp(0.0, 1.0);
+        let v = v.clamp(0.0, 1.0);
+        let c = v * s;
+        let (r, g, b) = hue_to_rgb(h, c);
+        let m = v - c;
+        Self::from_unit(r + m, g + m, b + m, 1.0)
+    }
+
+    // Returns (hue in degrees, saturation, value)
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let (r, g, b) = self.unit_rgb();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let s = if max > 0.0 { (max - min) / max } else { 0.0 };
+        (hue(r, g, b, max, min), s, max)
+    }
+
+    // Hue in degrees, saturation and lightness in 0..1
+    pub fn from_hsl(h: f32, s: f32, l: f32) -> Self {
+        let s = s.clamp(0.0, 1.0);
+        let l = l.clamp(0.0, 1.0);
+        let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let (r, g, b) = hue_to_rgb(h, c);
+        let m = l - c / 2.0;
+        Self::from_unit(r + m, g + m, b + m, 1.0)
+    }
+
+    // Returns (hue in degrees, saturation, lightness)
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let (r, g, b) = self.unit_rgb();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / 2.0;
+        let s = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * l - 1.0).abs())
+        };
+        (hue(r, g, b, max, min), s, l)
+    }
+
+    pub fn with_alpha(self, a: u8) -> Self {
+        Self(self.0, self.1, self.2, a)
+    }
+
+    // Multiplies the alpha, so fade(0.5) makes the color half as opaque as it was
+    pub fn fade(self, amount: f32) -> Self {
+        let a = (self.3 as f32 * amount.clamp(0.0, 1.0)).round() as u8;
+        self.with_alpha(a)
+    }
+
+    // Mixes in linear light, the same way the GPU blends and interpolates vertex colors
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        LinearColor::from(self)
+            .lerp(other.into(), t.clamp(0.0, 1.0))
+            .into()
+    }
+
+    // Raises the HSL lightness by `amount` (0..1), keeping hue, saturation and alpha
+    pub fn brighten(self, amount: f32) -> Self {
+        let (h, s, l) = self.to_hsl();
+        Self::from_hsl(h, s, l + amount).with_alpha(self.3)
+    }
+
+    pub fn darken(self, amount: f32) -> Self {
+        self.brighten(-amount)
+    }
+
+    // Draws `self` on top of `dst` with normal alpha blending, in linear light like the GPU
+    pub fn blend_over(self, dst: Self) -> Self {
+        LinearColor::from(self).over(dst.into()).into()
+    }
+
+    pub fn to_linear(self) -> [f32; 4] {
+        LinearColor::from(self).into()
+    }
+
+    fn unit_rgb(self) -> (f32, f32, f32) {
+        (
+            self.0 as f32 / 255.0,
+            self.1 as f32 / 255.0,
+            self.2 as f32 / 255.0,
+        )
+    }
+
+    fn from_unit(r: f32, g: f32, b: f32, a: f32) -> Self {
+        let to_u8 = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Self(to_u8(r), to_u8(g), to_u8(b), to_u8(a))
+    }
+}
+
+// The rgb of a fully saturated hue with chroma `c`, before the lightness offset is added
+fn hue_to_rgb(h: f32, c: f32) -> (f32, f32, f32) {
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    }
+}
+
+fn hue(r: f32, g: f32, b: f32, max: f32, min: f32) -> f32 {
+    let d = max - min;
+    if d == 0.0 {
+        return 0.0;
+    }
+    let h = if max == r {
+        ((g - b) / d).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    h * 60.0
+}
+
+// The exact sRGB transfer functions, alpha is never encoded
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+// The sRGB values as they are. Clearing an sRGB target wants `LinearColor` instead.
+impl From<Color> for wgpu::Color {
+    fn from(c: Color) -> Self {
+        wgpu::Color {
+            r: c.0 as f64 / 255.0,
+            g: c.1 as f64 / 255.0,
+            b: c.2 as f64 / 255.0,
+            a: c.3 as f64 / 255.0,
+        }
+    }
+}
+
+impl From<Color> for [f32; 4] {
+    fn from(c: Color) -> Self {
+        [
+            c.0 as f32 / 255.0,
+            c.1 as f32 / 255.0,
+            c.2 as f32 / 255.0,
+            c.3 as f32 / 255.0,
+        ]
+    }
+}
+
+// Linear light with straight (not premultiplied) alpha. Values above 1.0 are allowed for HDR,
+// converting back to `Color` clamps them.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Default, bytemuck::Pod, bytemuck::Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinearColor(pub f32, pub f32, pub f32, pub f32);
+
+impl LinearColor {
+    pub const WHITE: Self = Self(1.0, 1.0, 1.0, 1.0);
+    pub const BLACK: Self = Self(0.0, 0.0, 0.0, 1.0);
+    pub const TRANSPARENT: Self = Self(0.0, 0.0, 0.0, 0.0);
+
+    pub fn rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self(r, g, b, a)
+    }
+
+    pub fn rgb(r: f32, g: f32, b: f32) -> Self {
+        Self(r, g, b, 1.0)
+    }
+
+    pub fn with_alpha(self, a: f32) -> Self {
+        Self(self.0, self.1, self.2, a)
+    }
+
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        self * (1.0 - t) + other * t
+    }
+
+    // Source-over compositing of `self` on top of `dst`
+    pub fn over(self, dst: Self) -> Self {
+        let a = self.3 + dst.3 * (1.0 - self.3);
+        if a <= 0.0 {
+            return Self::TRANSPARENT;
+        }
+        let mix = |s: f32, d: f32| (s * self.3 + d * dst.3 * (1.0 - self.3)) / a;
+        Self(
+            mix(self.0, dst.0),
+            mix(self.1, dst.1),
+            mix(self.2, dst.2),
+            a,
+        )
+    }
+
+    pub fn premultiplied(self) -> Self {
+        Self(self.0 * self.3, self.1 * self.3, self.2 * self.3, self.3)
+    }
+
+    // Relative luminance, how bright the color looks
+    pub fn luminance(self) -> f32 {
+        0.2126 * self.0 + 0.7152 * self.1 + 0.0722 * self.2
+    }
+}
+
+impl From<Color> for LinearColor {
+    fn from(c: Color) -> Self {
+        let (r, g, b) = c.unit_rgb();
+        Self(
+            srgb_to_linear(r),
+            srgb_to_linear(g),
+            srgb_to_linear(b),
+            c.3 as f32 / 255.0,
+        )
+    }
+}
+
+impl From<LinearColor> for Color {
+    fn from(c: LinearColor) -> Self {
+        let encode = |v: f32| linear_to_srgb(v.clamp(0.0, 1.0));
+        Color::from_unit(encode(c.0), encode(c.1), encode(c.2), c.3)
+    }
+}
+
+impl From<LinearColor> for [f32; 4] {
+    fn from(c: LinearColor) -> Self {
+        [c.0, c.1, c.2, c.3]
+    }
+}
+
+impl From<[f32; 4]> for LinearColor {
+    fn from(c: [f32; 4]) -> Self {
+        Self(c[0], c[1], c[2], c[3])
+    }
+}
+
+impl From<LinearColor> for wgpu::Color {
+    fn from(c: LinearColor) -> Self {
+        wgpu::Color {
+            r: c.0 as f64,
+            g: c.1 as f64,
+            b: c.2 as f64,
+            a: c.3 as f64,
+        }
+    }
+}
+
+impl Add for LinearColor {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(
+            self.0 + other.0,
+            self.1 + other.1,
+            self.2 + other.2,
+            self.3 + other.3,
+        )
+    }
+}
+
+impl Mul for LinearColor {
+    type Output = Self;
+
+    // Component-wise, for tinting
+    fn mul(self, other: Self) -> Self {
+        Self(
+            self.0 * other.0,
+            self.1 * other.1,
+            self.2 * other.2,
+            self.3 * other.3,
+        )
+    }
+}
+
+impl Mul<f32> for LinearColor {
+    type Output = Self;
+
+    fn mul(self, s: f32) -> Self {
+        Self(self.0 * s, self.1 * s, self.2 * s, self.3 * s)
+    }
+}
+
+// The named colors from CSS. `css::GREEN` is CSS green (0, 128, 0), `Color::GREEN` is pure green.
+pub mod css {
+    use super::Color;
+
+    // Looks up a CSS color name, ignoring case
+    pub fn from_name(name: &str) -> Option<Color> {
+        let name = name.to_ascii_lowercase();
+        NAMED.iter().find(|(n, _)| *n == name).map(|(_, c)| *c)
+    }
+
+    pub const ALICEBLUE: Color = Color(240, 248, 255, 255);
+    pub const ANTIQUEWHITE: Color = Color(250, 235, 215, 255);
+    pub const AQUA: Color = Color(0, 255, 255, 255);
+    pub const AQUAMARINE: Color = Color(127, 255, 212, 255);
+    pub const AZURE: Color = Color(240, 255, 255, 255);
+    pub const BEIGE: Color = Color(245, 245, 220, 255);
+    pub const BISQUE: Color = Color(255, 228, 196, 255);
+    pub const BLACK: Color = Color(0, 0, 0, 255);
+    pub const BLANCHEDALMOND: Color = Color(255, 235, 205, 255);
+    pub const BLUE: Color = Color(0, 0, 255, 255);
+    pub const BLUEVIOLET: Color = Color(138, 43, 226, 255);
+    pub const BROWN: Color = Color(165, 42, 42, 255);
+    pub const BURLYWOOD: Color = Color(222, 184, 135, 255);
+    pub const CADETBLUE: Color = Color(95, 158, 160, 255);
+    pub const CHARTREUSE: Color = Color(127, 255, 0, 255);
+    pub const CHOCOLATE: Color = Color(210, 105, 30, 255);
+    pub const CORAL: Color = Color(255, 127, 80, 255);
+    pub const CORNFLOWERBLUE: Color = Color(100, 149, 237, 255);
+    pub const CORNSILK: Color = Color(255, 248, 220, 255);
+    pub const CRIMSON: Color = Color(220, 20, 60, 255);
+    pub const CYAN: Color = Color(0, 255, 255, 255);
+    pub const DARKBLUE: Color = Color(0, 0, 139, 255);
+    pub const DARKCYAN: Color = Color(0, 139, 139, 255);
+    pub const DARKGOLDENROD: Color = Color(184, 134, 11, 255);
+    pub const DARKGRAY: Color = Color(169, 169, 169, 255);
+    pub const DARKGREEN: Color = Color(0, 100, 0, 255);
+    pub const DARKGREY: Color = Color(169, 169, 169, 255);
+    pub const DARKKHAKI: Color = Color(189, 183, 107, 255);
+    pub const DARKMAGENTA: Color = Color(139, 0, 139, 255);
+    pub const DARKOLIVEGREEN: Color = Color(85, 107, 47, 255);
+    pub const DARKORANGE: Color = Color(255, 140, 0, 255);
+    pub const DARKORCHID: Color = Color(153, 50, 204, 255);
+    pub const DARKRED: Color = Color(139, 0, 0, 255);
+    pub const DARKSALMON: Color = Color(233, 150, 122, 255);
+    pub const DARKSEAGREEN: Color = Color(143, 188, 143, 255);
+    pub const DARKSLATEBLUE: Color = Color(72, 61, 139, 255);
+    pub const DARKSLATEGRAY: Color = Color(47, 79, 79, 255);
+    pub const DARKSLATEGREY: Color = Color(47, 79, 79, 255);
+    pub const DARKTURQUOISE: Color = Color(0, 206, 209, 255);
+    pub const DARKVIOLET: Color = Color(148, 0, 211, 255);
+    pub const DEEPPINK: Color = Color(255, 20, 147, 255);
+    pub const DEEPSKYBLUE: Color = Color(0, 191, 255, 255);
+    pub const DIMGRAY: Color = Color(105, 105, 105, 255);
+    pub const DIMGREY: Color = Color(105, 105, 105, 255);
+    pub const DODGERBLUE: Color = Color(30, 144, 255, 255);
+    pub const FIREBRICK: Color = Color(178, 34, 34, 255);
+    pub const FLORALWHITE: Color = Color(255, 250, 240, 255);
+    pub const FORESTGREEN: Color = Color(34, 139, 34, 255);
+    pub const FUCHSIA: Color = Color(255, 0, 255, 255);
+    pub const GAINSBORO: Color = Color(220, 220, 220, 255);
+    pub const GHOSTWHITE: Color = Color(248, 248, 255, 255);
+    pub const GOLD: Color = Color(255, 215, 0, 255);
+    pub const GOLDENROD: Color = Color(218, 165, 32, 255);
+    pub const GRAY: Color = Color(128, 128, 128, 255);
+    pub const GREEN: Color = Color(0, 128, 0, 255);
+    pub const GREENYELLOW: Color = Color(173, 255, 47, 255);
+    pub const GREY: Color = Color(128, 128, 128, 255);
+    pub const HONEYDEW: Color = Color(240, 255, 240, 255);
+    pub const HOTPINK: Color = Color(255, 105, 180, 255);
+    pub const INDIANRED: Color = Color(205, 92, 92, 255);
+    pub const INDIGO: Color = Color(75, 0, 130, 255);
+    pub const IVORY: Color = Color(255, 255, 240, 255);
+    pub const KHAKI: Color = Color(240, 230, 140, 255);
+    pub const LAVENDER: Color = Color(230, 230, 250, 255);
+    pub const LAVENDERBLUSH: Color = Color(255, 240, 245, 255);
+    pub const LAWNGREEN: Color = Color(124, 252, 0, 255);
+    pub const LEMONCHIFFON: Color = Color(255, 250, 205, 255);
+    pub const LIGHTBLUE: Color = Color(173, 216, 230, 255);
+    pub const LIGHTCORAL: Color = Color(240, 128, 128, 255);
+    pub const LIGHTCYAN: Color = Color(224, 255, 255, 255);
+    pub const LIGHTGOLDENRODYELLOW: Color = Color(250, 250, 210, 255);
+    pub const LIGHTGRAY: Color = Color(211, 211, 211, 255);
+    pub const LIGHTGREEN: Color = Color(144, 238, 144, 255);
+    pub const LIGHTGREY: Color = Color(211, 211, 211, 255);
+    pub const LIGHTPINK: Color = Color(255, 182, 193, 255);
+    pub const LIGHTSALMON: Color = Color(255, 160, 122, 255);
+    pub const LIGHTSEAGREEN: Color = Color(32, 178, 170, 255);
+    pub const LIGHTSKYBLUE: Color = Color(135, 206, 250, 255);
+    pub const LIGHTSLATEGRAY: Color = Color(119, 136, 153, 255);
+    pub const LIGHTSLATEGREY: Color = Color(119, 136, 153, 255);
+    pub const LIGHTSTEELBLUE: Color = Color(176, 196, 222, 255);
+    pub const LIGHTYELLOW: Color = Color(255, 255, 224, 255);
+    pub const LIME: Color = Color(0, 255, 0, 255);
+    pub const LIMEGREEN: Color = Color(50, 205, 50, 255);
+    pub const LINEN: Color = Color(250, 240, 230, 255);
+    pub const MAGENTA: Color = Color(255, 0, 255, 255);
+    pub const MAROON: Color = Color(128, 0, 0, 255);
+    pub const MEDIUMAQUAMARINE: Color = Color(102, 205, 170, 255);
+    pub const MEDIUMBLUE: Color = Color(0, 0, 205, 255);
+    pub const MEDIUMORCHID: Color = Color(186, 85, 211, 255);
+    pub const MEDIUMPURPLE: Color = Color(147, 112, 219, 255);
+    pub const MEDIUMSEAGREEN: Color = Color(60, 179, 113, 255);
+    pub const MEDIUMSLATEBLUE: Color = Color(123, 104, 238, 255);
+    pub const MEDIUMSPRINGGREEN: Color = Color(0, 250, 154, 255);
+    pub const MEDIUMTURQUOISE: Color = Color(72, 209, 204, 255);
+    pub const MEDIUMVIOLETRED: Color = Color(199, 21, 133, 255);
+    pub const MIDNIGHTBLUE: Color = Color(25, 25, 112, 255);
+    pub const MINTCREAM: Color = Color(245, 255, 250, 255);
+    pub const MISTYROSE: Color = Color(255, 228, 225, 255);
+    pub const MOCCASIN: Color = Color(255, 228, 181, 255);
+    pub const NAVAJOWHITE: Color = Color(255, 222, 173, 255);
+    pub const NAVY: Color = Color(0, 0, 128, 255);
+    pub const OLDLACE: Color = Color(253, 245, 230, 255);
+    pub const OLIVE: Color = Color(128, 128, 0, 255);
+    pub const OLIVEDRAB: Color = Color(107, 142, 35, 255);
+    pub const ORANGE: Color = Color(255, 165, 0, 255);
+    pub const ORANGERED: Color = Color(255, 69, 0, 255);
+    pub const ORCHID: Color = Color(218, 112, 214, 255);
+    pub const PALEGOLDENROD: Color = Color(238, 232, 170, 255);
+    pub const PALEGREEN: Color = Color(152, 251, 152, 255);
+    pub const PALETURQUOISE: Color = Color(175, 238, 238, 255);
+    pub const PALEVIOLETRED: Color = Color(219, 112, 147, 255);
+    pub const PAPAYAWHIP: Color = Color(255, 239, 213, 255);
+    pub const PEACHPUFF: Color = Color(255, 218, 185, 255);
+    pub const PERU: Color = Color(205, 133, 63, 255);
+    pub const PINK: Color = Color(255, 192, 203, 255);
+    pub const PLUM: Color = Color(221, 160, 221, 255);
+    pub const POWDERBLUE: Color = Color(176, 224, 230, 255);
+    pub const PURPLE: Color = Color(128, 0, 128, 255);
+    pub const REBECCAPURPLE: Color = Color(102, 51, 153, 255);
+    pub const RED: Color = Color(255, 0, 0, 255);
+    pub const ROSYBROWN: Color = Color(188, 143, 143, 255);
+    pub const ROYALBLUE: Color = Color(65, 105, 225, 255);
+    pub const SADDLEBROWN: Color = Color(139, 69, 19, 255);
+    pub const SALMON: Color = Color(250, 128, 114, 255);
+    pub const SANDYBROWN: Color = Color(244, 164, 96, 255);
+    pub const SEAGREEN: Color = Color(46, 139, 87, 255);
+    pub const SEASHELL: Color = Color(255, 245, 238, 255);
+    pub const SIENNA: Color = Color(160, 82, 45, 255);
+    pub const SILVER: Color = Color(192, 192, 192, 255);
+    pub const SKYBLUE: Color = Color(135, 206, 235, 255);
+    pub const SLATEBLUE: Color = Color(106, 90, 205, 255);
+    pub const SLATEGRAY: Color = Color(112, 128, 144, 255);
+    pub const SLATEGREY: Color = Color(112, 128, 144, 255);
+    pub const SNOW: Color = Color(255, 250, 250, 255);
+    pub const SPRINGGREEN: Color = Color(0, 255, 127, 255);
+    pub const STEELBLUE: Color = Color(70, 130, 180, 255);
+    pub const TAN: Color = Color(210, 180, 140, 255);
+    pub const TEAL: Color = Color(0, 128, 128, 255);
+    pub const THISTLE: Color = Color(216, 191, 216, 255);
+    pub const TOMATO: Color = Color(255, 99, 71, 255);
+    pub const TURQUOISE: Color = Color(64, 224, 208, 255);
+    pub const VIOLET: Color = Color(238, 130, 238, 255);
+    pub const WHEAT: Color = Color(245, 222, 179, 255);
+    pub const WHITE: Color = Color(255, 255, 255, 255);
+    pub const WHITESMOKE: Color = Color(245, 245, 245, 255);
+    pub const YELLOW: Color = Color(255, 255, 0, 255);
+    pub const YELLOWGREEN: Color = Color(154, 205, 50, 255);
+
+    const NAMED: [(&str, Color); 148] = [
+        ("aliceblue", ALICEBLUE),
+        ("antiquewhite", ANTIQUEWHITE),
+        ("aqua", AQUA),
+        ("aquamarine", AQUAMARINE),
+        ("azure", AZURE),
+        ("beige", BEIGE),
+        ("bisque", BISQUE),
+        ("black", BLACK),
+        ("blanchedalmond", BLANCHEDALMOND),
+        ("blue", BLUE),
+        ("blueviolet", BLUEVIOLET),
+        ("brown", BROWN),
+        ("burlywood", BURLYWOOD),
+        ("cadetblue", CADETBLUE),
+        ("chartreuse", CHARTREUSE),
+        ("chocolate", CHOCOLATE),
+        ("coral", CORAL),
+        ("cornflowerblue", CORNFLOWERBLUE),
+        ("cornsilk", CORNSILK),
+        ("crimson", CRIMSON),
+        ("cyan", CYAN),
+        ("darkblue", DARKBLUE),
+        ("darkcyan", DARKCYAN),
+        ("darkgoldenrod", DARKGOLDENROD),
+        ("darkgray", DARKGRAY),
+        ("darkgreen", DARKGREEN),
+        ("darkgrey", DARKGREY),
+        ("darkkhaki", DARKKHAKI),
+        ("darkmagenta", DARKMAGENTA),
+        ("darkolivegreen", DARKOLIVEGREEN),
+        ("darkorange", DARKORANGE),
+        ("darkorchid", DARKORCHID),
+        ("darkred", DARKRED),
+        ("darksalmon", DARKSALMON),
+        ("darkseagreen", DARKSEAGREEN),
+        ("darkslateblue", DARKSLATEBLUE),
+        ("darkslategray", DARKSLATEGRAY),
+        ("darkslategrey", DARKSLATEGREY),
+        ("darkturquoise", DARKTURQUOISE),
+        ("darkviolet", DARKVIOLET),
+        ("deeppink", DEEPPINK),
+        ("deepskyblue", DEEPSKYBLUE),
+        ("dimgray", DIMGRAY),
+        ("dimgrey", DIMGREY),
+        ("dodgerblue", DODGERBLUE),
+        ("firebrick", FIREBRICK),
+        ("floralwhite", FLORALWHITE),
+        ("forestgreen", FORESTGREEN),
+        ("fuchsia", FUCHSIA),
+        ("gainsboro", GAINSBORO),
+        ("ghostwhite", GHOSTWHITE),
+        ("gold", GOLD),
+        ("goldenrod", GOLDENROD),
+        ("gray", GRAY),
+        ("green", GREEN),
+        ("greenyellow", GREENYELLOW),
+        ("grey", GREY),
+        ("honeydew", HONEYDEW),
+        ("hotpink", HOTPINK),
+        ("indianred", INDIANRED),
+        ("indigo", INDIGO),
+        ("ivory", IVORY),
+        ("khaki", KHAKI),
+        ("lavender", LAVENDER),
+        ("lavenderblush", LAVENDERBLUSH),
+        ("lawngreen", LAWNGREEN),
+        ("lemonchiffon", LEMONCHIFFON),
+        ("lightblue", LIGHTBLUE),
+        ("lightcoral", LIGHTCORAL),
+        ("lightcyan", LIGHTCYAN),
+        ("lightgoldenrodyellow", LIGHTGOLDENRODYELLOW),
+        ("lightgray", LIGHTGRAY),
+        ("lightgreen", LIGHTGREEN),
+        ("lightgrey", LIGHTGREY),
+        ("lightpink", LIGHTPINK),
+        ("lightsalmon", LIGHTSALMON),
+        ("lightseagreen", LIGHTSEAGREEN),
+        ("lightskyblue", LIGHTSKYBLUE),
+        ("lightslategray", LIGHTSLATEGRAY),
+        ("lightslategrey", LIGHTSLATEGREY),
+        ("lightsteelblue", LIGHTSTEELBLUE),
+        ("lightyellow", LIGHTYELLOW),
+        ("lime", LIME),
+        ("limegreen", LIMEGREEN),
+        ("linen", LINEN),
+        ("magenta", MAGENTA),
+        ("maroon", MAROON),
+        ("mediumaquamarine", MEDIUMAQUAMARINE),
+        ("mediumblue", MEDIUMBLUE),
+        ("mediumorchid", MEDIUMORCHID),
+        ("mediumpurple", MEDIUMPURPLE),
+        ("mediumseagreen", MEDIUMSEAGREEN),
+        ("mediumslateblue", MEDIUMSLATEBLUE),
+        ("mediumspringgreen", MEDIUMSPRINGGREEN),
+        ("mediumturquoise", MEDIUMTURQUOISE),
+        ("mediumvioletred", MEDIUMVIOLETRED),
+        ("midnightblue", MIDNIGHTBLUE),
+        ("mintcream", MINTCREAM),
+        ("mistyrose", MISTYROSE),
+        ("moccasin", MOCCASIN),
+        ("navajowhite", NAVAJOWHITE),
+        ("navy", NAVY),
+        ("oldlace", OLDLACE),
+        ("olive", OLIVE),
+        ("olivedrab", OLIVEDRAB),
+        ("orange", ORANGE),
+        ("orangered", ORANGERED),
+        ("orchid", ORCHID),
+        ("palegoldenrod", PALEGOLDENROD),
+        ("palegreen", PALEGREEN),
+        ("paleturquoise", PALETURQUOISE),
+        ("palevioletred", PALEVIOLETRED),
+        ("papayawhip", PAPAYAWHIP),
+        ("peachpuff", PEACHPUFF),
+        ("peru", PERU),
+        ("pink", PINK),
+        ("plum", PLUM),
+        ("powderblue", POWDERBLUE),
+        ("purple", PURPLE),
+        ("rebeccapurple", REBECCAPURPLE),
+        ("red", RED),
+        ("rosybrown", ROSYBROWN),
+        ("royalblue", ROYALBLUE),
+        ("saddlebrown", SADDLEBROWN),
+        ("salmon", SALMON),
+        ("sandybrown", SANDYBROWN),
+        ("seagreen", SEAGREEN),
+        ("seashell", SEASHELL),
+        ("sienna", SIENNA),
+        ("silver", SILVER),
+        ("skyblue", SKYBLUE),
+        ("slateblue", SLATEBLUE),
+        ("slategray", SLATEGRAY),
+        ("slategrey", SLATEGREY),
+        ("snow", SNOW),
+        ("springgreen", SPRINGGREEN),
+        ("steelblue", STEELBLUE),
+        ("tan", TAN),
+        ("teal", TEAL),
+        ("thistle", THISTLE),
+        ("tomato", TOMATO),
+        ("turquoise", TURQUOISE),
+        ("violet", VIOLET),
+        ("wheat", WHEAT),
+        ("white", WHITE),
+        ("whitesmoke", WHITESMOKE),
+        ("yellow", YELLOW),
+        ("yellowgreen", YELLOWGREEN),
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: (f32, f32, f32), b: (f32, f32, f32)) {
+        let eps = 1e-2;
+        assert!(
+            (a.0 - b.0).abs() <= 0.5 && (a.1 - b.1).abs() <= eps && (a.2 - b.2).abs() <= eps,
+            "{a:?} != {b:?}"
+        );
+    }
+
+    #[test]
+    fn from_hex_lengths() {
+        assert_eq!(Color::from_hex("#f80").unwrap(), Color(255, 136, 0, 255));
+        assert_eq!(Color::from_hex("f808").unwrap(), Color(255, 136, 0, 136));
+        assert_eq!(Color::from_hex("#1a2B3c").unwrap(), Color(26, 43, 60, 255));
+        assert_eq!(
+            Color::from_hex("#1a2b3c80").unwrap(),
+            Color(26, 43, 60, 128)
+        );
+    }
+
+    #[test]
+    fn from_hex_rejects_bad_input() {
+        for hex in [
+            "",
+            "#",
+            "#ff",
+            "#fffff",
+            "#fffffffff",
+            "#ggg",
+            "#+f+f+f",
+            "#-1-1-1",
+            "#ff ff",
+            "#ééé",
+        ] {
+            assert!(Color::from_hex(hex).is_err(), "{hex:?} parsed");
+        }
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        for c in [
+            Color(1, 2, 3, 255),
+            Color(255, 128, 0, 7),
+            Color::TRANSPARENT,
+        ] {
+            assert_eq!(Color::from_hex(&c.to_hex()).unwrap(), c);
+        }
+        assert_eq!(Color(255, 0, 16, 255).to_hex(), "#ff0010");
+        assert_eq!(Color(255, 0, 16, 32).to_hex(), "#ff001020");
+    }
+
+    #[test]
+    fn hsv() {
+        assert_eq!(Color::from_hsv(0.0, 1.0, 1.0), Color::RED);
+        assert_eq!(Color::from_hsv(120.0, 1.0, 1.0), Color::GREEN);
+        assert_eq!(Color::from_hsv(240.0, 1.0, 1.0), Color::BLUE);
+        // Hue wraps around
+        assert_eq!(Color::from_hsv(-300.0, 1.0, 1.0), Color::YELLOW);
+        assert_eq!(Color::from_hsv(60.0, 0.0, 1.0), Color::WHITE);
+        assert_eq!(Color::from_hsv(60.0, 1.0, 0.0), Color::BLACK);
+
+        assert_close(Color::CYAN.to_hsv(), (180.0, 1.0, 1.0));
+        assert_close(Color::GRAY.to_hsv(), (0.0, 0.0, 128.0 / 255.0));
+        assert_close(Color(255, 128, 0, 255).to_hsv(), (30.1, 1.0, 1.0));
+    }
+
+    #[test]
+    fn hsl() {
+        assert_eq!(Color::from_hsl(0.0, 1.0, 0.5), Color::RED);
+        assert_eq!(Color::from_hsl(300.0, 1.0, 0.5), Color::MAGENTA);
+        assert_eq!(Color::from_hsl(0.0, 1.0, 1.0), Color::WHITE);
+        assert_eq!(Color::from_hsl(0.0, 1.0, 0.0), Color::BLACK);
+        assert_eq!(Color::from_hsl(120.0, 1.0, 0.25), Color(0, 128, 0, 255));
+
+        assert_close(Color::BLUE.to_hsl(), (240.0, 1.0, 0.5));
+        assert_close(Color::WHITE.to_hsl(), (0.0, 0.0, 1.0));
+        assert_close(Color(0, 128, 0, 255).to_hsl(), (120.0, 1.0, 0.25));
+    }
+
+    #[test]
+    fn hsv_and_hsl_round_trip() {
+        for c in [
+            Color(12, 200, 99, 255),
+            Color(250, 10, 180, 255),
+            Color(77, 77, 200, 255),
+            Color(3, 2, 1, 255),
+        ] {
+            let (h, s, v) = c.to_hsv();
+            assert_eq!(Color::from_hsv(h, s, v), c);
+            let (h, s, l) = c.to_hsl();
+            assert_eq!(Color::from_hsl(h, s, l), c);
+        }
+    }
+
+    #[test]
+    fn lerp() {
+        assert_eq!(Color::BLACK.lerp(Color::WHITE, 0.0), Color::BLACK);
+        assert_eq!(Color::BLACK.lerp(Color::WHITE, 1.0), Color::WHITE);
+        // t is clamped
+        assert_eq!(Color::BLACK.lerp(Color::WHITE, 2.0), Color::WHITE);
+
+        // Halfway in linear light is brighter than halfway in sRGB
+        let mid = Color::BLACK.lerp(Color::WHITE, 0.5);
+        assert_eq!(mid, Color::from(LinearColor(0.5, 0.5, 0.5, 1.0)));
+        assert!(mid.0 > 180, "{mid:?}");
+
+        let mid = Color::RED.lerp(Color::BLUE.with_alpha(0), 0.5);
+        assert_eq!(mid.3, 128);
+    }
+}
@@ -0,0 +1,639 @@
+// This module has Bézier curves and splines through points. They are drawn by cutting them
+// into short straight pieces, as many as it takes to stay within a quarter of a pixel,
+// and drawing those as a polyline, or as strips of quads when they're opaque.
+use crate::RendiumDrawHandle;
+use crate::shapes::DrawShape;
+use crate::stroke::{LineCap, LineJoin};
+use crate::tessellate::{add_strip, solid};
+use crate::types::{Color, Vector2};
+
+// How far a flattened curve may be from the real one, in pixels
+const TOLERANCE: f32 = 0.25;
+// Deep enough for any curve on screen, and stops curves with huge or broken points from running forever
+const MAX_DEPTH: u32 = 16;
+// The sharpest turn a strip is mitered at rather than split, as the cosine of the angle.
+// The miters stick out less than 4% of the thickness past a round join.
+const SMOOTH_TURN: f32 = 0.85;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuadBezier {
+    pub from: Vector2,
+    pub control: Vector2,
+    pub to: Vector2,
+}
+
+impl QuadBezier {
+    pub fn new(from: Vector2, control: Vector2, to: Vector2) -> Self {
+        Self { from, control, to }
+    }
+
+    pub fn point_at(&self, t: f32) -> Vector2 {
+        let a = self.from.lerp(self.control, t);
+        let b = self.control.lerp(self.to, t);
+        a.lerp(b, t)
+    }
+
+    // Not normalized, its length is the speed along the curve
+    pub fn tangent_at(&self, t: f32) -> Vector2 {
+        ((self.control - self.from) * (1.0 - t) + (self.to - self.control) * t) * 2.0
+    }
+
+    pub fn arc_length(&self) -> f32 {
+        CubicBezier::from(*self).arc_length()
+    }
+
+    pub fn t_at_length(&self, length: f32) -> f32 {
+        CubicBezier::from(*self).t_at_length(length)
+    }
+
+    pub fn point_at_length(&self, length: f32) -> Vector2 {
+        self.point_at(self.t_at_length(length))
+    }
+
+    // Points along the curve, no further than `tolerance` from it, including both ends
+    pub fn flatten(&self, tolerance: f32) -> Vec<Vector2> {
+        CubicBezier::from(*self).flatten(tolerance)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CubicBezier {
+    pub from: Vector2,
+    pub control1: Vector2,
+    pub control2: Vector2,
+    pub to: Vector2,
+}
+
+impl CubicBezier {
+    pub fn new(from: Vector2, control1: Vector2, control2: Vector2, to: Vector2) -> Self {
+        Self {
+            from,
+            control1,
+            control2,
+            to,
+        }
+    }
+
+    pub fn point_at(&self, t: f32) -> Vector2 {
+        let u = 1.0 - t;
+        self.from * (u * u * u)
+            + self.control1 * (3.0 * u * u * t)
+            + self.control2 * (3.0 * u * t * t)
+            + self.to * (t * t * t)
+    }
+
+    // Not normalized, its length is the speed along the curve
+    pub fn tangent_at(&self, t: f32) -> Vector2 {
+        let u = 1.0 - t;
+        (self.control1 - self.from) * (3.0 * u * u)
+            + (self.control2 - self.control1) * (6.0 * u * t)
+            + (self.to - self.control2) * (3.0 * t * t)
+    }
+
+    pub fn arc_length(&self) -> f32 {
+        let points = self.flatten(self.fine_tolerance());
+        points.windows(2).map(|p| p[0].distance(p[1])).sum()
+    }
+
+    // The `t` that's `length` along the curve from the start. Going up `t` at a steady rate
+    // speeds up and slows down along the curve, going up the length doesn't. Lengths past
+    // either end give that end.
+    pub fn t_at_length(&self, length: f32) -> f32 {
+        if length <= 0.0 {
+            return 0.0;
+        }
+        let mut travelled = 0.0;
+        let mut last = (0.0, self.from);
+        let mut found = None;
+        self.flatten_into(self.fine_tolerance(), MAX_DEPTH, (0.0, 1.0), &mut |t, p| {
+            let step = last.1.distance(p);
+            if found.is_none() && travelled + step >= length {
+                found = Some((last, t, length - travelled));
+            }
+            travelled += step;
+            last = (t, p);
+        });
+        let Some(((mut low, start), mut high, rest)) = found else {
+            return 1.0;
+        };
+        // The piece is straight, but the curve can still speed up or slow down along it
+        for _ in 0..24 {
+            let mid = (low + high) / 2.0;
+            if self.point_at(mid).distance(start) < rest {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        (low + high) / 2.0
+    }
+
+    pub fn point_at_length(&self, length: f32) -> Vector2 {
+        self.point_at(self.t_at_length(length))
+    }
+
+    // The curve is never longer than its control points, so this is a close fit whatever its size
+    fn fine_tolerance(&self) -> f32 {
+        let hull = self.from.distance(self.control1)
+            + self.control1.distance(self.control2)
+            + self.control2.distance(self.to);
+        hull * 1e-5
+    }
+
+    // The two halves of the curve, split at `t`
+    pub fn split(&self, t: f32) -> (Self, Self) {
+        let a = self.from.lerp(self.control1, t);
+        let b = self.control1.lerp(self.control2, t);
+        let c = self.control2.lerp(self.to, t);
+        let ab = a.lerp(b, t);
+        let bc = b.lerp(c, t);
+        let mid = ab.lerp(bc, t);
+        (
+            Self::new(self.from, a, ab, mid),
+            Self::new(mid, bc, c, self.to),
+        )
+    }
+
+    // Points along the curve, no further than `tolerance` from it, including both ends
+    pub fn flatten(&self, tolerance: f32) -> Vec<Vector2> {
+        let mut points = vec![self.from];
+        self.flatten_into(tolerance, MAX_DEPTH, (0.0, 1.0), &mut |_, p| points.push(p));
+        points
+    }
+
+    // Calls `f` with the end of every piece after the start, and its `t` on the whole curve.
+    // `span` is where this part of the curve is on the whole curve.
+    fn flatten_into(
+        &self,
+        tolerance: f32,
+        depth: u32,
+        span: (f32, f32),
+        f: &mut impl FnMut(f32, Vector2),
+    ) {
+        if depth == 0 || self.flatness() <= tolerance {
+            f(span.1, self.to);
+            return;
+        }
+        let (first, second) = self.split(0.5);
+        let mid = (span.0 + span.1) / 2.0;
+        first.flatten_into(tolerance, depth - 1, (span.0, mid), f);
+        second.flatten_into(tolerance, depth - 1, (mid, span.1), f);
+    }
+
+    // How far the curve can be from the straight line between its ends
+    fn flatness(&self) -> f32 {
+        let chord = self.to - self.from;
+        let length = chord.length();
+        let distance = |p: Vector2| {
+            if length == 0.0 {
+                p.distance(self.from)
+            } else {
+                (p - self.from).cross(chord).abs() / length
+            }
+        };
+        0.75 * distance(self.control1).max(distance(self.control2))
+    }
+}
+
+impl From<QuadBezier> for CubicBezier {
+    // The same curve, with the control point split in two
+    fn from(q: QuadBezier) -> Self {
+        Self::new(
+            q.from,
+            q.from.lerp(q.control, 2.0 / 3.0),
+            q.to.lerp(q.control, 2.0 / 3.0),
+            q.to,
+        )
+    }
+}
+
+// A smooth curve going through every point, as one cubic for each pair of neighbours
+pub fn catmull_rom(points: &[Vector2]) -> Vec<CubicBezier> {
+    let n = points.len();
+    (0..n.saturating_sub(1))
+        .map(|i| {
+            // The ends get a neighbour that's the same as them
+            let p0 = points[i.saturating_sub(1)];
+            let (p1, p2) = (points[i], points[i + 1]);
+            let p3 = points[(i + 2).min(n - 1)];
+            CubicBezier::new(p1, p1 + (p2 - p0) / 6.0, p2 - (p3 - p1) / 6.0, p2)
+        })
+        .collect()
+}
+
+// A smoother curve than `catmull_rom` that goes through the first and last point
+// but is only pulled towards the others
+pub fn b_spline(points: &[Vector2]) -> Vec<CubicBezier> {
+    let (Some(&first), Some(&last)) = (points.first(), points.last()) else {
+        return Vec::new();
+    };
+    // Repeating the ends three times pins the curve to them
+    let mut padded = vec![first, first];
+    padded.extend_from_slice(points);
+    padded.extend([last, last]);
+
+    padded
+        .windows(4)
+        .map(|p| {
+            CubicBezier::new(
+                (p[0] + p[1] * 4.0 + p[2]) / 6.0,
+                (p[1] * 2.0 + p[2]) / 3.0,
+                (p[1] + p[2] * 2.0) / 3.0,
+                (p[1] + p[2] * 4.0 + p[3]) / 6.0,
+            )
+        })
+        .collect()
+}
+
+pub trait DrawCurve {
+    fn draw_bezier_quad(
+        &mut self,
+        from: Vector2,
+        control: Vector2,
+        to: Vector2,
+        thickness: f32,
+        col: Color,
+    );
+    fn draw_bezier_cubic(
+        &mut self,
+        from: Vector2,
+        control1: Vector2,
+        control2: Vector2,
+        to: Vector2,
+        thickness: f32,
+        col: Color,
+    );
+    // Goes through every point
+    fn draw_catmull_rom(&mut self, points: &[Vector2], thickness: f32, col: Color);
+    // Goes through the first and last point, the others pull it towards them
+    fn draw_b_spline(&mut self, points: &[Vector2], thickness: f32, col: Color);
+}
+
+impl DrawCurve for RendiumDrawHandle {
+    fn draw_bezier_quad(
+        &mut self,
+        from: Vector2,
+        control: Vector2,
+        to: Vector2,
+        thickness: f32,
+        col: Color,
+    ) {
+        draw_cubics(
+            self,
+            &[QuadBezier::new(from, control, to).into()],
+            thickness,
+            col,
+        );
+    }
+
+    fn draw_bezier_cubic(
+        &mut self,
+        from: Vector2,
+        control1: Vector2,
+        control2: Vector2,
+        to: Vector2,
+        thickness: f32,
+        col: Color,
+    ) {
+        let curve = CubicBezier::new(from, control1, control2, to);
+        draw_cubics(self, &[curve], thickness, col);
+    }
+
+    fn draw_catmull_rom(&mut self, points: &[Vector2], thickness: f32, col: Color) {
+        draw_cubics(self, &catmull_rom(points), thickness, col);
+    }
+
+    fn draw_b_spline(&mut self, points: &[Vector2], thickness: f32, col: Color) {
+        draw_cubics(self, &b_spline(points), thickness, col);
+    }
+}
+
+// The curves one after another as a single line, so the places they meet don't overlap
+fn draw_cubics(d: &mut RendiumDrawHandle, curves: &[CubicBezier], thickness: f32, col: Color) {
+    let tolerance = flatten_tolerance(d);
+    let mut points = Vec::new();
+    for curve in curves {
+        let flat = curve.flatten(tolerance);
+        let skip = usize::from(!points.is_empty());
+        points.extend_from_slice(&flat[skip..]);
+    }
+    // Where an opaque line overlaps itself doesn't show, so it can be strips along the points
+    // instead of an outline with the overlaps taken out, which is much quicker
+    if col.3 == 255 {
+        add_opaque_line(d, &points, thickness.abs() / 2.0, col);
+        return;
+    }
+    d.draw_polyline(
+        &points,
+        thickness,
+        LineJoin::Round,
+        LineCap::Butt,
+        false,
+        col,
+    );
+}
+
+// A line `half` its thickness out either side of the points, as strips mitered at every point.
+// Sharp turns end one strip and start the next, with a circle round the corner for a round join.
+fn add_opaque_line(d: &mut RendiumDrawHandle, points: &[Vector2], half: f32, col: Color) {
+    let mut points = points.to_vec();
+    points.dedup();
+    let normals: Vec<Vector2> = points
+        .windows(2)
+        .map(|p| (p[1] - p[0]).perpendicular().normalized())
+        .collect();
+    let mut start = 0;
+    for i in 1..points.len() {
+        let last = i == normals.len();
+        let sharp = !last && normals[i - 1].dot(normals[i]) < SMOOTH_TURN;
+        if !last && !sharp {
+            continue;
+        }
+        let (left, right) = strip_sides(&points[start..=i], &normals[start..i], half);
+        add_strip(d, &left, &right, &solid(col));
+        if sharp {
+            d.draw_circle_v(points[i], half, col);
+        }
+        start = i;
+    }
+}
+
+// Both sides of a line through the points, with the normal of each segment between them
+fn strip_sides(points: &[Vector2], normals: &[Vector2], half: f32) -> (Vec<Vector2>, Vec<Vector2>) {
+    let mut left = Vec::with_capacity(points.len());
+    let mut right = Vec::with_capacity(points.len());
+    for (i, &p) in points.iter().enumerate() {
+        // The ends only have one segment to go by
+        let (n1, n2) = (
+            normals[i.saturating_sub(1)],
+            normals[i.min(normals.len() - 1)],
+        );
+        let offset = (n1 + n2) * (half / (1.0 + n1.dot(n2)));
+        left.push(p + offset);
+        right.push(p - offset);
+    }
+    (left, right)
+}
+
+// `TOLERANCE` in the current coordinates
+pub(crate) fn flatten_tolerance(d: &RendiumDrawHandle) -> f32 {
+    TOLERANCE / d.pixels_per_unit().max(1e-6)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use winit::dpi::PhysicalSize;
+
+    const EPSILON: f32 = 1e-4;
+
+    fn assert_vec2_eq(a: Vector2, b: Vector2) {
+        assert!(a.approx_eq(b, EPSILON), "{a:?} != {b:?}");
+    }
+
+    fn s_curve() -> CubicBezier {
+        CubicBezier::new(
+            Vector2(0.0, 0.0),
+            Vector2(10.0, 30.0),
+            Vector2(40.0, -20.0),
+            Vector2(50.0, 10.0),
+        )
+    }
+
+    // How many of the triangles drawn `p` is in
+    fn coverage(d: &RendiumDrawHandle, p: Vector2) -> usize {
+        d.indices
+            .chunks(3)
+            .filter(|t| {
+                let [a, b, c] = [0, 1, 2].map(|i| {
+                    let v = d.vertices[t[i] as usize].position;
+                    Vector2(v[0], v[1])
+                });
+                let sides = [
+                    (b - a).cross(p - a),
+                    (c - b).cross(p - b),
+                    (a - c).cross(p - c),
+                ];
+                sides.iter().all(|&s| s > 0.0) || sides.iter().all(|&s| s < 0.0)
+            })
+            .count()
+    }
+
+    fn distance_to_line(line: &[Vector2], p: Vector2) -> f32 {
+        line.windows(2)
+            .map(|s| {
+                let t =
+                    ((p - s[0]).dot(s[1] - s[0]) / (s[1] - s[0]).dot(s[1] - s[0])).clamp(0.0, 1.0);
+                s[0].lerp(s[1], t).distance(p)
+            })
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    // Curves crossing themselves and turning sharply, and points all around them
+    fn tricky_curves() -> ([CubicBezier; 2], impl Iterator<Item = Vector2>) {
+        let curves = [
+            CubicBezier::new(
+                Vector2(0.0, 0.0),
+                Vector2(120.0, 100.0),
+                Vector2(-20.0, 100.0),
+                Vector2(100.0, 0.0),
+            ),
+            // A cusp
+            CubicBezier::new(
+                Vector2(0.0, 0.0),
+                Vector2(100.0, 100.0),
+                Vector2(0.0, 100.0),
+                Vector2(100.0, 0.0),
+            ),
+        ];
+        let points = (0..70 * 60)
+            .map(|i| Vector2((i % 70) as f32 * 2.0 - 10.0, (i / 70) as f32 * 2.0 - 10.0));
+        (curves, points)
+    }
+
+    #[test]
+    fn opaque_curves_cover_the_line() {
+        let (curves, points) = tricky_curves();
+        let points: Vec<Vector2> = points.collect();
+        for curve in curves {
+            let mut d = RendiumDrawHandle::new(PhysicalSize::new(200, 200), HashMap::new());
+            d.set_anti_aliasing(false);
+            d.draw_bezier_cubic(
+                curve.from,
+                curve.control1,
+                curve.control2,
+                curve.to,
+                8.0,
+                Color::WHITE,
+            );
+            let line = curve.flatten(0.01);
+            for &p in &points {
+                // The ends are cut off square
+                if p.distance(curve.from).min(p.distance(curve.to)) < 6.0 {
+                    continue;
+                }
+                // The drawn line can be a quarter of a pixel off the curve
+                let distance = distance_to_line(&line, p);
+                if distance < 3.6 {
+                    assert!(coverage(&d, p) >= 1, "{p:?} isn't drawn");
+                } else if distance > 4.4 {
+                    assert_eq!(coverage(&d, p), 0, "{p:?} is drawn");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn translucent_curves_dont_overlap_themselves() {
+        let (curves, points) = tricky_curves();
+        let points: Vec<Vector2> = points.collect();
+        for curve in curves {
+            let mut d = RendiumDrawHandle::new(PhysicalSize::new(200, 200), HashMap::new());
+            d.set_anti_aliasing(false);
+            let col = Color(255, 255, 255, 128);
+            d.draw_bezier_cubic(
+                curve.from,
+                curve.control1,
+                curve.control2,
+                curve.to,
+                8.0,
+                col,
+            );
+            assert!(points.iter().all(|&p| coverage(&d, p) <= 1));
+            assert!(points.iter().any(|&p| coverage(&d, p) == 1));
+        }
+    }
+
+    #[test]
+    fn split_agrees_with_point_at() {
+        let curve = s_curve();
+        for t in [0.25, 0.5, 0.7] {
+            let (first, second) = curve.split(t);
+            assert_vec2_eq(first.to, curve.point_at(t));
+            assert_vec2_eq(second.from, curve.point_at(t));
+            for s in [0.0, 0.3, 0.5, 0.9, 1.0] {
+                assert_vec2_eq(first.point_at(s), curve.point_at(s * t));
+                assert_vec2_eq(second.point_at(s), curve.point_at(t + s * (1.0 - t)));
+            }
+        }
+    }
+
+    #[test]
+    fn quad_is_the_same_curve_as_its_cubic() {
+        let quad = QuadBezier::new(Vector2(0.0, 0.0), Vector2(20.0, 40.0), Vector2(40.0, 0.0));
+        let cubic = CubicBezier::from(quad);
+        for t in [0.0, 0.2, 0.5, 0.8, 1.0] {
+            assert_vec2_eq(quad.point_at(t), cubic.point_at(t));
+            assert_vec2_eq(quad.tangent_at(t), cubic.tangent_at(t));
+        }
+    }
+
+    #[test]
+    fn straight_arc_length_is_the_chord() {
+        // The control points are on the line, out of order so the speed along it changes
+        let line = CubicBezier::new(
+            Vector2(0.0, 0.0),
+            Vector2(6.0, 8.0),
+            Vector2(1.5, 2.0),
+            Vector2(9.0, 12.0),
+        );
+        assert!((line.arc_length() - 15.0).abs() < EPSILON * 15.0);
+
+        let quad = QuadBezier::new(Vector2(1.0, 1.0), Vector2(2.0, 1.0), Vector2(5.0, 1.0));
+        assert!((quad.arc_length() - 4.0).abs() < EPSILON * 4.0);
+    }
+
+    #[test]
+    fn flatten_stays_within_tolerance() {
+        let curve = s_curve();
+        let points = curve.flatten(0.1);
+        assert_eq!(points.first(), Some(&curve.from));
+        assert_eq!(points.last(), Some(&curve.to));
+        // Every piece's middle is close to the curve somewhere
+        let samples: Vec<Vector2> = (0..=2000)
+            .map(|i| curve.point_at(i as f32 / 2000.0))
+            .collect();
+        for piece in points.windows(2) {
+            let mid = piece[0].lerp(piece[1], 0.5);
+            let closest = samples
+                .iter()
+                .map(|p| p.distance(mid))
+                .fold(f32::INFINITY, f32::min);
+            assert!(closest <= 0.1 + 0.05, "{mid:?} is {closest} away");
+        }
+    }
+
+    #[test]
+    fn catmull_rom_goes_through_every_point() {
+        let points = [
+            Vector2(0.0, 0.0),
+            Vector2(10.0, 20.0),
+            Vector2(30.0, 5.0),
+            Vector2(40.0, 40.0),
+        ];
+        let curves = catmull_rom(&points);
+        assert_eq!(curves.len(), 3);
+        for (curve, pair) in curves.iter().zip(points.windows(2)) {
+            assert_eq!(curve.from, pair[0]);
+            assert_eq!(curve.to, pair[1]);
+        }
+        // Smooth where the curves meet
+        let (a, b) = (curves[0].tangent_at(1.0), curves[1].tangent_at(0.0));
+        assert_vec2_eq(a, b);
+    }
+
+    #[test]
+    fn b_spline_goes_through_its_end_points() {
+        let points = [
+            Vector2(0.0, 0.0),
+            Vector2(10.0, 20.0),
+            Vector2(30.0, 5.0),
+            Vector2(40.0, 40.0),
+        ];
+        let curves = b_spline(&points);
+        assert_vec2_eq(curves.first().unwrap().from, points[0]);
+        assert_vec2_eq(curves.last().unwrap().to, points[3]);
+        for pair in curves.windows(2) {
+            assert_vec2_eq(pair[0].to, pair[1].from);
+        }
+        // The middle points only pull the curve towards them
+        assert!(curves.iter().all(|c| !c.to.approx_eq(points[1], 1.0)));
+
+        assert!(b_spline(&[]).is_empty());
+        let single = b_spline(&[Vector2(3.0, 4.0)]);
+        assert!(
+            single
+                .iter()
+                .all(|c| c.from == Vector2(3.0, 4.0) && c.to == Vector2(3.0, 4.0))
+        );
+    }
+
+    #[test]
+    fn t_at_length_goes_at_a_steady_speed() {
+        let curve = s_curve();
+        let length = curve.arc_length();
+        for fraction in [0.1, 0.25, 0.5, 0.8] {
+            let travelled = curve
+                .split(curve.t_at_length(length * fraction))
+                .0
+                .arc_length();
+            assert!((travelled - length * fraction).abs() < length * 1e-4);
+        }
+        assert_eq!(curve.t_at_length(-1.0), 0.0);
+        assert_eq!(curve.t_at_length(length * 2.0), 1.0);
+
+        // A straight line whose speed along it changes
+        let line = CubicBezier::new(
+            Vector2(0.0, 0.0),
+            Vector2(6.0, 8.0),
+            Vector2(1.5, 2.0),
+            Vector2(9.0, 12.0),
+        );
+        assert_vec2_eq(line.point_at_length(5.0), Vector2(3.0, 4.0));
+        let quad = QuadBezier::new(Vector2(1.0, 1.0), Vector2(2.0, 1.0), Vector2(5.0, 1.0));
+        assert_vec2_eq(quad.point_at_length(3.0), Vector2(4.0, 1.0));
+    }
+}
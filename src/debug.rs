@@ -0,0 +1,296 @@
+// This module has the built-in debug overlay
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::RendiumDrawHandle;
+use crate::input::{Key, RendiumInput};
+use crate::shapes::DrawShape;
+use crate::types::{Color, Vector2};
+
+// How many frames the frame time graph shows
+const FRAME_HISTORY: usize = 120;
+
+const TEXT_SCALE: i32 = 2;
+const CHAR_WIDTH: i32 = 4 * TEXT_SCALE;
+const LINE_HEIGHT: i32 = 7 * TEXT_SCALE;
+const PADDING: i32 = 8;
+const GRAPH_HEIGHT: i32 = 48;
+const MAX_TEXTURES_LISTED: usize = 8;
+
+const BACKGROUND: Color = Color(0, 0, 0, 180);
+const TEXT_COLOR: Color = Color::WHITE;
+const GRAPH_COLOR: Color = Color(80, 220, 120, 255);
+const SLOW_FRAME_COLOR: Color = Color(230, 80, 60, 255);
+const TARGET_LINE_COLOR: Color = Color(255, 255, 255, 90);
+
+// 60 FPS budget, frames above it are drawn in red
+const TARGET_FRAME_MS: f32 = 1000.0 / 60.0;
+// The top of the graph
+const GRAPH_MAX_MS: f32 = 2.0 * TARGET_FRAME_MS;
+
+// Numbers from the last rendered frame
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameStats {
+    pub draw_calls: u32,
+    pub vertices: u32,
+    pub indices: u32,
+    pub sprites: u32,
+}
+
+pub struct DebugOverlay {
+    enabled: bool,
+    toggle_key: Key,
+    frame_times: VecDeque<Duration>,
+}
+
+impl Default for DebugOverlay {
+    fn default() -> Self {
+        Self::new(Key::BackTick)
+    }
+}
+
+impl DebugOverlay {
+    pub fn new(toggle_key: Key) -> Self {
+        Self {
+            enabled: false,
+            toggle_key,
+            frame_times: VecDeque::with_capacity(FRAME_HISTORY),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn toggle_key(&self) -> Key {
+        self.toggle_key.clone()
+    }
+
+    pub fn set_toggle_key(&mut self, key: Key) {
+        self.toggle_key = key;
+    }
+
+    // Called once per frame, before the user's callback
+    pub fn update(&mut self, input: &RendiumInput, delta_time: Duration) {
+        if input.is_key_pressed(self.toggle_key.clone()) {
+            self.enabled = !self.enabled;
+        }
+
+        if self.frame_times.len() == FRAME_HISTORY {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(delta_time);
+    }
+
+    pub fn fps(&self) -> f32 {
+        let total: Duration = self.frame_times.iter().sum();
+        if total.is_zero() {
+            return 0.0;
+        }
+        self.frame_times.len() as f32 / total.as_secs_f32()
+    }
+
+    // The overlay gets its own draw handle, so none of this touches the user's geometry
+    pub(crate) fn draw(
+        &self,
+        d: &mut RendiumDrawHandle,
+        stats: FrameStats,
+        input: &RendiumInput,
+        scale_factor: f64,
+    ) {
+        let frame_ms = self
+            .frame_times
+            .back()
+            .map(|t| t.as_secs_f32() * 1000.0)
+            .unwrap_or(0.0);
+
+        let mut textures: Vec<_> = d
+            .textures
+            .iter()
+            .map(|(name, tex)| (name.clone(), tex.width(), tex.height(), tex.memory_size()))
+            .collect();
+        textures.sort();
+        let texture_memory: u64 = textures.iter().map(|(_, _, _, mem)| mem).sum();
+
+        let mouse = input.get_mouse_pos();
+        let mut keys: Vec<String> = input
+            .get_keys_down()
+            .iter()
+            .map(|k| format!("{:?}", k))
+            .collect();
+        keys.sort();
+
+        let header = vec![format!("FPS: {:.0} ({:.2} MS)", self.fps(), frame_ms)];
+        let mut lines = vec![
+            format!("DRAW CALLS: {}", stats.draw_calls),
+            format!("VERTICES: {}  INDICES: {}", stats.vertices, stats.indices),
+            format!("SPRITES: {}", stats.sprites),
+            format!(
+                "WINDOW: {}X{}  SCALE: {:.2}",
+                d.window_size.width, d.window_size.height, scale_factor
+            ),
+            format!(
+                "TEXTURES: {} ({})",
+                textures.len(),
+                format_bytes(texture_memory)
+            ),
+        ];
+        for (name, w, h, mem) in textures.iter().take(MAX_TEXTURES_LISTED) {
+            lines.push(format!("  {} {}X{} {}", name, w, h, format_bytes(*mem)));
+        }
+        if textures.len() > MAX_TEXTURES_LISTED {
+            lines.push(format!(
+                "  ... {} MORE",
+                textures.len() - MAX_TEXTURES_LISTED
+            ));
+        }
+        lines.push(format!("MOUSE: {:.0}, {:.0}", mouse.0, mouse.1));
+        lines.push(format!("KEYS: {}", keys.join(" ")));
+
+        let graph_width = FRAME_HISTORY as i32 * 2;
+        let text_width = header
+            .iter()
+            .chain(lines.iter())
+            .map(|l| l.chars().count() as i32 * CHAR_WIDTH)
+            .max()
+            .unwrap_or(0);
+        let width = text_width.max(graph_width) + PADDING * 2;
+        let height = (header.len() + lines.len()) as i32 * LINE_HEIGHT + GRAPH_HEIGHT + PADDING * 3;
+
+        let origin = Vector2(PADDING as f32, PADDING as f32);
+        d.draw_rect(origin, width, height, BACKGROUND);
+
+        let mut y = origin.1 + PADDING as f32;
+        let x = origin.0 + PADDING as f32;
+        for line in &header {
+            draw_text(d, line, Vector2(x, y), TEXT_COLOR);
+            y += LINE_HEIGHT as f32;
+        }
+
+        self.draw_graph(d, Vector2(x, y));
+        y += (GRAPH_HEIGHT + PADDING) as f32;
+
+        for line in &lines {
+            draw_text(d, line, Vector2(x, y), TEXT_COLOR);
+            y += LINE_HEIGHT as f32;
+        }
+    }
+
+    fn draw_graph(&self, d: &mut RendiumDrawHandle, pos: Vector2) {
+        let bottom = pos.1 + GRAPH_HEIGHT as f32;
+
+        for (i, time) in self.frame_times.iter().enumerate() {
+            let ms = time.as_secs_f32() * 1000.0;
+            let bar = ((ms / GRAPH_MAX_MS).min(1.0) * GRAPH_HEIGHT as f32).max(1.0);
+            let col = if ms > TARGET_FRAME_MS {
+                SLOW_FRAME_COLOR
+            } else {
+                GRAPH_COLOR
+            };
+            d.draw_rect(
+                Vector2(pos.0 + i as f32 * 2.0, bottom - bar),
+                2,
+                bar as i32,
+                col,
+            );
+        }
+
+        let target_y = bottom - TARGET_FRAME_MS / GRAPH_MAX_MS * GRAPH_HEIGHT as f32;
+        d.draw_rect(
+            Vector2(pos.0, target_y),
+            FRAME_HISTORY as i32 * 2,
+            1,
+            TARGET_LINE_COLOR,
+        );
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f32 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} KB", bytes as f32 / 1024.0)
+    }
+}
+
+fn draw_text(d: &mut RendiumDrawHandle, text: &str, pos: Vector2, col: Color) {
+    for (i, c) in text.chars().enumerate() {
+        let bits = glyph(c);
+        let cx = pos.0 + (i as i32 * CHAR_WIDTH) as f32;
+
+        for row in 0..5 {
+            for column in 0..3 {
+                if bits & (1 << (14 - (row * 3 + column))) != 0 {
+                    d.draw_rect(
+                        Vector2(
+                            cx + (column * TEXT_SCALE) as f32,
+                            pos.1 + (row * TEXT_SCALE) as f32,
+                        ),
+                        TEXT_SCALE,
+                        TEXT_SCALE,
+                        col,
+                    );
+                }
+            }
+        }
+    }
+}
+
+// A tiny 3x5 pixel font, one bit per pixel, row by row from the top left
+fn glyph(c: char) -> u16 {
+    match c.to_ascii_uppercase() {
+        ' ' => 0,
+        '0' => 0b111_101_101_101_111,
+        '1' => 0b010_110_010_010_111,
+        '2' => 0b111_001_111_100_111,
+        '3' => 0b111_001_111_001_111,
+        '4' => 0b101_101_111_001_001,
+        '5' => 0b111_100_111_001_111,
+        '6' => 0b111_100_111_101_111,
+        '7' => 0b111_001_001_001_001,
+        '8' => 0b111_101_111_101_111,
+        '9' => 0b111_101_111_001_111,
+        'A' => 0b010_101_111_101_101,
+        'B' => 0b110_101_110_101_110,
+        'C' => 0b011_100_100_100_011,
+        'D' => 0b110_101_101_101_110,
+        'E' => 0b111_100_110_100_111,
+        'F' => 0b111_100_110_100_100,
+        'G' => 0b011_100_101_101_011,
+        'H' => 0b101_101_111_101_101,
+        'I' => 0b111_010_010_010_111,
+        'J' => 0b001_001_001_101_010,
+        'K' => 0b101_101_110_101_101,
+        'L' => 0b100_100_100_100_111,
+        'M' => 0b101_111_111_101_101,
+        'N' => 0b110_101_101_101_101,
+        'O' => 0b010_101_101_101_010,
+        'P' => 0b110_101_110_100_100,
+        'Q' => 0b010_101_101_110_011,
+        'R' => 0b110_101_110_101_101,
+        'S' => 0b011_100_010_001_110,
+        'T' => 0b111_010_010_010_010,
+        'U' => 0b101_101_101_101_111,
+        'V' => 0b101_101_101_101_010,
+        'W' => 0b101_101_111_111_101,
+        'X' => 0b101_101_010_101_101,
+        'Y' => 0b101_101_010_010_010,
+        'Z' => 0b111_001_010_100_111,
+        ':' => 0b000_010_000_010_000,
+        '.' => 0b000_000_000_000_010,
+        ',' => 0b000_000_000_010_100,
+        '-' => 0b000_000_111_000_000,
+        '_' => 0b000_000_000_000_111,
+        '=' => 0b000_111_000_111_000,
+        '/' => 0b001_001_010_100_100,
+        '(' => 0b010_100_100_100_010,
+        ')' => 0b010_001_001_001_010,
+        '[' => 0b110_100_100_100_110,
+        ']' => 0b011_001_001_001_011,
+        _ => 0b111_001_010_000_010,
+    }
+}
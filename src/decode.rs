@@ -0,0 +1,967 @@
+// Turns image files into RGBA pixels. PNG is always there, the other formats each have a feature,
+// so programs only build the decoders they use. The format comes from the first bytes of the file,
+// never from its name.
+use std::io::Cursor;
+
+use anyhow::{Context, bail};
+
+use crate::texture::Texture;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Bmp,
+    Tga,
+    // Only the first frame is loaded
+    Gif,
+    Qoi,
+    WebP,
+}
+
+impl ImageFormat {
+    // TGA files have no magic number, so they're recognised by their footer, or failing that
+    // by a header that makes sense
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        const MAGIC: [(&[u8], ImageFormat); 7] = [
+            (b"\x89PNG\r\n\x1a\n", ImageFormat::Png),
+            (b"\xff\xd8\xff", ImageFormat::Jpeg),
+            (b"GIF87a", ImageFormat::Gif),
+            (b"GIF89a", ImageFormat::Gif),
+            (b"qoif", ImageFormat::Qoi),
+            (b"BM", ImageFormat::Bmp),
+            (b"RIFF", ImageFormat::WebP),
+        ];
+        let format = MAGIC
+            .iter()
+            .find(|(magic, _)| bytes.starts_with(magic))
+            .map(|&(_, format)| format);
+        match format {
+            Some(ImageFormat::WebP) if bytes.get(8..12) != Some(b"WEBP") => None,
+            Some(format) => Some(format),
+            None => looks_like_tga(bytes).then_some(ImageFormat::Tga),
+        }
+    }
+
+    // The cargo feature that turns the format on
+    fn feature(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "",
+            ImageFormat::Jpeg => "jpeg",
+            ImageFormat::Bmp => "bmp",
+            ImageFormat::Tga => "tga",
+            ImageFormat::Gif => "gif",
+            ImageFormat::Qoi => "qoi",
+            ImageFormat::WebP => "webp",
+        }
+    }
+}
+
+pub(crate) fn decode(bytes: &[u8]) -> anyhow::Result<Texture> {
+    let format = ImageFormat::detect(bytes).context("Unknown image format")?;
+    let texture = match format {
+        ImageFormat::Png => decode_png(bytes)?,
+        #[cfg(feature = "jpeg")]
+        ImageFormat::Jpeg => decode_jpeg(bytes)?,
+        #[cfg(feature = "bmp")]
+        ImageFormat::Bmp => decode_bmp(bytes)?,
+        #[cfg(feature = "tga")]
+        ImageFormat::Tga => decode_tga(bytes)?,
+        #[cfg(feature = "gif")]
+        ImageFormat::Gif => decode_gif(bytes)?,
+        #[cfg(feature = "qoi")]
+        ImageFormat::Qoi => decode_qoi(bytes)?,
+        #[cfg(feature = "webp")]
+        ImageFormat::WebP => decode_webp(bytes)?,
+        #[allow(unreachable_patterns)]
+        _ => bail!(
+            "Loading {format:?} images needs the {:?} feature",
+            format.feature()
+        ),
+    };
+
+    if texture.width == 0 || texture.height == 0 {
+        bail!("{format:?} image is empty");
+    }
+    if texture.data.len() != texture.width as usize * texture.height as usize * 4 {
+        bail!("{format:?} image decoded to the wrong number of bytes");
+    }
+    Ok(texture)
+}
+
+fn decode_png(bytes: &[u8]) -> anyhow::Result<Texture> {
+    use png::ColorType;
+
+    // Palettes and low bit depths expand to 8 bits, 16 bits get cut down to 8
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info()?;
+
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels)?;
+    pixels.truncate(info.buffer_size());
+
+    let data = match info.color_type {
+        ColorType::Grayscale => pixels.iter().flat_map(|&l| [l, l, l, 255]).collect(),
+        ColorType::GrayscaleAlpha => pixels
+            .chunks_exact(2)
+            .flat_map(|la| [la[0], la[0], la[0], la[1]])
+            .collect(),
+        ColorType::Rgb => pixels
+            .chunks_exact(3)
+            .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+            .collect(),
+        ColorType::Rgba => pixels,
+        ColorType::Indexed => bail!("PNG palette wasn't expanded"),
+    };
+
+    Ok(Texture {
+        data,
+        width: info.width,
+        height: info.height,
+    })
+}
+
+#[cfg(feature = "jpeg")]
+fn decode_jpeg(bytes: &[u8]) -> anyhow::Result<Texture> {
+    use jpeg_decoder::PixelFormat;
+
+    let mut decoder = jpeg_decoder::Decoder::new(Cursor::new(bytes));
+    let pixels = decoder.decode()?;
+    let info = decoder.info().context("JPEG has no image")?;
+
+    let data = match info.pixel_format {
+        PixelFormat::L8 => pixels.iter().flat_map(|&l| [l, l, l, 255]).collect(),
+        // Big endian, the high byte is close enough
+        PixelFormat::L16 => pixels
+            .chunks_exact(2)
+            .flat_map(|l| [l[0], l[0], l[0], 255])
+            .collect(),
+        PixelFormat::RGB24 => pixels
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        PixelFormat::CMYK32 => pixels
+            .chunks_exact(4)
+            .flat_map(|p| {
+                let k = 255 - p[3] as u32;
+                let channel = |c: u8| ((255 - c as u32) * k / 255) as u8;
+                [channel(p[0]), channel(p[1]), channel(p[2]), 255]
+            })
+            .collect(),
+    };
+
+    Ok(Texture {
+        data,
+        width: info.width as u32,
+        height: info.height as u32,
+    })
+}
+
+#[cfg(feature = "gif")]
+fn decode_gif(bytes: &[u8]) -> anyhow::Result<Texture> {
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::RGBA);
+    let mut decoder = options.read_info(bytes)?;
+    let (width, height) = (decoder.width() as u32, decoder.height() as u32);
+    let frame = decoder.read_next_frame()?.context("GIF has no frames")?;
+
+    let frame_row = frame.width as usize * 4;
+    if frame_row == 0 {
+        bail!("GIF frame is empty");
+    }
+    // The screen size is only two numbers in the header, while the frame's pixels were really
+    // decoded. Up to where the frame reaches, or a size no real GIF goes past, it's trusted,
+    // so a bad size can't ask for gigabytes.
+    const MAX_PIXELS: usize = 1 << 24;
+    let reach =
+        (frame.left as usize + frame.width as usize) * (frame.top as usize + frame.height as usize);
+    if width as usize * height as usize > reach.max(MAX_PIXELS) {
+        bail!(
+            "GIF is {width}x{height}, too big for a first frame of {}x{}",
+            frame.width,
+            frame.height
+        );
+    }
+
+    // The first frame can be smaller than the image, the rest is transparent
+    let mut data = vec![0; width as usize * height as usize * 4];
+    for (y, row) in frame.buffer.chunks_exact(frame_row).enumerate() {
+        let y = frame.top as usize + y;
+        if y >= height as usize || frame.left as u32 >= width {
+            continue;
+        }
+        let start = (y * width as usize + frame.left as usize) * 4;
+        let len = frame_row.min((width as usize - frame.left as usize) * 4);
+        data[start..start + len].copy_from_slice(&row[..len]);
+    }
+
+    Ok(Texture {
+        data,
+        width,
+        height,
+    })
+}
+
+#[cfg(feature = "qoi")]
+fn decode_qoi(bytes: &[u8]) -> anyhow::Result<Texture> {
+    let mut decoder = qoi::Decoder::new(bytes)?.with_channels(qoi::Channels::Rgba);
+    let data = decoder.decode_to_vec()?;
+    let header = decoder.header();
+    Ok(Texture {
+        data,
+        width: header.width,
+        height: header.height,
+    })
+}
+
+#[cfg(feature = "webp")]
+fn decode_webp(bytes: &[u8]) -> anyhow::Result<Texture> {
+    let mut decoder = image_webp::WebPDecoder::new(Cursor::new(bytes))?;
+    let (width, height) = decoder.dimensions();
+    let size = decoder
+        .output_buffer_size()
+        .context("WebP image is too big")?;
+    let mut pixels = vec![0; size];
+    decoder.read_image(&mut pixels)?;
+
+    let data = if decoder.has_alpha() {
+        pixels
+    } else {
+        pixels
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect()
+    };
+    Ok(Texture {
+        data,
+        width,
+        height,
+    })
+}
+
+// Reads little endian numbers, failing on a file that's cut short
+#[cfg(any(feature = "bmp", feature = "tga"))]
+struct Bytes<'a>(&'a [u8]);
+
+#[cfg(any(feature = "bmp", feature = "tga"))]
+impl Bytes<'_> {
+    fn u8(&self, at: usize) -> anyhow::Result<u8> {
+        self.0.get(at).copied().context("Image file is cut short")
+    }
+
+    fn u16(&self, at: usize) -> anyhow::Result<u16> {
+        Ok(u16::from_le_bytes([self.u8(at)?, self.u8(at + 1)?]))
+    }
+
+    #[cfg(feature = "bmp")]
+    fn u32(&self, at: usize) -> anyhow::Result<u32> {
+        Ok(u32::from_le_bytes([
+            self.u8(at)?,
+            self.u8(at + 1)?,
+            self.u8(at + 2)?,
+            self.u8(at + 3)?,
+        ]))
+    }
+
+    fn slice(&self, at: usize, len: usize) -> anyhow::Result<&[u8]> {
+        self.0
+            .get(at..at.saturating_add(len))
+            .context("Image file is cut short")
+    }
+}
+
+// A channel under a bit mask, scaled to 8 bits
+#[cfg(feature = "bmp")]
+fn masked(value: u32, mask: u32) -> Option<u8> {
+    if mask == 0 {
+        return None;
+    }
+    let max = (mask >> mask.trailing_zeros()) as u64;
+    let v = ((value & mask) >> mask.trailing_zeros()) as u64;
+    Some(((v * 255 + max / 2) / max) as u8)
+}
+
+// Uncompressed BMPs of any depth, with palettes or bit masks
+#[cfg(feature = "bmp")]
+fn decode_bmp(bytes: &[u8]) -> anyhow::Result<Texture> {
+    const BI_RGB: u32 = 0;
+    const BI_BITFIELDS: u32 = 3;
+    const BI_ALPHABITFIELDS: u32 = 6;
+
+    let b = Bytes(bytes);
+    let data_offset = b.u32(10)? as usize;
+    let header_size = b.u32(14)? as usize;
+
+    // The old OS/2 header has 16 bit sizes and 3 byte palette entries
+    let (width, height, bpp, compression, palette_len, entry_size) = if header_size == 12 {
+        let bpp = b.u16(24)?;
+        (
+            b.u16(18)? as i64,
+            b.u16(20)? as i16 as i64,
+            bpp,
+            BI_RGB,
+            0,
+            3,
+        )
+    } else if header_size >= 40 {
+        let colors_used = b.u32(46)? as usize;
+        let (w, h) = (b.u32(18)? as i32 as i64, b.u32(22)? as i32 as i64);
+        (w, h, b.u16(28)?, b.u32(30)?, colors_used, 4)
+    } else {
+        bail!("Unsupported BMP header size {header_size}");
+    };
+    let top_down = height < 0;
+    let (width, height) = (width, height.abs());
+    if width <= 0 || height == 0 || width > 1 << 16 || height > 1 << 16 {
+        bail!("Unsupported BMP size {width}x{height}");
+    }
+    let (width, height) = (width as usize, height as usize);
+    let stride = (width * bpp as usize).div_ceil(32) * 4;
+    // Before making room for the pixels, so a bad size can't ask for gigabytes
+    b.slice(data_offset, stride * height)?;
+
+    let (masks, masks_len) = match compression {
+        BI_RGB => match bpp {
+            16 => ([0x7c00, 0x03e0, 0x001f, 0], 0),
+            _ => ([0x00ff_0000, 0x0000_ff00, 0x0000_00ff, 0], 0),
+        },
+        BI_BITFIELDS | BI_ALPHABITFIELDS => {
+            let at = 14 + 40;
+            let alpha = if compression == BI_ALPHABITFIELDS || header_size >= 56 {
+                b.u32(at + 12)?
+            } else {
+                0
+            };
+            let masks = [b.u32(at)?, b.u32(at + 4)?, b.u32(at + 8)?, alpha];
+            // Version 1 headers have the masks after them, later ones inside
+            let len = match (header_size, compression) {
+                (40, BI_ALPHABITFIELDS) => 16,
+                (40, _) => 12,
+                _ => 0,
+            };
+            (masks, len)
+        }
+        _ => bail!("Compressed BMPs aren't supported"),
+    };
+
+    let palette = if bpp <= 8 {
+        let len = if palette_len == 0 {
+            1 << bpp
+        } else {
+            palette_len
+        };
+        let raw = b.slice(14 + header_size + masks_len, len.min(256) * entry_size)?;
+        raw.chunks_exact(entry_size)
+            .map(|e| [e[2], e[1], e[0], 255])
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let mut data = Vec::with_capacity(width * height * 4);
+    let mut any_alpha = false;
+    for y in 0..height {
+        let row = if top_down { y } else { height - 1 - y };
+        let row = b.slice(data_offset + row * stride, stride)?;
+        for x in 0..width {
+            let pixel = match bpp {
+                1 | 2 | 4 | 8 => {
+                    let bit = x * bpp as usize;
+                    let shift = 8 - bpp as usize - bit % 8;
+                    let index = (row[bit / 8] >> shift) & ((1 << bpp) - 1) as u8;
+                    *palette
+                        .get(index as usize)
+                        .context("BMP palette index is out of range")?
+                }
+                16 | 24 | 32 => {
+                    let size = bpp as usize / 8;
+                    let mut value = [0; 4];
+                    value[..size].copy_from_slice(&row[x * size..(x + 1) * size]);
+                    let value = u32::from_le_bytes(value);
+                    let channel = |i: usize| masked(value, masks[i]).unwrap_or(0);
+                    let alpha = masked(value, masks[3]);
+                    any_alpha |= alpha.is_some_and(|a| a > 0);
+                    [channel(0), channel(1), channel(2), alpha.unwrap_or(255)]
+                }
+                _ => bail!("Unsupported BMP bit depth {bpp}"),
+            };
+            data.extend_from_slice(&pixel);
+        }
+    }
+    // An alpha mask with nothing in it is a file that doesn't really use alpha
+    if masks[3] != 0 && !any_alpha {
+        data.chunks_exact_mut(4).for_each(|p| p[3] = 255);
+    }
+
+    Ok(Texture {
+        data,
+        width: width as u32,
+        height: height as u32,
+    })
+}
+
+fn looks_like_tga(bytes: &[u8]) -> bool {
+    const FOOTER: &[u8] = b"TRUEVISION-XFILE.\0";
+    if bytes.ends_with(FOOTER) {
+        return true;
+    }
+    let Some(header) = bytes.get(..18) else {
+        return false;
+    };
+    let (color_map, kind) = (header[1], header[2]);
+    let width = u16::from_le_bytes([header[12], header[13]]);
+    let height = u16::from_le_bytes([header[14], header[15]]);
+    let depth = header[16];
+    color_map <= 1
+        && matches!(kind, 1 | 2 | 3 | 9 | 10 | 11)
+        && matches!(depth, 8 | 15 | 16 | 24 | 32)
+        && width > 0
+        && height > 0
+}
+
+// TGAs of any kind: true color, grayscale or with a color map, plain or run length encoded
+#[cfg(feature = "tga")]
+fn decode_tga(bytes: &[u8]) -> anyhow::Result<Texture> {
+    let b = Bytes(bytes);
+    let id_len = b.u8(0)? as usize;
+    let has_map = b.u8(1)? == 1;
+    let kind = b.u8(2)?;
+    let (map_first, map_len, map_depth) = (b.u16(3)? as usize, b.u16(5)? as usize, b.u8(7)?);
+    let (width, height) = (b.u16(12)? as usize, b.u16(14)? as usize);
+    let depth = b.u8(16)?;
+    let descriptor = b.u8(17)?;
+    let alpha_bits = descriptor & 0x0f;
+    let right_to_left = descriptor & 0x10 != 0;
+    let top_down = descriptor & 0x20 != 0;
+
+    let rle = kind & 8 != 0;
+    let gray = kind & 3 == 3;
+    let mapped = kind & 3 == 1;
+    if !matches!(kind & !8, 1..=3) || (mapped && !has_map) {
+        bail!("Unsupported TGA image type {kind}");
+    }
+    if !matches!(depth, 8 | 15 | 16 | 24 | 32) {
+        bail!("Unsupported TGA bit depth {depth}");
+    }
+    if has_map && !matches!(map_depth, 15 | 16 | 24 | 32) {
+        bail!("Unsupported TGA color map depth {map_depth}");
+    }
+
+    // One pixel's bytes to a color, for both pixels and color map entries
+    let color = |p: &[u8], depth: u8| -> anyhow::Result<[u8; 4]> {
+        Ok(match (depth, gray && !mapped) {
+            (8, true) => [p[0], p[0], p[0], 255],
+            (16, true) => [p[0], p[0], p[0], p[1]],
+            (15 | 16, false) => {
+                let v = u16::from_le_bytes([p[0], p[1]]);
+                let five = |shift: u16| (((v >> shift) & 31) as u32 * 255 / 31) as u8;
+                let alpha = if depth == 16 && alpha_bits > 0 && v & 0x8000 == 0 {
+                    0
+                } else {
+                    255
+                };
+                [five(10), five(5), five(0), alpha]
+            }
+            (24, false) => [p[2], p[1], p[0], 255],
+            (32, false) => [p[2], p[1], p[0], if alpha_bits > 0 { p[3] } else { 255 }],
+            _ => bail!("Unsupported TGA bit depth {depth}"),
+        })
+    };
+
+    let map_start = 18 + id_len;
+    let map_entry = (map_depth as usize).div_ceil(8);
+    let color_map: Vec<[u8; 4]> = if has_map {
+        b.slice(map_start, map_len * map_entry)?
+            .chunks_exact(map_entry)
+            .map(|e| color(e, map_depth))
+            .collect::<anyhow::Result<_>>()?
+    } else {
+        Vec::new()
+    };
+
+    let pixel_size = (depth as usize).div_ceil(8);
+    let to_color = |p: &[u8]| -> anyhow::Result<[u8; 4]> {
+        if mapped {
+            let index = if pixel_size == 1 {
+                p[0] as usize
+            } else {
+                u16::from_le_bytes([p[0], p[1]]) as usize
+            };
+            index
+                .checked_sub(map_first)
+                .and_then(|i| color_map.get(i))
+                .copied()
+                .context("TGA color map index is out of range")
+        } else {
+            color(p, depth)
+        }
+    };
+
+    // The pixels in file order, which goes along rows starting at the bottom left by default
+    let count = width * height;
+    // Run length encoding can't make more than 128 pixels from 2 bytes
+    let mut pixels = Vec::with_capacity(count.min(bytes.len() * 64));
+    let mut at = map_start + if has_map { map_len * map_entry } else { 0 };
+    while pixels.len() < count {
+        if rle {
+            let packet = b.u8(at)?;
+            at += 1;
+            let n = (packet & 0x7f) as usize + 1;
+            if packet & 0x80 != 0 {
+                let col = to_color(b.slice(at, pixel_size)?)?;
+                pixels.extend(std::iter::repeat_n(col, n));
+                at += pixel_size;
+            } else {
+                for p in b.slice(at, n * pixel_size)?.chunks_exact(pixel_size) {
+                    pixels.push(to_color(p)?);
+                }
+                at += n * pixel_size;
+            }
+        } else {
+            let raw = b.slice(at, count * pixel_size)?;
+            for p in raw.chunks_exact(pixel_size) {
+                pixels.push(to_color(p)?);
+            }
+        }
+    }
+    pixels.truncate(count);
+
+    let mut data = Vec::with_capacity(count * 4);
+    for y in 0..height {
+        let row = if top_down { y } else { height - 1 - y };
+        for x in 0..width {
+            let column = if right_to_left { width - 1 - x } else { x };
+            data.extend_from_slice(&pixels[row * width + column]);
+        }
+    }
+
+    Ok(Texture {
+        data,
+        width: width as u32,
+        height: height as u32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(values: &[u8]) -> Vec<u8> {
+        values.iter().flat_map(|&l| [l, l, l, 255]).collect()
+    }
+
+    // All 3x2, so rows of low bit depths end partway through a byte
+    #[test]
+    fn png_decodes_every_color_type_and_depth() {
+        const RED: [u8; 4] = [255, 0, 0, 255];
+        const GREEN: [u8; 4] = [0, 255, 0, 255];
+        const BLUE: [u8; 4] = [0, 0, 255, 255];
+        const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+        let fixtures: [(&str, &[u8], Vec<u8>); 12] = [
+            (
+                "gray1",
+                include_bytes!("../tests/fixtures/png/gray1.png"),
+                gray(&[255, 0, 255, 0, 255, 0]),
+            ),
+            (
+                "gray2",
+                include_bytes!("../tests/fixtures/png/gray2.png"),
+                gray(&[0, 85, 170, 255, 170, 85]),
+            ),
+            (
+                "gray4",
+                include_bytes!("../tests/fixtures/png/gray4.png"),
+                gray(&[0, 85, 170, 255, 17, 136]),
+            ),
+            (
+                "gray8",
+                include_bytes!("../tests/fixtures/png/gray8.png"),
+                gray(&[0, 100, 200, 255, 1, 128]),
+            ),
+            // 16 bits keep their high byte
+            (
+                "gray16",
+                include_bytes!("../tests/fixtures/png/gray16.png"),
+                gray(&[0x00, 0x12, 0xab, 0xff, 0x01, 0x80]),
+            ),
+            (
+                "gray_alpha8",
+                include_bytes!("../tests/fixtures/png/gray_alpha8.png"),
+                [
+                    [10, 10, 10, 20],
+                    [30, 30, 30, 40],
+                    [50, 50, 50, 60],
+                    [255, 255, 255, 0],
+                    [0, 0, 0, 255],
+                    [128, 128, 128, 128],
+                ]
+                .concat(),
+            ),
+            (
+                "gray_alpha16",
+                include_bytes!("../tests/fixtures/png/gray_alpha16.png"),
+                [
+                    [0x0a, 0x0a, 0x0a, 0x14],
+                    [0x1e, 0x1e, 0x1e, 0x28],
+                    [0x32, 0x32, 0x32, 0x3c],
+                    [0xff, 0xff, 0xff, 0x00],
+                    [0x00, 0x00, 0x00, 0xff],
+                    [0x80, 0x80, 0x80, 0x7f],
+                ]
+                .concat(),
+            ),
+            (
+                "rgb8",
+                include_bytes!("../tests/fixtures/png/rgb8.png"),
+                [RED, GREEN, BLUE, [1, 2, 3, 255], [250, 128, 7, 255], WHITE].concat(),
+            ),
+            (
+                "rgb16",
+                include_bytes!("../tests/fixtures/png/rgb16.png"),
+                [
+                    RED,
+                    [0x12, 0x56, 0x9a, 255],
+                    [0xde, 0x0f, 0xf0, 255],
+                    [1, 2, 3, 255],
+                    [0xfa, 0x80, 0x07, 255],
+                    WHITE,
+                ]
+                .concat(),
+            ),
+            (
+                "rgba16",
+                include_bytes!("../tests/fixtures/png/rgba16.png"),
+                [
+                    RED,
+                    [0, 255, 0, 0x80],
+                    [0, 0, 255, 0],
+                    [1, 3, 5, 7],
+                    [0xfa, 0x80, 0x07, 0x40],
+                    WHITE,
+                ]
+                .concat(),
+            ),
+            // 2 bits per index
+            (
+                "palette",
+                include_bytes!("../tests/fixtures/png/palette.png"),
+                [RED, GREEN, BLUE, WHITE, RED, GREEN].concat(),
+            ),
+            // Transparency for the first 2 entries only, the rest stay opaque
+            (
+                "palette_trns",
+                include_bytes!("../tests/fixtures/png/palette_trns.png"),
+                [
+                    [255, 0, 0, 0],
+                    [0, 255, 0, 128],
+                    BLUE,
+                    WHITE,
+                    [255, 0, 0, 0],
+                    [0, 255, 0, 128],
+                ]
+                .concat(),
+            ),
+        ];
+
+        for (name, bytes, expected) in fixtures {
+            let texture = decode(bytes).unwrap();
+            assert_eq!((texture.width, texture.height), (3, 2), "{name}");
+            assert_eq!(texture.data.len(), 4 * 3 * 2, "{name}");
+            assert_eq!(texture.data, expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn detect_magic_numbers() {
+        let cases: [(&[u8], Option<ImageFormat>); 9] = [
+            (b"\x89PNG\r\n\x1a\n....", Some(ImageFormat::Png)),
+            (b"\xff\xd8\xff\xe0", Some(ImageFormat::Jpeg)),
+            (b"GIF87a..", Some(ImageFormat::Gif)),
+            (b"GIF89a..", Some(ImageFormat::Gif)),
+            (b"qoif....", Some(ImageFormat::Qoi)),
+            (b"BM......", Some(ImageFormat::Bmp)),
+            (b"RIFF....WEBPVP8L", Some(ImageFormat::WebP)),
+            // Other RIFF files, like WAV
+            (b"RIFF....WAVEfmt ", None),
+            (b"\x89PN", None),
+        ];
+        for (bytes, format) in cases {
+            assert_eq!(ImageFormat::detect(bytes), format, "{bytes:?}");
+        }
+        assert_eq!(ImageFormat::detect(b""), None);
+        assert_eq!(ImageFormat::detect(&[0xaa; 32]), None);
+
+        // TGA has no magic number, only a footer
+        let mut tga = vec![0xaa; 32];
+        tga.extend_from_slice(b"TRUEVISION-XFILE.\0");
+        assert_eq!(ImageFormat::detect(&tga), Some(ImageFormat::Tga));
+    }
+
+    // The fixtures for other formats are 3x2 with the same pixels
+    #[cfg(any(feature = "qoi", feature = "webp"))]
+    const RGBA: [u8; 24] = [
+        255, 0, 0, 255, 0, 255, 0, 128, 0, 0, 255, 0, 1, 3, 5, 7, 250, 128, 7, 64, 255, 255, 255,
+        255,
+    ];
+    #[cfg(any(feature = "qoi", feature = "webp"))]
+    const RGB: [u8; 24] = [
+        255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, 1, 2, 3, 255, 250, 128, 7, 255, 255, 255,
+        255, 255,
+    ];
+
+    #[cfg(any(feature = "qoi", feature = "webp"))]
+    fn assert_decodes_to(name: &str, bytes: &[u8], expected: &[u8]) {
+        let texture = decode(bytes).unwrap();
+        assert_eq!((texture.width, texture.height), (3, 2), "{name}");
+        assert_eq!(texture.data, expected, "{name}");
+    }
+
+    #[cfg(feature = "qoi")]
+    #[test]
+    fn qoi_decodes() {
+        let rgba = include_bytes!("../tests/fixtures/qoi/rgba.qoi");
+        assert_decodes_to("rgba", rgba, &RGBA);
+        // Opaque when the file has no alpha
+        let rgb = include_bytes!("../tests/fixtures/qoi/rgb.qoi");
+        assert_decodes_to("rgb", rgb, &RGB);
+    }
+
+    // Lossless, so the pixels come back exactly
+    #[cfg(feature = "webp")]
+    #[test]
+    fn webp_decodes() {
+        let rgba = include_bytes!("../tests/fixtures/webp/rgba.webp");
+        assert_decodes_to("rgba", rgba, &RGBA);
+        let rgb = include_bytes!("../tests/fixtures/webp/rgb.webp");
+        assert_decodes_to("rgb", rgb, &RGB);
+    }
+
+    // 16x8, a flat 8x8 block on the left and another on the right. Each block is only its
+    // average with a quantizer of 1, so the values come back within rounding.
+    #[cfg(feature = "jpeg")]
+    fn assert_jpeg_halves(name: &str, bytes: &[u8], left: [u8; 3], right: [u8; 3]) {
+        let texture = decode(bytes).unwrap();
+        assert_eq!((texture.width, texture.height), (16, 8), "{name}");
+        for (i, pixel) in texture.data.chunks(4).enumerate() {
+            let expected = if i % 16 < 8 { left } else { right };
+            let close = pixel[..3]
+                .iter()
+                .zip(expected)
+                .all(|(&a, b)| a.abs_diff(b) <= 2);
+            assert!(close && pixel[3] == 255, "{name}: {pixel:?} at {i}");
+        }
+    }
+
+    #[cfg(feature = "jpeg")]
+    #[test]
+    fn jpeg_decodes() {
+        let gray = include_bytes!("../tests/fixtures/jpeg/gray.jpg");
+        assert_jpeg_halves("gray", gray, [50; 3], [200; 3]);
+        let color = include_bytes!("../tests/fixtures/jpeg/color.jpg");
+        assert_jpeg_halves("color", color, [200, 40, 30], [20, 100, 220]);
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    fn gif_decodes() {
+        // Red, green, blue and white, the whole screen in one frame
+        let texture = decode(include_bytes!("../tests/fixtures/gif/full.gif")).unwrap();
+        assert_eq!((texture.width, texture.height), (3, 2));
+        assert_eq!(
+            texture.data,
+            [
+                [255, 0, 0, 255],
+                [0, 255, 0, 255],
+                [0, 0, 255, 255],
+                [255, 255, 255, 255],
+                [255, 0, 0, 255],
+                [0, 255, 0, 255],
+            ]
+            .concat()
+        );
+
+        // A 2x1 first frame at (1, 1) on a 4x3 screen, blue and then a transparent white. The
+        // second frame covers the screen in green and isn't loaded.
+        let texture = decode(include_bytes!("../tests/fixtures/gif/offset.gif")).unwrap();
+        assert_eq!((texture.width, texture.height), (4, 3));
+        let mut expected = vec![0; 4 * 3 * 4];
+        expected[5 * 4..7 * 4].copy_from_slice(&[0, 0, 255, 255, 255, 255, 255, 0]);
+        assert_eq!(texture.data, expected);
+    }
+
+    // Every cut short copy of a file fails to load instead of panicking
+    #[cfg(any(feature = "bmp", feature = "tga"))]
+    fn assert_cut_short_fails(bytes: &[u8]) {
+        for len in 0..bytes.len() {
+            assert!(
+                decode(&bytes[..len]).is_err(),
+                "{len} of {} bytes",
+                bytes.len()
+            );
+        }
+    }
+
+    // A bottom up BMP with a version 1 header and no palette
+    #[cfg(feature = "bmp")]
+    fn bmp(width: i32, height: i32, bpp: u16, pixels: &[u8]) -> Vec<u8> {
+        let mut bytes = b"BM".to_vec();
+        bytes.extend_from_slice(&(54 + pixels.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(&54u32.to_le_bytes());
+        bytes.extend_from_slice(&40u32.to_le_bytes());
+        bytes.extend_from_slice(&width.to_le_bytes());
+        bytes.extend_from_slice(&height.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&bpp.to_le_bytes());
+        bytes.extend_from_slice(&[0; 24]);
+        bytes.extend_from_slice(pixels);
+        bytes
+    }
+
+    #[cfg(feature = "bmp")]
+    #[test]
+    fn bmp_decodes() {
+        // Blue, green, red, with rows padded to 4 bytes
+        let pixels = [255, 0, 0, 0, 255, 0, 0, 0, 0, 0, 255, 0, 0, 0, 0, 0];
+        let texture = decode(&bmp(2, 2, 24, &pixels)).unwrap();
+        assert_eq!((texture.width, texture.height), (2, 2));
+        assert_eq!(
+            texture.data,
+            [255, 0, 0, 255, 0, 0, 0, 255, 0, 0, 255, 255, 0, 255, 0, 255]
+        );
+    }
+
+    #[cfg(feature = "bmp")]
+    #[test]
+    fn malformed_bmp_fails() {
+        let pixels = [0; 16];
+        assert_cut_short_fails(&bmp(2, 2, 24, &pixels));
+
+        for (width, height, bpp) in [
+            (0, 2, 24),
+            (2, 0, 24),
+            (-2, 2, 24),
+            (2, 2, 0),
+            (2, 2, 7),
+            (2, 2, 64),
+            // Sizes the file doesn't have the pixels for
+            (1 << 16, 1 << 16, 32),
+            (i32::MAX, i32::MIN, 32),
+        ] {
+            let bytes = bmp(width, height, bpp, &pixels);
+            assert!(decode(&bytes).is_err(), "{width}x{height} at {bpp} bits");
+        }
+
+        // Pixels after the end of the file
+        let mut bytes = bmp(2, 2, 24, &pixels);
+        bytes[10..14].copy_from_slice(&1000u32.to_le_bytes());
+        assert!(decode(&bytes).is_err());
+
+        // A palette with 2 colors and a pixel using the third
+        let mut bytes = bmp(1, 1, 8, &[0, 0, 255, 0, 0, 255, 0, 0, 2, 0, 0, 0]);
+        bytes[46..50].copy_from_slice(&2u32.to_le_bytes());
+        bytes[10..14].copy_from_slice(&62u32.to_le_bytes());
+        assert!(decode(&bytes).is_err());
+    }
+
+    #[cfg(feature = "tga")]
+    fn tga(kind: u8, width: u16, height: u16, depth: u8, pixels: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0, 0, kind, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        bytes.extend_from_slice(&width.to_le_bytes());
+        bytes.extend_from_slice(&height.to_le_bytes());
+        // Top down
+        bytes.extend_from_slice(&[depth, 0x20]);
+        bytes.extend_from_slice(pixels);
+        bytes
+    }
+
+    #[cfg(feature = "tga")]
+    #[test]
+    fn tga_decodes() {
+        let plain = tga(2, 2, 1, 24, &[0, 0, 255, 255, 0, 0]);
+        // A run of one blue pixel, then one raw red pixel
+        let rle = tga(10, 2, 1, 24, &[0x80, 255, 0, 0, 0x00, 0, 0, 255]);
+        for (bytes, data) in [
+            (plain, [255, 0, 0, 255, 0, 0, 255, 255]),
+            (rle, [0, 0, 255, 255, 255, 0, 0, 255]),
+        ] {
+            let texture = decode(&bytes).unwrap();
+            assert_eq!((texture.width, texture.height), (2, 1));
+            assert_eq!(texture.data, data);
+        }
+    }
+
+    #[cfg(feature = "tga")]
+    #[test]
+    fn malformed_tga_fails() {
+        assert_cut_short_fails(&tga(2, 2, 1, 24, &[0, 0, 255, 255, 0, 0]));
+        assert_cut_short_fails(&tga(10, 2, 1, 24, &[0x80, 255, 0, 0, 0x00, 0, 0, 255]));
+
+        // These only get recognised by the footer
+        let pixels = [0; 6];
+        for (kind, width, height, depth) in [
+            (2, 0, 1, 24),
+            (2, 2, 0, 24),
+            (2, 2, 1, 0),
+            (2, 2, 1, 12),
+            (2, 2, 1, 64),
+            (4, 2, 1, 24),
+            // Color mapped without a color map
+            (1, 2, 1, 8),
+        ] {
+            let mut bytes = tga(kind, width, height, depth, &pixels);
+            bytes.extend_from_slice(&[0; 8]);
+            bytes.extend_from_slice(b"TRUEVISION-XFILE.\0");
+            assert!(
+                decode(&bytes).is_err(),
+                "type {kind}, {width}x{height} at {depth} bits"
+            );
+        }
+
+        // A run claiming the whole of a huge image, from a few bytes
+        assert!(decode(&tga(10, u16::MAX, u16::MAX, 24, &[0xff, 0, 0, 0])).is_err());
+
+        // A color map with 0 bit entries, then one with 2 entries and a pixel using the third
+        let mut bytes = tga(1, 1, 1, 8, &[0]);
+        bytes[1] = 1;
+        bytes[5..7].copy_from_slice(&2u16.to_le_bytes());
+        assert!(decode_tga(&bytes).is_err());
+        bytes[7] = 24;
+        bytes.splice(18..18, [0; 6]);
+        bytes[24] = 2;
+        assert!(decode_tga(&bytes).is_err());
+        bytes[24] = 1;
+        assert!(decode_tga(&bytes).is_ok());
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    fn gif_with_an_empty_frame_fails() {
+        let mut bytes = b"GIF89a".to_vec();
+        // 1x1 with a global palette of 2 colors
+        bytes.extend_from_slice(&[1, 0, 1, 0, 0x80, 0, 0, 0, 0, 0, 255, 255, 255]);
+        // A 0x0 frame with an empty image
+        bytes.extend_from_slice(&[0x2c, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0x3b]);
+        assert!(decode(&bytes).is_err());
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    fn gif_with_a_huge_screen_fails() {
+        let mut bytes = b"GIF89a".to_vec();
+        // 65535x65535 with a global palette of 2 colors
+        bytes.extend_from_slice(&[0xff, 0xff, 0xff, 0xff, 0x80, 0, 0, 0, 0, 0, 255, 255, 255]);
+        // A 1x1 frame, clear code, index 0, end code
+        bytes.extend_from_slice(&[0x2c, 0, 0, 0, 0, 1, 0, 1, 0, 0, 2, 2, 0x44, 0x01, 0, 0x3b]);
+        assert_eq!(bytes.len(), 35);
+        assert!(decode(&bytes).is_err());
+
+        // The same frame on a 2x2 screen is fine
+        bytes[6..10].copy_from_slice(&[2, 0, 2, 0]);
+        let texture = decode(&bytes).unwrap();
+        assert_eq!((texture.width, texture.height), (2, 2));
+        assert_eq!(texture.data[..4], [0, 0, 0, 255]);
+    }
+}
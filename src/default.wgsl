@@ -1,9 +1,25 @@
 
+// Whether the render target encodes to sRGB by itself, see RendiumBuilder::with_linear_workflow
+override LINEAR_TARGET: bool = true;
+
+// Vertex and sprite colors are sRGB like `Color`, everything after the vertex shader is linear
+fn srgb_to_linear(c: vec4<f32>) -> vec4<f32> {
+    let rgb = select(pow((c.rgb + 0.055) / 1.055, vec3<f32>(2.4)), c.rgb / 12.92, c.rgb <= vec3<f32>(0.04045));
+    return vec4<f32>(rgb, c.a);
+}
+
+fn linear_to_srgb(c: vec4<f32>) -> vec4<f32> {
+    let rgb = select(1.055 * pow(c.rgb, vec3<f32>(1.0 / 2.4)) - 0.055, c.rgb * 12.92, c.rgb <= vec3<f32>(0.0031308));
+    return vec4<f32>(rgb, c.a);
+}
+
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) color: vec4<f32>,
     @location(2) uv: vec2<f32>,
     @location(3) tex_index: u32,
+    @location(4) kind: u32,
+    @location(5) params: vec4<f32>,
 };
 
 struct VertexOutput {
@@ -11,15 +27,75 @@ struct VertexOutput {
     @location(0) color: vec4<f32>,
     @location(1) uv: vec2<f32>,
     @location(2) tex_index: u32,
+    @location(3) kind: u32,
+    @location(4) params: vec4<f32>,
+};
+
+// Vertex kinds, the same as ShapeKind in lib.rs
+const SHAPE_PLAIN: u32 = 0u;
+const SHAPE_LINEAR_GRADIENT: u32 = 1u;
+const SHAPE_RADIAL_GRADIENT: u32 = 2u;
+const SHAPE_CIRCLE: u32 = 3u;
+const SHAPE_ROUNDED_RECT: u32 = 4u;
+const SHAPE_CAPSULE: u32 = 5u;
+const SHAPE_TRIANGLE: u32 = 6u;
+const SHAPE_ELLIPSE: u32 = 7u;
+const SHAPE_ARC: u32 = 8u;
+
+struct Globals {
+    view_proj: mat4x4<f32>,
 };
 
+@group(1) @binding(0)
+var<uniform> globals: Globals;
+
 @vertex
 fn vs_main(model: VertexInput) -> VertexOutput {
     var out: VertexOutput;
-    out.clip_position = vec4<f32>(model.position, 1.0);
-    out.color = model.color;
+    out.clip_position = globals.view_proj * vec4<f32>(model.position, 1.0);
+    out.color = srgb_to_linear(model.color);
     out.uv = model.uv;
     out.tex_index = model.tex_index;
+    out.kind = model.kind;
+    out.params = model.params;
+    return out;
+}
+
+struct SpriteInput {
+    @location(0) position: vec2<f32>,
+    @location(1) size: vec2<f32>,
+    @location(2) rotation: f32,
+    @location(3) uv_rect: vec4<f32>,
+    @location(4) color: vec4<f32>,
+};
+
+// Expands one sprite instance into a quad, 6 vertices per instance
+@vertex
+fn vs_sprite(@builtin(vertex_index) vertex_index: u32, sprite: SpriteInput) -> VertexOutput {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 1.0),
+    );
+    let corner = corners[vertex_index];
+
+    // Rotate around the center of the sprite
+    let local = (corner - vec2<f32>(0.5, 0.5)) * sprite.size;
+    let c = cos(sprite.rotation);
+    let s = sin(sprite.rotation);
+    let rotated = vec2<f32>(local.x * c - local.y * s, local.x * s + local.y * c);
+    let pixel = sprite.position + sprite.size * 0.5 + rotated;
+
+    var out: VertexOutput;
+    out.clip_position = globals.view_proj * vec4<f32>(pixel, 0.0, 1.0);
+    out.color = srgb_to_linear(sprite.color);
+    out.uv = sprite.uv_rect.xy + corner * sprite.uv_rect.zw;
+    out.tex_index = 0u;
+    out.kind = SHAPE_PLAIN;
+    out.params = vec4<f32>(0.0);
     return out;
 }
 
@@ -28,7 +104,103 @@ var t_diffuse: texture_2d<f32>;
 @group(0) @binding(1)
 var s_diffuse: sampler;
 
+// Where `t` of a gradient is in the ramp texture, one row per gradient.
+// Texel centers are hit exactly at 0 and 1, and anything outside keeps the end colors.
+fn gradient_uv(t: f32, row: f32) -> vec2<f32> {
+    let size = vec2<f32>(textureDimensions(t_diffuse));
+    let u = (clamp(t, 0.0, 1.0) * (size.x - 1.0) + 0.5) / size.x;
+    return vec2<f32>(u, (row + 0.5) / size.y);
+}
+
+// Signed distances, negative inside. `p` is relative to the center of the shape.
+fn sd_rounded_rect(p: vec2<f32>, half_size: vec2<f32>, radius: f32) -> f32 {
+    let q = abs(p) - half_size + radius;
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - radius;
+}
+
+// A segment along the x axis from -half_length to half_length, thickened by radius
+fn sd_capsule(p: vec2<f32>, half_length: f32, radius: f32) -> f32 {
+    return length(vec2<f32>(max(abs(p.x) - half_length, 0.0), p.y)) - radius;
+}
+
+// Not exact, the implicit function divided by the length of its gradient,
+// which is close enough near the edge where it matters
+fn sd_ellipse(p: vec2<f32>, radii: vec2<f32>) -> f32 {
+    let k = length(p / radii);
+    if k == 0.0 {
+        return -min(radii.x, radii.y);
+    }
+    return (k - 1.0) * k / length(p / (radii * radii));
+}
+
+// A ring between mid_radius - half_thickness and mid_radius + half_thickness,
+// cut to half_angle on both sides of the x axis
+fn sd_arc(p: vec2<f32>, mid_radius: f32, half_thickness: f32, half_angle: f32) -> f32 {
+    let ring = abs(length(p) - mid_radius) - half_thickness;
+    if half_angle >= 3.14159 {
+        return ring;
+    }
+    // Folded onto the upper half, the only edge left is the ray at half_angle
+    let wedge = dot(vec2<f32>(p.x, abs(p.y)), vec2<f32>(-sin(half_angle), cos(half_angle)));
+    return max(ring, wedge);
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return textureSample(t_diffuse, s_diffuse, in.uv);
+    var uv = in.uv;
+    // For the SDF shapes `in.uv` is the position in the shape and `params.w` the outline
+    // thickness, or 0 to fill it
+    var distance = -1.0;
+    switch in.kind {
+        case SHAPE_LINEAR_GRADIENT: {
+            uv = gradient_uv(in.params.x, in.params.z);
+        }
+        case SHAPE_RADIAL_GRADIENT: {
+            uv = gradient_uv(length(in.params.xy), in.params.z);
+            if in.params.w > 0.0 {
+                distance = length(in.params.xy) - 1.0;
+            }
+        }
+        case SHAPE_CIRCLE: {
+            distance = length(in.uv) - in.params.x;
+        }
+        case SHAPE_ROUNDED_RECT: {
+            distance = sd_rounded_rect(in.uv, in.params.xy, in.params.z);
+            // An outline is the rect minus the rect inset by its thickness, so the outer
+            // corners keep their radius instead of getting rounded by half the thickness
+            if in.params.w > 0.0 {
+                let inner_radius = max(in.params.z - in.params.w, 0.0);
+                distance = max(distance, -sd_rounded_rect(in.uv, in.params.xy - in.params.w, inner_radius));
+            }
+        }
+        case SHAPE_CAPSULE: {
+            distance = sd_capsule(in.uv, in.params.x, in.params.y);
+        }
+        case SHAPE_TRIANGLE: {
+            // params.xyz are the distances to the three edges, positive inside
+            distance = -min(in.params.x, min(in.params.y, in.params.z));
+        }
+        case SHAPE_ELLIPSE: {
+            distance = sd_ellipse(in.uv, in.params.xy);
+        }
+        case SHAPE_ARC: {
+            distance = sd_arc(in.uv, in.params.x, in.params.y, in.params.z);
+        }
+        default: {}
+    }
+    if in.kind >= SHAPE_CIRCLE && in.kind != SHAPE_ROUNDED_RECT && in.params.w > 0.0 {
+        distance = abs(distance) - in.params.w * 0.5;
+    }
+
+    // Analytic anti-aliasing, a pixel is covered by how much of it is inside the edge.
+    // fwidth turns the distance into pixels whatever the transform and camera are.
+    let coverage = clamp(0.5 - distance / max(fwidth(distance), 1e-5), 0.0, 1.0);
+
+    // Textures are sRGB formats, so sampling already gives linear values
+    var color = textureSample(t_diffuse, s_diffuse, uv) * in.color;
+    color.a *= coverage;
+    if LINEAR_TARGET {
+        return color;
+    }
+    return linear_to_srgb(color);
 }
@@ -0,0 +1,397 @@
+// This module has methods for drawing shapes filled with linear and radial gradients.
+// Gradients are baked into rows of a small texture, so any number of stops is smooth.
+use crate::shapes::{aa_margin, circle_segments};
+use crate::tessellate::{FillRule, add_fill_shaded};
+use crate::texture::{GPUTexture, Texture, create_gpu_texture};
+use crate::types::{Color, Matrix3, Vector2};
+use crate::{RendiumDrawHandle, ShapeKind};
+
+// Texture index of the first ramp texture, which are made per draw handle at render time.
+// Each one holds `RAMPS_PER_TEXTURE` gradients, the next ones count down from here.
+pub(crate) const GRADIENT_TEXTURE: u32 = u32::MAX;
+// How many colors each gradient is baked into
+const RAMP_WIDTH: usize = 256;
+// Well under 2048, the smallest texture size wgpu lets a GPU have
+const RAMPS_PER_TEXTURE: usize = 256;
+// Way more ramp textures than a frame will ever need, so user textures don't clash with them
+const MAX_RAMP_TEXTURES: u32 = 1 << 16;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColorStop {
+    // Where the color is, 0 is the start of the gradient and 1 the end
+    pub offset: f32,
+    pub col: Color,
+}
+
+impl ColorStop {
+    pub fn new(offset: f32, col: Color) -> Self {
+        Self { offset, col }
+    }
+}
+
+impl From<(f32, Color)> for ColorStop {
+    fn from((offset, col): (f32, Color)) -> Self {
+        Self::new(offset, col)
+    }
+}
+
+// The color of a gradient at `t`. Stops don't have to be sorted, before the first
+// and after the last stop the end colors continue.
+pub fn color_at(stops: &[ColorStop], t: f32) -> Color {
+    let mut sorted = stops.to_vec();
+    sorted.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+    sorted_color_at(&sorted, t)
+}
+
+fn sorted_color_at(stops: &[ColorStop], t: f32) -> Color {
+    let (first, last) = match (stops.first(), stops.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Color::TRANSPARENT,
+    };
+    if t <= first.offset {
+        return first.col;
+    }
+
+    for pair in stops.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        if t <= b.offset {
+            let span = b.offset - a.offset;
+            if span <= 0.0 {
+                return b.col;
+            }
+            return a.col.lerp(b.col, (t - a.offset) / span);
+        }
+    }
+
+    last.col
+}
+
+pub trait DrawGradient {
+    // Top to bottom
+    fn draw_rect_gradient_v(&mut self, pos: Vector2, size: Vector2, stops: &[ColorStop]);
+    // Left to right
+    fn draw_rect_gradient_h(&mut self, pos: Vector2, size: Vector2, stops: &[ColorStop]);
+    // A color for each corner, blended across the rect
+    fn draw_rect_gradient(
+        &mut self,
+        pos: Vector2,
+        size: Vector2,
+        top_left: Color,
+        top_right: Color,
+        bottom_right: Color,
+        bottom_left: Color,
+    );
+    // The first stop is at the center and the last at the edge
+    fn draw_circle_gradient(&mut self, center: Vector2, radius: f32, stops: &[ColorStop]);
+    // A polygon with a linear gradient going from `from` to `to`, filled like `draw_polygon`
+    fn draw_polygon_gradient(
+        &mut self,
+        points: &[Vector2],
+        from: Vector2,
+        to: Vector2,
+        stops: &[ColorStop],
+    );
+    // The gradient runs along the line
+    fn draw_line_gradient(
+        &mut self,
+        from: Vector2,
+        to: Vector2,
+        thickness: f32,
+        stops: &[ColorStop],
+    );
+}
+
+impl DrawGradient for RendiumDrawHandle {
+    fn draw_rect_gradient_v(&mut self, pos: Vector2, size: Vector2, stops: &[ColorStop]) {
+        let corners = rect_corners(pos, size);
+        self.draw_polygon_gradient(&corners, pos, pos + Vector2(0.0, size.1), stops);
+    }
+
+    fn draw_rect_gradient_h(&mut self, pos: Vector2, size: Vector2, stops: &[ColorStop]) {
+        let corners = rect_corners(pos, size);
+        self.draw_polygon_gradient(&corners, pos, pos + Vector2(size.0, 0.0), stops);
+    }
+
+    fn draw_rect_gradient(
+        &mut self,
+        pos: Vector2,
+        size: Vector2,
+        top_left: Color,
+        top_right: Color,
+        bottom_right: Color,
+        bottom_left: Color,
+    ) {
+        let corners = rect_corners(pos, size);
+        let colors = [top_left, top_right, bottom_right, bottom_left];
+        // Two triangles would blend along one diagonal only, a center vertex keeps it symmetric
+        let center_col = top_left
+            .lerp(bottom_right, 0.5)
+            .lerp(top_right.lerp(bottom_left, 0.5), 0.5);
+
+        let base = self.vertices.len() as u32;
+        self.add_vertex(
+            [pos.0 + size.0 / 2.0, pos.1 + size.1 / 2.0, 0.0],
+            center_col,
+            [0.0, 0.0],
+            0,
+        );
+        for (corner, col) in corners.iter().zip(colors) {
+            self.add_vertex([corner.0, corner.1, 0.0], col, [0.0, 0.0], 0);
+        }
+
+        for i in 0..4 {
+            self.add_index(base);
+            self.add_index(base + 1 + i);
+            self.add_index(base + 1 + (i + 1) % 4);
+        }
+    }
+
+    fn draw_circle_gradient(&mut self, center: Vector2, radius: f32, stops: &[ColorStop]) {
+        if stops.is_empty() || radius <= 0.0 {
+            return;
+        }
+
+        let (tex_index, row) = ramp_row(self, stops);
+        let base = self.vertices.len() as u32;
+
+        // The fragment shader measures the distance from the center, so the gradient is smooth
+        // no matter how many stops there are
+        if self.anti_aliasing {
+            // A quad, cut to a circle with a soft edge
+            let half = 1.0 + aa_margin(self) / radius;
+            for corner in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+                let local = Vector2::from(corner) * half;
+                self.add_shape_vertex(
+                    center + local * radius,
+                    Color::WHITE,
+                    Vector2::zero(),
+                    ShapeKind::RadialGradient,
+                    [local.0, local.1, row, 1.0],
+                    tex_index,
+                );
+            }
+            for i in [0, 1, 2, 0, 2, 3] {
+                self.add_index(base + i);
+            }
+            return;
+        }
+
+        let segments = circle_segments(radius * self.pixels_per_unit());
+        let points: Vec<Vector2> = (0..segments)
+            .map(|i| {
+                let theta = i as f32 / segments as f32 * std::f32::consts::TAU;
+                center + Vector2(theta.cos(), theta.sin()) * radius
+            })
+            .collect();
+        let shade = gradient_shade(
+            self,
+            GradientShape::Radial { center, radius },
+            stops,
+            Matrix3::IDENTITY,
+            Color::WHITE,
+        );
+        add_fill_shaded(self, &[points], FillRule::NonZero, &shade);
+    }
+
+    fn draw_polygon_gradient(
+        &mut self,
+        points: &[Vector2],
+        from: Vector2,
+        to: Vector2,
+        stops: &[ColorStop],
+    ) {
+        if stops.is_empty() {
+            return;
+        }
+        let shade = gradient_shade(
+            self,
+            GradientShape::Linear { from, to },
+            stops,
+            Matrix3::IDENTITY,
+            Color::WHITE,
+        );
+        add_fill_shaded(self, &[points.to_vec()], FillRule::NonZero, &shade);
+    }
+
+    fn draw_line_gradient(
+        &mut self,
+        from: Vector2,
+        to: Vector2,
+        thickness: f32,
+        stops: &[ColorStop],
+    ) {
+        let dir = to - from;
+        if dir.length_squared() == 0.0 {
+            return;
+        }
+
+        let offset = dir.normalized().perpendicular() * (thickness / 2.0);
+        let corners = [from + offset, to + offset, to - offset, from - offset];
+        self.draw_polygon_gradient(&corners, from, to, stops);
+    }
+}
+
+fn rect_corners(pos: Vector2, size: Vector2) -> [Vector2; 4] {
+    [
+        pos,
+        pos + Vector2(size.0, 0.0),
+        pos + size,
+        pos + Vector2(0.0, size.1),
+    ]
+}
+
+// Where a gradient lies, in the space it's shaded in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum GradientShape {
+    Linear { from: Vector2, to: Vector2 },
+    // The first stop is at the center and the last one at `radius`
+    Radial { center: Vector2, radius: f32 },
+}
+
+// Shades a fill with a gradient, tinted by `tint`. `to_gradient` takes the points of the fill
+// into the space of the gradient, so it can be stretched, rotated or skewed.
+pub(crate) fn gradient_shade(
+    d: &mut RendiumDrawHandle,
+    shape: GradientShape,
+    stops: &[ColorStop],
+    to_gradient: Matrix3,
+    tint: Color,
+) -> impl Fn(&mut RendiumDrawHandle, Vector2, bool) + use<> {
+    let (tex_index, row) = ramp_row(d, stops);
+    move |d, p, faded| {
+        // Both stay linear across the triangles, so they interpolate exactly
+        let q = to_gradient.transform_point(p);
+        let (kind, params) = match shape {
+            GradientShape::Linear { from, to } => {
+                let axis = to - from;
+                let length_squared = axis.length_squared();
+                let t = if length_squared > 0.0 {
+                    (q - from).dot(axis) / length_squared
+                } else {
+                    0.0
+                };
+                (ShapeKind::LinearGradient, [t, 0.0, row, 0.0])
+            }
+            GradientShape::Radial { center, radius } => {
+                // With no radius it's all the last stop
+                let local = if radius > 0.0 {
+                    (q - center) / radius
+                } else {
+                    Vector2(1.0, 0.0)
+                };
+                (ShapeKind::RadialGradient, [local.0, local.1, row, 0.0])
+            }
+        };
+        let col = if faded { tint.with_alpha(0) } else { tint };
+        d.add_shape_vertex(p, col, Vector2::zero(), kind, params, tex_index);
+    }
+}
+
+// Bakes the stops into a row of a ramp texture, reusing the row of identical stops.
+// Returns the texture index of the ramp texture and the row in it.
+fn ramp_row(d: &mut RendiumDrawHandle, stops: &[ColorStop]) -> (u32, f32) {
+    let mut sorted = stops.to_vec();
+    sorted.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+    let key: Vec<(u32, Color)> = sorted.iter().map(|s| (s.offset.to_bits(), s.col)).collect();
+
+    let row = match d.gradient_rows.get(&key) {
+        Some(&row) => row,
+        None => {
+            let ramp = (0..RAMP_WIDTH)
+                .map(|i| sorted_color_at(&sorted, i as f32 / (RAMP_WIDTH - 1) as f32))
+                .collect();
+            d.gradient_ramps.push(ramp);
+            let row = d.gradient_ramps.len() - 1;
+            d.gradient_rows.insert(key, row);
+            row
+        }
+    };
+
+    let texture = (row / RAMPS_PER_TEXTURE) as u32;
+    (GRADIENT_TEXTURE - texture, (row % RAMPS_PER_TEXTURE) as f32)
+}
+
+// Which of the ramp textures a texture index points at, if it's one of them
+pub(crate) fn ramp_texture(tex_index: u32) -> Option<usize> {
+    let texture = GRADIENT_TEXTURE - tex_index;
+    (texture < MAX_RAMP_TEXTURES).then_some(texture as usize)
+}
+
+// One texture per `RAMPS_PER_TEXTURE` rows, so it never goes over the size limit
+pub(crate) fn create_ramp_textures(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    ramps: &[Vec<Color>],
+) -> Vec<GPUTexture> {
+    ramps
+        .chunks(RAMPS_PER_TEXTURE)
+        .map(|ramps| {
+            let data = ramps
+                .iter()
+                .flatten()
+                .flat_map(|c| [c.0, c.1, c.2, c.3])
+                .collect();
+            let texture = Texture {
+                data,
+                width: RAMP_WIDTH as u32,
+                height: ramps.len() as u32,
+            };
+            create_gpu_texture(device, queue, bind_group_layout, &texture)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use winit::dpi::PhysicalSize;
+
+    fn stops(i: u8) -> [ColorStop; 2] {
+        [
+            ColorStop::new(0.0, Color::rgb(i, 0, 0)),
+            ColorStop::new(1.0, Color::rgb(0, i, 255 - i)),
+        ]
+    }
+
+    #[test]
+    fn identical_stops_share_a_row() {
+        let mut d = RendiumDrawHandle::new(PhysicalSize::new(100, 100), HashMap::new());
+        let first = ramp_row(&mut d, &stops(1));
+        assert_eq!(first, (GRADIENT_TEXTURE, 0.0));
+        assert_eq!(ramp_row(&mut d, &stops(2)), (GRADIENT_TEXTURE, 1.0));
+
+        // The same stops in another order are the same gradient
+        let mut reversed = stops(1);
+        reversed.reverse();
+        assert_eq!(ramp_row(&mut d, &stops(1)), first);
+        assert_eq!(ramp_row(&mut d, &reversed), first);
+        assert_eq!(d.gradient_ramps.len(), 2);
+    }
+
+    #[test]
+    fn ramps_spill_into_more_textures() {
+        let mut d = RendiumDrawHandle::new(PhysicalSize::new(100, 100), HashMap::new());
+        let count = RAMPS_PER_TEXTURE * 2 + 10;
+        let slots: Vec<(u32, f32)> = (0..count)
+            .map(|i| {
+                let stops = [
+                    ColorStop::new(0.0, Color::WHITE),
+                    ColorStop::new(i as f32 / count as f32, Color::BLACK),
+                ];
+                ramp_row(&mut d, &stops)
+            })
+            .collect();
+
+        assert_eq!(slots[RAMPS_PER_TEXTURE - 1], (GRADIENT_TEXTURE, 255.0));
+        assert_eq!(slots[RAMPS_PER_TEXTURE], (GRADIENT_TEXTURE - 1, 0.0));
+        assert_eq!(slots[count - 1], (GRADIENT_TEXTURE - 2, 9.0));
+        assert_eq!(ramp_texture(slots[count - 1].0), Some(2));
+        assert_eq!(ramp_texture(GRADIENT_TEXTURE), Some(0));
+        // Indices of loaded textures aren't ramps
+        assert_eq!(ramp_texture(1), None);
+        assert_eq!(ramp_texture(0), None);
+    }
+}
@@ -0,0 +1,654 @@
+// Images in memory, for making textures in code or changing them before they're uploaded.
+// Pixels are 8 bit sRGB with straight alpha, row by row from the top left, like `Color`.
+// Anything that mixes colors does it in linear light, the same as the GPU.
+use std::io::Write;
+
+use anyhow::bail;
+
+use crate::color::LinearColor;
+use crate::decode;
+use crate::texture::{self, Texture};
+use crate::types::Color;
+
+pub use crate::decode::ImageFormat;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+// How `Image::resize` picks the new pixels
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ResizeFilter {
+    // The closest old pixel, keeps pixel art sharp
+    Nearest,
+    // A mix of the four closest old pixels
+    #[default]
+    Bilinear,
+}
+
+impl Image {
+    // An image filled with one color
+    pub fn new(width: u32, height: u32, col: Color) -> Self {
+        let data = [col.0, col.1, col.2, col.3].repeat(width as usize * height as usize);
+        Self {
+            data,
+            width,
+            height,
+        }
+    }
+
+    // `data` is RGBA, 4 bytes per pixel
+    pub fn from_raw(width: u32, height: u32, data: Vec<u8>) -> anyhow::Result<Self> {
+        let expected = width as usize * height as usize * 4;
+        if data.len() != expected {
+            bail!(
+                "Image data is {} bytes, {width}x{height} needs {expected}",
+                data.len()
+            );
+        }
+        Ok(Self {
+            data,
+            width,
+            height,
+        })
+    }
+
+    // PNG, and the other formats that have their feature turned on
+    pub fn load(filename: &str) -> anyhow::Result<Self> {
+        Ok(texture::load_texture(filename)?.into())
+    }
+
+    // A whole image file, the format is worked out from its first bytes
+    pub fn from_memory(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(decode::decode(bytes)?.into())
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_raw(self) -> Vec<u8> {
+        self.data
+    }
+
+    // None outside the image
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<Color> {
+        let i = self.index(x as i64, y as i64)?;
+        let p = &self.data[i..i + 4];
+        Some(Color(p[0], p[1], p[2], p[3]))
+    }
+
+    // Replaces the pixel without blending, does nothing outside the image
+    pub fn set_pixel(&mut self, x: u32, y: u32, col: Color) {
+        if let Some(i) = self.index(x as i64, y as i64) {
+            self.data[i..i + 4].copy_from_slice(&[col.0, col.1, col.2, col.3]);
+        }
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32, filter: ResizeFilter) {
+        if width == 0 || height == 0 || self.width == 0 || self.height == 0 {
+            *self = Self::new(width, height, Color::TRANSPARENT);
+            return;
+        }
+
+        let scale_x = self.width as f32 / width as f32;
+        let scale_y = self.height as f32 / height as f32;
+        let mut resized = Self::new(width, height, Color::TRANSPARENT);
+        for y in 0..height {
+            for x in 0..width {
+                // Where the middle of the new pixel is in the old image
+                let sx = (x as f32 + 0.5) * scale_x;
+                let sy = (y as f32 + 0.5) * scale_y;
+                let col = match filter {
+                    ResizeFilter::Nearest => self.pixel(sx as u32, sy as u32),
+                    ResizeFilter::Bilinear => self.sample_bilinear(sx - 0.5, sy - 0.5),
+                };
+                resized.set_pixel(x, y, col);
+            }
+        }
+        *self = resized;
+    }
+
+    // Keeps only the part inside the rect, which is cut down to fit the image
+    pub fn crop(&mut self, x: u32, y: u32, width: u32, height: u32) {
+        let x = x.min(self.width);
+        let y = y.min(self.height);
+        let width = width.min(self.width - x);
+        let height = height.min(self.height - y);
+
+        let mut data = Vec::with_capacity(width as usize * height as usize * 4);
+        for row in y..y + height {
+            let start = self.index(x as i64, row as i64).unwrap_or(0);
+            data.extend_from_slice(&self.data[start..start + width as usize * 4]);
+        }
+        *self = Self {
+            data,
+            width,
+            height,
+        };
+    }
+
+    // Mirrors left to right
+    pub fn flip_horizontal(&mut self) {
+        let row_len = self.width as usize * 4;
+        if row_len == 0 {
+            return;
+        }
+        for row in self.data.chunks_exact_mut(row_len) {
+            let pixels: Vec<[u8; 4]> = row
+                .chunks_exact(4)
+                .rev()
+                .map(|p| [p[0], p[1], p[2], p[3]])
+                .collect();
+            row.copy_from_slice(pixels.as_flattened());
+        }
+    }
+
+    // Mirrors top to bottom
+    pub fn flip_vertical(&mut self) {
+        let row_len = self.width as usize * 4;
+        if row_len == 0 {
+            return;
+        }
+        let rows: Vec<&[u8]> = self.data.chunks_exact(row_len).rev().collect();
+        self.data = rows.concat();
+    }
+
+    // Turns a quarter turn clockwise, the width and height swap
+    pub fn rotate_cw(&mut self) {
+        let (w, h) = (self.width, self.height);
+        self.remap(h, w, |x, y| (y, h - 1 - x));
+    }
+
+    pub fn rotate_ccw(&mut self) {
+        let (w, h) = (self.width, self.height);
+        self.remap(h, w, |x, y| (w - 1 - y, x));
+    }
+
+    // Multiplies every pixel by the color, like the color passed to `draw_texture`
+    pub fn tint(&mut self, col: Color) {
+        let tint = LinearColor::from(col);
+        self.map_pixels(|p| (LinearColor::from(p) * tint).into());
+    }
+
+    // Multiplies the color of each pixel by its alpha, for blending set up for premultiplied
+    // colors. It's done in linear light, so the result is right once the GPU decodes the sRGB.
+    pub fn premultiply_alpha(&mut self) {
+        self.map_pixels(|p| Color::from(LinearColor::from(p).premultiplied()).with_alpha(p.3));
+    }
+
+    // Replaces every pixel, without blending
+    pub fn fill(&mut self, col: Color) {
+        *self = Self::new(self.width, self.height, col);
+    }
+
+    // Draws another image on top with its top left corner at (x, y), blending them
+    pub fn draw_image(&mut self, src: &Image, x: i32, y: i32) {
+        for sy in 0..src.height {
+            for sx in 0..src.width {
+                self.blend_pixel(
+                    x as i64 + sx as i64,
+                    y as i64 + sy as i64,
+                    src.pixel(sx, sy),
+                );
+            }
+        }
+    }
+
+    // The rect is cut down to fit the image, the colors are blended
+    pub fn draw_rect(&mut self, x: i32, y: i32, width: u32, height: u32, col: Color) {
+        let (x, y) = (x as i64, y as i64);
+        for py in y.max(0)..(y + height as i64).min(self.height as i64) {
+            for px in x.max(0)..(x + width as i64).min(self.width as i64) {
+                self.blend_pixel(px, py, col);
+            }
+        }
+    }
+
+    // A line one pixel thick, including both ends
+    pub fn draw_line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, col: Color) {
+        // Bresenham's, each pixel is drawn once so translucent lines are even
+        let (mut x, mut y) = (x1 as i64, y1 as i64);
+        let (x2, y2) = (x2 as i64, y2 as i64);
+        let dx = (x2 - x).abs();
+        let dy = -(y2 - y).abs();
+        let step_x = if x < x2 { 1 } else { -1 };
+        let step_y = if y < y2 { 1 } else { -1 };
+        let mut error = dx + dy;
+        loop {
+            self.blend_pixel(x, y, col);
+            if x == x2 && y == y2 {
+                break;
+            }
+            let e2 = 2 * error;
+            if e2 >= dy {
+                error += dy;
+                x += step_x;
+            }
+            if e2 <= dx {
+                error += dx;
+                y += step_y;
+            }
+        }
+    }
+
+    // A filled circle, every pixel whose middle is inside is drawn
+    pub fn draw_circle(&mut self, x: i32, y: i32, radius: u32, col: Color) {
+        let (cx, cy, r) = (x as i64, y as i64, radius as i64);
+        for py in (cy - r).max(0)..=(cy + r).min(self.height as i64 - 1) {
+            for px in (cx - r).max(0)..=(cx + r).min(self.width as i64 - 1) {
+                let (dx, dy) = (px - cx, py - cy);
+                if dx * dx + dy * dy <= r * r {
+                    self.blend_pixel(px, py, col);
+                }
+            }
+        }
+    }
+
+    pub fn encode_png(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.write_png(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    pub fn save_png(&self, filename: &str) -> anyhow::Result<()> {
+        let file = std::fs::File::create(filename)?;
+        self.write_png(std::io::BufWriter::new(file))
+    }
+
+    fn write_png(&self, out: impl Write) -> anyhow::Result<()> {
+        let mut encoder = png::Encoder::new(out, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_source_srgb(png::SrgbRenderingIntent::Perceptual);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.data)?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    fn index(&self, x: i64, y: i64) -> Option<usize> {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return None;
+        }
+        Some((y as usize * self.width as usize + x as usize) * 4)
+    }
+
+    // Clamped to the edges, so there must be at least one pixel
+    fn pixel(&self, x: u32, y: u32) -> Color {
+        let x = x.min(self.width - 1);
+        let y = y.min(self.height - 1);
+        self.get_pixel(x, y).unwrap_or(Color::TRANSPARENT)
+    }
+
+    fn blend_pixel(&mut self, x: i64, y: i64, col: Color) {
+        if let Some(i) = self.index(x, y) {
+            let p = &mut self.data[i..i + 4];
+            let Color(r, g, b, a) = col.blend_over(Color(p[0], p[1], p[2], p[3]));
+            p.copy_from_slice(&[r, g, b, a]);
+        }
+    }
+
+    fn sample_bilinear(&self, x: f32, y: f32) -> Color {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let corner = |dx: f32, dy: f32| {
+            // Past the edge the edge pixels carry on
+            let px = (x0 + dx).max(0.0) as u32;
+            let py = (y0 + dy).max(0.0) as u32;
+            LinearColor::from(self.pixel(px, py)).premultiplied()
+        };
+        // Mixed premultiplied, so transparent pixels don't darken their neighbours
+        let top = corner(0.0, 0.0) * (1.0 - fx) + corner(1.0, 0.0) * fx;
+        let bottom = corner(0.0, 1.0) * (1.0 - fx) + corner(1.0, 1.0) * fx;
+        let mixed = top * (1.0 - fy) + bottom * fy;
+        if mixed.3 <= 0.0 {
+            return Color::TRANSPARENT;
+        }
+        LinearColor(
+            mixed.0 / mixed.3,
+            mixed.1 / mixed.3,
+            mixed.2 / mixed.3,
+            mixed.3,
+        )
+        .into()
+    }
+
+    fn map_pixels(&mut self, f: impl Fn(Color) -> Color) {
+        for p in self.data.chunks_exact_mut(4) {
+            let Color(r, g, b, a) = f(Color(p[0], p[1], p[2], p[3]));
+            p.copy_from_slice(&[r, g, b, a]);
+        }
+    }
+
+    // A new image of the given size, each of its pixels copied from `from(x, y)`
+    fn remap(&mut self, width: u32, height: u32, from: impl Fn(u32, u32) -> (u32, u32)) {
+        let mut data = Vec::with_capacity(self.data.len());
+        for y in 0..height {
+            for x in 0..width {
+                let (sx, sy) = from(x, y);
+                let i = (sy as usize * self.width as usize + sx as usize) * 4;
+                data.extend_from_slice(&self.data[i..i + 4]);
+            }
+        }
+        *self = Self {
+            data,
+            width,
+            height,
+        };
+    }
+}
+
+impl From<Texture> for Image {
+    fn from(texture: Texture) -> Self {
+        Self {
+            data: texture.data,
+            width: texture.width,
+            height: texture.height,
+        }
+    }
+}
+
+impl From<Image> for Texture {
+    fn from(image: Image) -> Self {
+        Self {
+            data: image.data,
+            width: image.width,
+            height: image.height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every pixel is different, red is x and green is y
+    fn numbered(width: u32, height: u32) -> Image {
+        let mut image = Image::new(width, height, Color::BLACK);
+        for y in 0..height {
+            for x in 0..width {
+                image.set_pixel(x, y, Color(x as u8, y as u8, 7, 255));
+            }
+        }
+        image
+    }
+
+    fn at(x: u32, y: u32) -> Color {
+        Color(x as u8, y as u8, 7, 255)
+    }
+
+    fn pixels(image: &Image) -> Vec<Color> {
+        image
+            .data()
+            .chunks(4)
+            .map(|p| Color(p[0], p[1], p[2], p[3]))
+            .collect()
+    }
+
+    #[test]
+    fn from_raw_checks_the_size() {
+        assert!(Image::from_raw(2, 2, vec![0; 16]).is_ok());
+        assert!(Image::from_raw(2, 2, vec![0; 15]).is_err());
+        assert_eq!(Image::new(3, 2, Color::RED).data().len(), 24);
+        assert_eq!(numbered(2, 2).get_pixel(2, 0), None);
+    }
+
+    #[test]
+    fn rotate_cw_maps_indices() {
+        let mut image = numbered(3, 2);
+        image.rotate_cw();
+        assert_eq!((image.width(), image.height()), (2, 3));
+        for y in 0..3 {
+            for x in 0..2 {
+                assert_eq!(image.get_pixel(x, y), Some(at(y, 1 - x)), "({x}, {y})");
+            }
+        }
+        // The top left corner goes to the top right
+        assert_eq!(image.get_pixel(1, 0), Some(at(0, 0)));
+        assert_eq!(image.get_pixel(1, 2), Some(at(2, 0)));
+    }
+
+    #[test]
+    fn rotate_ccw_maps_indices() {
+        let mut image = numbered(3, 2);
+        image.rotate_ccw();
+        assert_eq!((image.width(), image.height()), (2, 3));
+        for y in 0..3 {
+            for x in 0..2 {
+                assert_eq!(image.get_pixel(x, y), Some(at(2 - y, x)), "({x}, {y})");
+            }
+        }
+        // The top left corner goes to the bottom left
+        assert_eq!(image.get_pixel(0, 2), Some(at(0, 0)));
+    }
+
+    #[test]
+    fn rotations_undo_each_other() {
+        let original = numbered(4, 3);
+        let mut image = original.clone();
+        image.rotate_cw();
+        image.rotate_ccw();
+        assert_eq!(image, original);
+        for _ in 0..4 {
+            image.rotate_cw();
+        }
+        assert_eq!(image, original);
+    }
+
+    #[test]
+    fn flips() {
+        let original = numbered(3, 2);
+        let mut image = original.clone();
+        image.flip_horizontal();
+        assert_eq!(image.get_pixel(0, 0), Some(at(2, 0)));
+        assert_eq!(image.get_pixel(1, 1), Some(at(1, 1)));
+        assert_eq!(image.get_pixel(2, 1), Some(at(0, 1)));
+        image.flip_horizontal();
+        assert_eq!(image, original);
+
+        image.flip_vertical();
+        assert_eq!(image.get_pixel(0, 0), Some(at(0, 1)));
+        assert_eq!(image.get_pixel(2, 1), Some(at(2, 0)));
+        image.flip_vertical();
+        assert_eq!(image, original);
+
+        // Nothing to flip
+        let mut empty = Image::new(0, 3, Color::RED);
+        empty.flip_horizontal();
+        empty.flip_vertical();
+        assert_eq!(empty.data().len(), 0);
+    }
+
+    #[test]
+    fn crop_is_clamped_to_the_image() {
+        let mut image = numbered(4, 3);
+        image.crop(1, 1, 2, 1);
+        assert_eq!(pixels(&image), [at(1, 1), at(2, 1)]);
+
+        // Running past the right and bottom edges
+        let mut image = numbered(4, 3);
+        image.crop(2, 1, 10, 10);
+        assert_eq!((image.width(), image.height()), (2, 2));
+        assert_eq!(pixels(&image), [at(2, 1), at(3, 1), at(2, 2), at(3, 2)]);
+
+        // Starting outside leaves nothing
+        let mut image = numbered(4, 3);
+        image.crop(4, 0, 1, 1);
+        assert_eq!((image.width(), image.height()), (0, 1));
+        let mut image = numbered(4, 3);
+        image.crop(9, 9, 1, 1);
+        assert_eq!((image.width(), image.height()), (0, 0));
+        assert!(image.data().is_empty());
+    }
+
+    #[test]
+    fn resize_nearest() {
+        let mut image = numbered(2, 2);
+        image.resize(4, 4, ResizeFilter::Nearest);
+        for y in 0..4 {
+            for x in 0..4 {
+                assert_eq!(image.get_pixel(x, y), Some(at(x / 2, y / 2)));
+            }
+        }
+
+        let mut image = numbered(4, 4);
+        image.resize(2, 2, ResizeFilter::Nearest);
+        assert_eq!(pixels(&image), [at(1, 1), at(3, 1), at(1, 3), at(3, 3)]);
+
+        image.resize(0, 5, ResizeFilter::Nearest);
+        assert_eq!((image.width(), image.height()), (0, 5));
+    }
+
+    #[test]
+    fn resize_bilinear() {
+        let mut image = Image::from_raw(2, 1, vec![0, 0, 0, 255, 255, 255, 255, 255]).unwrap();
+        image.resize(4, 1, ResizeFilter::Bilinear);
+        let p = pixels(&image);
+        // The edges carry on, the middle is mixed in linear light
+        assert_eq!(p[0], Color::BLACK);
+        assert_eq!(p[3], Color::WHITE);
+        assert_eq!(p[1], Color::BLACK.lerp(Color::WHITE, 0.25));
+        assert_eq!(p[2], Color::BLACK.lerp(Color::WHITE, 0.75));
+
+        // Transparent pixels fade their neighbours out without darkening them
+        let mut image = Image::from_raw(2, 1, vec![255, 0, 0, 255, 0, 0, 0, 0]).unwrap();
+        image.resize(4, 1, ResizeFilter::Bilinear);
+        for p in pixels(&image).into_iter().take(3) {
+            assert_eq!((p.0, p.1, p.2), (255, 0, 0), "{p:?}");
+        }
+        assert_eq!(image.get_pixel(3, 0), Some(Color::TRANSPARENT));
+    }
+
+    #[test]
+    fn tint_and_premultiply() {
+        let mut image = Image::new(1, 1, Color::WHITE);
+        image.tint(Color(255, 0, 0, 128));
+        assert_eq!(image.get_pixel(0, 0), Some(Color(255, 0, 0, 128)));
+
+        let mut image = numbered(3, 2);
+        image.tint(Color::WHITE);
+        assert_eq!(image, numbered(3, 2));
+
+        let mut image = Image::from_raw(
+            3,
+            1,
+            vec![255, 255, 255, 0, 255, 0, 0, 128, 10, 20, 30, 255],
+        )
+        .unwrap();
+        image.premultiply_alpha();
+        let p = pixels(&image);
+        assert_eq!(p[0], Color::TRANSPARENT);
+        // Half of the linear value, not of the sRGB one
+        assert_eq!(
+            p[1],
+            Color::from(LinearColor(128.0 / 255.0, 0.0, 0.0, 1.0)).with_alpha(128)
+        );
+        assert!(p[1].0 > 128);
+        assert_eq!(p[2], Color(10, 20, 30, 255));
+    }
+
+    #[test]
+    fn draw_image_blends_and_clips() {
+        let mut image = Image::new(3, 3, Color::BLACK);
+        let src = Image::new(2, 2, Color::WHITE);
+        image.draw_image(&src, 2, -1);
+        assert_eq!(image.get_pixel(2, 0), Some(Color::WHITE));
+        assert_eq!(image.get_pixel(1, 0), Some(Color::BLACK));
+        assert_eq!(image.get_pixel(2, 1), Some(Color::BLACK));
+
+        let translucent = Image::new(1, 1, Color(255, 255, 255, 128));
+        image.draw_image(&translucent, 0, 0);
+        assert_eq!(
+            image.get_pixel(0, 0),
+            Some(Color(255, 255, 255, 128).blend_over(Color::BLACK))
+        );
+    }
+
+    #[test]
+    fn draw_rect_clips() {
+        let mut image = Image::new(3, 2, Color::BLACK);
+        image.draw_rect(-1, -1, 2, 2, Color::RED);
+        assert_eq!(
+            pixels(&image),
+            [
+                Color::RED,
+                Color::BLACK,
+                Color::BLACK,
+                Color::BLACK,
+                Color::BLACK,
+                Color::BLACK
+            ]
+        );
+        image.draw_rect(1, 0, 100, 100, Color::BLUE);
+        assert_eq!(image.get_pixel(2, 1), Some(Color::BLUE));
+        assert_eq!(image.get_pixel(0, 1), Some(Color::BLACK));
+        // Entirely outside
+        image.draw_rect(5, 5, 2, 2, Color::GREEN);
+        assert!(!pixels(&image).contains(&Color::GREEN));
+    }
+
+    #[test]
+    fn draw_line_draws_each_pixel_once() {
+        let half = Color(255, 255, 255, 128);
+        let mut image = Image::new(6, 3, Color::TRANSPARENT);
+        image.draw_line(0, 0, 5, 2, half);
+        let drawn: Vec<Color> = pixels(&image)
+            .into_iter()
+            .filter(|p| *p != Color::TRANSPARENT)
+            .collect();
+        assert_eq!(drawn, [half; 6]);
+        assert_eq!(image.get_pixel(0, 0), Some(half));
+        assert_eq!(image.get_pixel(5, 2), Some(half));
+
+        // Without a pixel exactly half way between two rows, either way round is the same
+        let mut reversed = Image::new(6, 3, Color::TRANSPARENT);
+        reversed.draw_line(5, 2, 0, 0, half);
+        assert_eq!(reversed, image);
+
+        // Off the image is skipped
+        let mut image = Image::new(2, 2, Color::TRANSPARENT);
+        image.draw_line(-5, 0, 5, 0, Color::RED);
+        assert_eq!(pixels(&image)[..2], [Color::RED; 2]);
+    }
+
+    #[test]
+    fn draw_circle() {
+        let mut image = Image::new(5, 5, Color::BLACK);
+        image.draw_circle(2, 2, 1, Color::WHITE);
+        let drawn = pixels(&image)
+            .iter()
+            .filter(|&&p| p == Color::WHITE)
+            .count();
+        assert_eq!(drawn, 5);
+        assert_eq!(image.get_pixel(1, 1), Some(Color::BLACK));
+
+        // Clipped at the edges
+        let mut image = Image::new(3, 3, Color::BLACK);
+        image.draw_circle(0, 0, 2, Color::WHITE);
+        assert_eq!(image.get_pixel(0, 0), Some(Color::WHITE));
+        assert_eq!(image.get_pixel(2, 2), Some(Color::BLACK));
+        image.draw_circle(-10, -10, 2, Color::RED);
+    }
+
+    #[test]
+    fn png_round_trip() {
+        let mut image = numbered(5, 3);
+        image.set_pixel(1, 2, Color(200, 100, 50, 0));
+        image.set_pixel(4, 0, Color(1, 2, 3, 129));
+        let bytes = image.encode_png().unwrap();
+        assert_eq!(ImageFormat::detect(&bytes), Some(ImageFormat::Png));
+        assert_eq!(Image::from_memory(&bytes).unwrap(), image);
+    }
+}
@@ -97,6 +97,10 @@ impl RendiumInput {
         self.key_char.clone()
     }
 
+    pub fn get_keys_down(&self) -> Vec<Key> {
+        self.curr_keys.iter().cloned().collect()
+    }
+
     pub fn is_mouse_button_down(&self, mb: MouseButton) -> bool {
         self.curr_mouse_button.contains(&mb)
     }
@@ -114,11 +118,11 @@ impl RendiumInput {
     }
 
     pub fn get_mouse_pos(&self) -> Vector2 {
-        self.mouse_pos.clone()
+        self.mouse_pos
     }
 
     pub fn get_mouse_delta(&self) -> Vector2 {
-        self.mouse_pos_delta.clone()
+        self.mouse_pos_delta
     }
 
     pub fn get_scroll_delta(&self) -> f32 {
@@ -134,6 +138,7 @@ pub trait Input {
     fn is_key_pressed(&self, key: Key) -> bool;
     fn is_key_released(&self, key: Key) -> bool;
     fn get_key_char(&self) -> Option<String>;
+    fn get_keys_down(&self) -> Vec<Key>;
     fn is_mouse_button_down(&self, mb: MouseButton) -> bool;
     fn is_mouse_button_up(&self, mb: MouseButton) -> bool;
     fn is_mouse_button_pressed(&self, mb: MouseButton) -> bool;
@@ -164,6 +169,10 @@ impl Input for RendiumInstance {
         self.input.get_key_char()
     }
 
+    fn get_keys_down(&self) -> Vec<Key> {
+        self.input.get_keys_down()
+    }
+
     fn is_mouse_button_down(&self, mb: MouseButton) -> bool {
         self.input.is_mouse_button_down(mb)
     }
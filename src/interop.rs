@@ -0,0 +1,375 @@
+// Conversions between rendium::types and other math crates, each behind its own feature.
+// Colors convert to and from 0..1 floats, the way most math code stores them.
+// LinearColor keeps its floats as they are.
+use crate::types::{Color, LinearColor, Matrix3, Matrix4, Quaternion, Vector2, Vector3, Vector4};
+
+fn color_from_floats(c: [f32; 4]) -> Color {
+    let to_u8 = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    Color(to_u8(c[0]), to_u8(c[1]), to_u8(c[2]), to_u8(c[3]))
+}
+
+#[cfg(feature = "glam")]
+mod glam_conversions {
+    use super::*;
+
+    impl From<glam::Vec2> for Vector2 {
+        fn from(v: glam::Vec2) -> Self {
+            Self(v.x, v.y)
+        }
+    }
+
+    impl From<Vector2> for glam::Vec2 {
+        fn from(v: Vector2) -> Self {
+            glam::Vec2::new(v.0, v.1)
+        }
+    }
+
+    impl From<glam::Vec3> for Vector3 {
+        fn from(v: glam::Vec3) -> Self {
+            Self(v.x, v.y, v.z)
+        }
+    }
+
+    impl From<Vector3> for glam::Vec3 {
+        fn from(v: Vector3) -> Self {
+            glam::Vec3::new(v.0, v.1, v.2)
+        }
+    }
+
+    impl From<glam::Vec4> for Vector4 {
+        fn from(v: glam::Vec4) -> Self {
+            Self(v.x, v.y, v.z, v.w)
+        }
+    }
+
+    impl From<Vector4> for glam::Vec4 {
+        fn from(v: Vector4) -> Self {
+            glam::Vec4::new(v.0, v.1, v.2, v.3)
+        }
+    }
+
+    impl From<glam::Vec4> for Color {
+        fn from(v: glam::Vec4) -> Self {
+            color_from_floats(v.to_array())
+        }
+    }
+
+    impl From<Color> for glam::Vec4 {
+        fn from(c: Color) -> Self {
+            glam::Vec4::from_array(c.into())
+        }
+    }
+
+    impl From<glam::Vec4> for LinearColor {
+        fn from(v: glam::Vec4) -> Self {
+            v.to_array().into()
+        }
+    }
+
+    impl From<LinearColor> for glam::Vec4 {
+        fn from(c: LinearColor) -> Self {
+            glam::Vec4::from_array(c.into())
+        }
+    }
+
+    impl From<glam::Mat3> for Matrix3 {
+        fn from(m: glam::Mat3) -> Self {
+            Self(m.to_cols_array_2d())
+        }
+    }
+
+    impl From<Matrix3> for glam::Mat3 {
+        fn from(m: Matrix3) -> Self {
+            glam::Mat3::from_cols_array_2d(&m.0)
+        }
+    }
+
+    impl From<glam::Mat4> for Matrix4 {
+        fn from(m: glam::Mat4) -> Self {
+            Self(m.to_cols_array_2d())
+        }
+    }
+
+    impl From<Matrix4> for glam::Mat4 {
+        fn from(m: Matrix4) -> Self {
+            glam::Mat4::from_cols_array_2d(&m.0)
+        }
+    }
+
+    impl From<glam::Quat> for Quaternion {
+        fn from(q: glam::Quat) -> Self {
+            Self(q.x, q.y, q.z, q.w)
+        }
+    }
+
+    impl From<Quaternion> for glam::Quat {
+        fn from(q: Quaternion) -> Self {
+            glam::Quat::from_xyzw(q.0, q.1, q.2, q.3)
+        }
+    }
+}
+
+#[cfg(feature = "mint")]
+mod mint_conversions {
+    use super::*;
+
+    impl From<mint::Vector2<f32>> for Vector2 {
+        fn from(v: mint::Vector2<f32>) -> Self {
+            Self(v.x, v.y)
+        }
+    }
+
+    impl From<Vector2> for mint::Vector2<f32> {
+        fn from(v: Vector2) -> Self {
+            mint::Vector2 { x: v.0, y: v.1 }
+        }
+    }
+
+    impl From<mint::Point2<f32>> for Vector2 {
+        fn from(v: mint::Point2<f32>) -> Self {
+            Self(v.x, v.y)
+        }
+    }
+
+    impl From<Vector2> for mint::Point2<f32> {
+        fn from(v: Vector2) -> Self {
+            mint::Point2 { x: v.0, y: v.1 }
+        }
+    }
+
+    impl From<mint::Vector3<f32>> for Vector3 {
+        fn from(v: mint::Vector3<f32>) -> Self {
+            Self(v.x, v.y, v.z)
+        }
+    }
+
+    impl From<Vector3> for mint::Vector3<f32> {
+        fn from(v: Vector3) -> Self {
+            mint::Vector3 {
+                x: v.0,
+                y: v.1,
+                z: v.2,
+            }
+        }
+    }
+
+    impl From<mint::Point3<f32>> for Vector3 {
+        fn from(v: mint::Point3<f32>) -> Self {
+            Self(v.x, v.y, v.z)
+        }
+    }
+
+    impl From<Vector3> for mint::Point3<f32> {
+        fn from(v: Vector3) -> Self {
+            mint::Point3 {
+                x: v.0,
+                y: v.1,
+                z: v.2,
+            }
+        }
+    }
+
+    impl From<mint::Vector4<f32>> for Vector4 {
+        fn from(v: mint::Vector4<f32>) -> Self {
+            Self(v.x, v.y, v.z, v.w)
+        }
+    }
+
+    impl From<Vector4> for mint::Vector4<f32> {
+        fn from(v: Vector4) -> Self {
+            mint::Vector4 {
+                x: v.0,
+                y: v.1,
+                z: v.2,
+                w: v.3,
+            }
+        }
+    }
+
+    impl From<mint::Vector4<f32>> for Color {
+        fn from(v: mint::Vector4<f32>) -> Self {
+            color_from_floats([v.x, v.y, v.z, v.w])
+        }
+    }
+
+    impl From<Color> for mint::Vector4<f32> {
+        fn from(c: Color) -> Self {
+            <[f32; 4]>::from(c).into()
+        }
+    }
+
+    impl From<mint::Vector4<f32>> for LinearColor {
+        fn from(v: mint::Vector4<f32>) -> Self {
+            Self(v.x, v.y, v.z, v.w)
+        }
+    }
+
+    impl From<LinearColor> for mint::Vector4<f32> {
+        fn from(c: LinearColor) -> Self {
+            <[f32; 4]>::from(c).into()
+        }
+    }
+
+    // mint has no color type, so a u8 vector keeps the exact bytes
+    impl From<mint::Vector4<u8>> for Color {
+        fn from(v: mint::Vector4<u8>) -> Self {
+            Self(v.x, v.y, v.z, v.w)
+        }
+    }
+
+    impl From<Color> for mint::Vector4<u8> {
+        fn from(c: Color) -> Self {
+            mint::Vector4 {
+                x: c.0,
+                y: c.1,
+                z: c.2,
+                w: c.3,
+            }
+        }
+    }
+
+    impl From<mint::ColumnMatrix3<f32>> for Matrix3 {
+        fn from(m: mint::ColumnMatrix3<f32>) -> Self {
+            Self(m.into())
+        }
+    }
+
+    impl From<Matrix3> for mint::ColumnMatrix3<f32> {
+        fn from(m: Matrix3) -> Self {
+            m.0.into()
+        }
+    }
+
+    impl From<mint::ColumnMatrix4<f32>> for Matrix4 {
+        fn from(m: mint::ColumnMatrix4<f32>) -> Self {
+            Self(m.into())
+        }
+    }
+
+    impl From<Matrix4> for mint::ColumnMatrix4<f32> {
+        fn from(m: Matrix4) -> Self {
+            m.0.into()
+        }
+    }
+
+    impl From<mint::Quaternion<f32>> for Quaternion {
+        fn from(q: mint::Quaternion<f32>) -> Self {
+            Self(q.v.x, q.v.y, q.v.z, q.s)
+        }
+    }
+
+    impl From<Quaternion> for mint::Quaternion<f32> {
+        fn from(q: Quaternion) -> Self {
+            mint::Quaternion {
+                v: mint::Vector3 {
+                    x: q.0,
+                    y: q.1,
+                    z: q.2,
+                },
+                s: q.3,
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "glam"))]
+mod glam_tests {
+    use super::*;
+
+    #[test]
+    fn vectors_round_trip() {
+        let v2 = Vector2(1.5, -2.0);
+        let v3 = Vector3(1.5, -2.0, 3.25);
+        let v4 = Vector4(1.5, -2.0, 3.25, -4.0);
+        assert_eq!(glam::Vec2::from(v2), glam::Vec2::new(1.5, -2.0));
+        assert_eq!(Vector2::from(glam::Vec2::from(v2)), v2);
+        assert_eq!(Vector3::from(glam::Vec3::from(v3)), v3);
+        assert_eq!(Vector4::from(glam::Vec4::from(v4)), v4);
+    }
+
+    #[test]
+    fn colors_round_trip() {
+        let c = Color(12, 128, 200, 255);
+        assert_eq!(Color::from(glam::Vec4::from(c)), c);
+        assert_eq!(glam::Vec4::from(Color::WHITE), glam::Vec4::ONE);
+    }
+
+    #[test]
+    fn out_of_range_colors_round_and_clamp() {
+        let c = Color::from(glam::Vec4::new(1.5, -0.25, 0.5, 0.999));
+        assert_eq!(c, Color(255, 0, 128, 255));
+    }
+
+    #[test]
+    fn linear_colors_keep_their_floats() {
+        // Out of range values are fine in linear space, e.g. for HDR
+        let c = LinearColor(2.0, -0.5, 0.25, 1.0);
+        assert_eq!(glam::Vec4::from(c), glam::Vec4::new(2.0, -0.5, 0.25, 1.0));
+        assert_eq!(LinearColor::from(glam::Vec4::from(c)), c);
+    }
+}
+
+#[cfg(all(test, feature = "mint"))]
+mod mint_tests {
+    use super::*;
+
+    #[test]
+    fn vectors_round_trip() {
+        let v2 = Vector2(1.5, -2.0);
+        let v3 = Vector3(1.5, -2.0, 3.25);
+        let v4 = Vector4(1.5, -2.0, 3.25, -4.0);
+        assert_eq!(Vector2::from(mint::Vector2::from(v2)), v2);
+        assert_eq!(Vector2::from(mint::Point2::from(v2)), v2);
+        assert_eq!(Vector3::from(mint::Vector3::from(v3)), v3);
+        assert_eq!(Vector3::from(mint::Point3::from(v3)), v3);
+        assert_eq!(Vector4::from(mint::Vector4::from(v4)), v4);
+    }
+
+    #[test]
+    fn colors_round_trip() {
+        let c = Color(12, 128, 200, 255);
+        assert_eq!(Color::from(mint::Vector4::<f32>::from(c)), c);
+        assert_eq!(Color::from(mint::Vector4::<u8>::from(c)), c);
+    }
+
+    #[test]
+    fn out_of_range_colors_round_and_clamp() {
+        let v = mint::Vector4 {
+            x: 1.5,
+            y: -0.25,
+            z: 0.5,
+            w: 0.999,
+        };
+        assert_eq!(Color::from(v), Color(255, 0, 128, 255));
+    }
+
+    #[test]
+    fn linear_colors_keep_their_floats() {
+        let c = LinearColor(2.0, -0.5, 0.25, 1.0);
+        assert_eq!(LinearColor::from(mint::Vector4::from(c)), c);
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+    use serde::{Serialize, de::DeserializeOwned};
+    use std::fmt::Debug;
+
+    fn round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: T) {
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(serde_json::from_str::<T>(&json).unwrap(), value, "{json}");
+    }
+
+    #[test]
+    fn types_round_trip() {
+        round_trip(Vector2(1.5, -2.0));
+        round_trip(Vector3(1.5, -2.0, 3.25));
+        round_trip(Vector4(1.5, -2.0, 3.25, -4.0));
+        round_trip(Color(12, 128, 200, 255));
+        round_trip(LinearColor(2.0, -0.5, 0.25, 1.0));
+        round_trip(Matrix3::rotation(0.5));
+        round_trip(Matrix4::translation(Vector3(1.0, 2.0, 3.0)));
+        round_trip(Quaternion(0.0, 0.0, 0.0, 1.0));
+    }
+}
@@ -14,25 +14,20 @@ use winit::{
 
 struct State {
     window: Arc<Window>,
-    device: wgpu::Device,
-    queue: wgpu::Queue,
     size: winit::dpi::PhysicalSize<u32>,
     surface: wgpu::Surface<'static>,
     surface_format: wgpu::TextureFormat,
-    render_pipeline: wgpu::RenderPipeline,
+    renderer: Renderer,
 }
 
 impl State {
-    async fn new(window: Arc<Window>) -> anyhow::Result<Self> {
+    async fn new(window: Arc<Window>, linear_workflow: bool) -> anyhow::Result<Self> {
         // Create a wgpu instance
         let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
         // Find a GPU
         let adapter = instance
             .request_adapter(&wgpu::RequestAdapterOptions::default())
             .await?;
-        let (device, queue) = adapter
-            .request_device(&wgpu::DeviceDescriptor::default())
-            .await?;
 
         let size = window.inner_size();
 
@@ -40,7 +35,143 @@ impl State {
         let surface = instance.create_surface(window.clone())?;
         // No idea on these two
         let cap = surface.get_capabilities(&adapter);
-        let surface_format = cap.formats[0];
+        let surface_format = cap
+            .formats
+            .iter()
+            .copied()
+            .find(|f| f.is_srgb())
+            .unwrap_or(cap.formats[0]);
+        let target_format = if linear_workflow {
+            surface_format.add_srgb_suffix()
+        } else {
+            surface_format.remove_srgb_suffix()
+        };
+
+        let renderer = Renderer::new(&adapter, target_format, linear_workflow).await?;
+
+        let state = Self {
+            window,
+            size,
+            surface_format,
+            surface,
+            renderer,
+        };
+
+        state.configure_surface();
+
+        Ok(state)
+    }
+
+    fn get_window(&self) -> &Window {
+        &self.window
+    }
+
+    fn configure_surface(&self) {
+        let surface_config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: self.surface_format,
+            view_formats: vec![self.renderer.target_format],
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            width: self.size.width,
+            height: self.size.height,
+            desired_maximum_frame_latency: 2,
+            present_mode: wgpu::PresentMode::AutoVsync,
+        };
+        self.surface
+            .configure(&self.renderer.device, &surface_config);
+    }
+
+    fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
+        self.size = new_size;
+
+        self.configure_surface();
+    }
+
+    fn render(
+        &mut self,
+        draw_handle: &RendiumDrawHandle,
+        overlay: Option<&RendiumDrawHandle>,
+        color: types::Color,
+    ) -> debug::FrameStats {
+        let handles: Vec<&RendiumDrawHandle> = std::iter::once(draw_handle)
+            .chain(overlay)
+            .filter(|d| !d.indices.is_empty() || !d.sprites.is_empty())
+            .collect();
+        if handles.is_empty() {
+            return debug::FrameStats::default();
+        }
+
+        let surface_texture = match self.surface.get_current_texture() {
+            Ok(texture) => texture,
+            Err(wgpu::SurfaceError::Outdated) => {
+                self.configure_surface();
+                return debug::FrameStats::default();
+            }
+            Err(e) => {
+                eprintln!("Failed to get surface texture: {:?}", e);
+                return debug::FrameStats::default();
+            }
+        };
+        let texture_view = surface_texture
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor {
+                format: Some(self.renderer.target_format),
+                ..Default::default()
+            });
+
+        let renderer = &self.renderer;
+        let mut encoder = renderer.device.create_command_encoder(&Default::default());
+        let batch_counts = renderer.record(
+            &mut encoder,
+            &texture_view,
+            &renderer.pipelines,
+            &handles,
+            renderer.clear_color(color),
+        );
+
+        renderer.queue.submit([encoder.finish()]);
+        self.window.pre_present_notify();
+        surface_texture.present();
+
+        // Only count the user's geometry, not the overlay
+        if std::ptr::eq(handles[0], draw_handle) {
+            debug::FrameStats {
+                draw_calls: batch_counts[0],
+                vertices: draw_handle.vertices.len() as u32,
+                indices: draw_handle.indices.len() as u32,
+                sprites: draw_handle.sprites.len() as u32,
+            }
+        } else {
+            debug::FrameStats::default()
+        }
+    }
+}
+
+// Everything needed to draw, without a window. `State` puts one on a window's surface.
+struct Renderer {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    // The format the frame is drawn in, the sRGB version of the surface format in the linear workflow
+    target_format: wgpu::TextureFormat,
+    linear_workflow: bool,
+    pipelines: Pipelines,
+    // Kept to make pipelines for other targets, like textures drawn into
+    shader: wgpu::ShaderModule,
+    pipeline_layout: wgpu::PipelineLayout,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+    globals_bind_group_layout: wgpu::BindGroupLayout,
+    white_texture: texture::GPUTexture,
+}
+
+impl Renderer {
+    async fn new(
+        adapter: &wgpu::Adapter,
+        target_format: wgpu::TextureFormat,
+        linear_workflow: bool,
+    ) -> anyhow::Result<Self> {
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor::default())
+            .await?;
 
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Shader"),
@@ -69,38 +200,340 @@ impl State {
             ],
         });
 
-        let render_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Render Pipeline Layout"),
-                bind_group_layouts: &[&bind_group_layout],
-                push_constant_ranges: &[],
+        // The view-projection matrix of each draw handle, picked with a dynamic offset
+        let globals_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Globals Bind Group Layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: wgpu::BufferSize::new(GLOBALS_SIZE),
+                    },
+                    count: None,
+                }],
             });
 
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Render Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout, &globals_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipelines = Pipelines::new(
+            &device,
+            &shader,
+            &pipeline_layout,
+            target_format,
+            linear_workflow,
+        );
+
+        let white_texture = texture::create_white_texture(&device, &queue, &bind_group_layout);
+
+        Ok(Self {
+            queue,
+            device,
+            target_format,
+            linear_workflow,
+            pipelines,
+            shader,
+            pipeline_layout,
+            texture_bind_group_layout: bind_group_layout,
+            globals_bind_group_layout,
+            white_texture,
+        })
+    }
+
+    // Colors are sRGB, so an sRGB target wants the clear color in linear light
+    // to end up with the same pixels a fill of that color gives
+    fn clear_color(&self, color: types::Color) -> wgpu::Color {
+        if self.linear_workflow {
+            types::LinearColor::from(color).into()
+        } else {
+            color.into()
+        }
+    }
+
+    // Draws a handle into a new texture cleared to `clear`, and reads it back.
+    // The pixels are sRGB with straight alpha, the same as a loaded PNG.
+    pub(crate) fn render_to_texture(
+        &self,
+        d: &RendiumDrawHandle,
+        width: u32,
+        height: u32,
+        clear: types::Color,
+    ) -> anyhow::Result<texture::Texture> {
+        anyhow::ensure!(width > 0 && height > 0, "can't render to an empty texture");
+        let limit = self.device.limits().max_texture_dimension_2d;
+        anyhow::ensure!(
+            width <= limit && height <= limit,
+            "{width}x{height} is bigger than the biggest texture the GPU can make, {limit}x{limit}"
+        );
+
+        let format = if self.linear_workflow {
+            wgpu::TextureFormat::Rgba8UnormSrgb
+        } else {
+            wgpu::TextureFormat::Rgba8Unorm
+        };
+        let pipelines = Pipelines::new(
+            &self.device,
+            &self.shader,
+            &self.pipeline_layout,
+            format,
+            self.linear_workflow,
+        );
+
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let target = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Render Target"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Rows of a copy have to line up to 256 bytes
+        let padded_row = (width * 4).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback Buffer"),
+            size: padded_row as u64 * height as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        self.record(
+            &mut encoder,
+            &view,
+            &pipelines,
+            &[d],
+            self.clear_color(clear),
+        );
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: &target,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &readback,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            size,
+        );
+        self.queue.submit([encoder.finish()]);
+
+        let slice = readback.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::PollType::Wait)?;
+        receiver.recv()??;
+
+        let mut data = Vec::with_capacity(width as usize * height as usize * 4);
+        for row in slice.get_mapped_range().chunks(padded_row as usize) {
+            data.extend_from_slice(&row[..width as usize * 4]);
+        }
+        readback.unmap();
+
+        // Blending onto transparent leaves the colors multiplied by their alpha,
+        // in linear light when the target is sRGB
+        for pixel in data.chunks_exact_mut(4) {
+            let alpha = pixel[3] as f32 / 255.0;
+            if alpha == 0.0 || alpha == 1.0 {
+                continue;
+            }
+            for c in &mut pixel[..3] {
+                *c = if self.linear_workflow {
+                    let linear = color::srgb_to_linear(*c as f32 / 255.0) / alpha;
+                    (color::linear_to_srgb(linear.min(1.0)) * 255.0).round() as u8
+                } else {
+                    (*c as f32 / alpha).round().min(255.0) as u8
+                };
+            }
+        }
+
+        Ok(texture::Texture {
+            data,
+            width,
+            height,
+        })
+    }
+
+    // Records the handles into a render pass on `target`, and returns how many draw calls each one took
+    fn record(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        pipelines: &Pipelines,
+        handles: &[&RendiumDrawHandle],
+        clear_color: wgpu::Color,
+    ) -> Vec<u32> {
+        let buffers: Vec<HandleBuffers> = handles
+            .iter()
+            .map(|d| {
+                HandleBuffers::new(
+                    &self.device,
+                    &self.queue,
+                    &self.texture_bind_group_layout,
+                    d,
+                )
+            })
+            .collect();
+
+        // Every view of every handle gets its own slot, aligned so it can be bound with a dynamic offset
+        let alignment = self.device.limits().min_uniform_buffer_offset_alignment as usize;
+        let globals_stride = (GLOBALS_SIZE as usize).next_multiple_of(alignment);
+        let views: Vec<&types::Matrix4> = handles.iter().flat_map(|d| &d.views).collect();
+        let mut globals = vec![0u8; globals_stride * views.len()];
+        for (i, view) in views.iter().enumerate() {
+            globals[i * globals_stride..i * globals_stride + GLOBALS_SIZE as usize]
+                .copy_from_slice(bytemuck::bytes_of(*view));
+        }
+        let globals_buffer = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Globals Buffer"),
+                contents: &globals,
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+        let globals_bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Globals Bind Group"),
+            layout: &self.globals_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer: &globals_buffer,
+                    offset: 0,
+                    size: wgpu::BufferSize::new(GLOBALS_SIZE),
+                }),
+            }],
+        });
+
+        let mut renderpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                depth_slice: None,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(clear_color),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        renderpass.set_pipeline(&pipelines.main);
+
+        let mut batch_counts = Vec::with_capacity(handles.len());
+        let mut first_view = 0;
+        for (d, buffers) in handles.iter().zip(&buffers) {
+            let batches = d.batches();
+            for batch in &batches {
+                let texture = match (batch.tex_index, gradient::ramp_texture(batch.tex_index)) {
+                    (0, _) => &self.white_texture,
+                    (_, Some(ramps)) => buffers.gradients.get(ramps).unwrap_or(&self.white_texture),
+                    (i, None) => d
+                        .textures
+                        .values()
+                        .nth(i as usize - 1)
+                        .unwrap_or(&self.white_texture),
+                };
+                let globals_offset = ((first_view + batch.view as usize) * globals_stride) as u32;
+
+                match (&batch.kind, &buffers.geometry, &buffers.instances) {
+                    (BatchKind::Geometry(indices), Some((vertex_buffer, index_buffer)), _) => {
+                        renderpass.set_pipeline(&pipelines.main);
+                        renderpass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                        renderpass
+                            .set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                        renderpass.set_bind_group(0, &texture.bind_group, &[]);
+                        renderpass.set_bind_group(1, &globals_bind_group, &[globals_offset]);
+                        renderpass.draw_indexed(indices.clone(), 0, 0..1);
+                    }
+                    (BatchKind::Sprites(instances), _, Some(instance_buffer)) => {
+                        renderpass.set_pipeline(&pipelines.sprite);
+                        renderpass.set_vertex_buffer(0, instance_buffer.slice(..));
+                        renderpass.set_bind_group(0, &texture.bind_group, &[]);
+                        renderpass.set_bind_group(1, &globals_bind_group, &[globals_offset]);
+                        renderpass.draw(0..6, instances.clone());
+                    }
+                    _ => (),
+                }
+            }
+            first_view += d.views.len();
+            batch_counts.push(batches.len() as u32);
+        }
+
+        batch_counts
+    }
+}
+
+// The pipelines for drawing into one format
+struct Pipelines {
+    main: wgpu::RenderPipeline,
+    sprite: wgpu::RenderPipeline,
+}
+
+impl Pipelines {
+    // `linear_target` is whether the format encodes to sRGB by itself
+    fn new(
+        device: &wgpu::Device,
+        shader: &wgpu::ShaderModule,
+        layout: &wgpu::PipelineLayout,
+        format: wgpu::TextureFormat,
+        linear_target: bool,
+    ) -> Self {
+        // Tells fs_main whether the target encodes to sRGB by itself
+        let constants = [("LINEAR_TARGET", if linear_target { 1.0 } else { 0.0 })];
+
         // This is so many nesting
         let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Render Pipeline"),
-            layout: Some(&render_pipeline_layout),
+            layout: Some(layout),
             vertex: wgpu::VertexState {
-                module: &shader,
+                module: shader,
                 entry_point: Some("vs_main"),
                 buffers: &[Vertex::desc()],
                 compilation_options: wgpu::PipelineCompilationOptions::default(),
             },
             fragment: Some(wgpu::FragmentState {
-                module: &shader,
+                module: shader,
                 entry_point: Some("fs_main"),
                 targets: &[Some(wgpu::ColorTargetState {
-                    format: surface_format,
+                    format,
                     blend: Some(wgpu::BlendState::ALPHA_BLENDING),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                compilation_options: wgpu::PipelineCompilationOptions {
+                    constants: &constants,
+                    ..Default::default()
+                },
             }),
             primitive: wgpu::PrimitiveState {
                 topology: wgpu::PrimitiveTopology::TriangleList,
                 strip_index_format: None,
                 front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
+                // Flipping the y axis flips the winding, so nothing is culled in 2D
+                cull_mode: None,
                 polygon_mode: wgpu::PolygonMode::Fill,
                 unclipped_depth: false,
                 conservative: false,
@@ -115,143 +548,137 @@ impl State {
             cache: None,
         });
 
-        let state = Self {
-            window,
-            queue,
-            device,
-            surface_format,
-            size,
-            render_pipeline,
-            surface,
-        };
-
-        state.configure_surface();
-
-        Ok(state)
-    }
-
-    fn get_window(&self) -> &Window {
-        &self.window
-    }
-
-    fn configure_surface(&self) {
-        let surface_config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: self.surface_format,
-            view_formats: vec![self.surface_format.add_srgb_suffix()],
-            alpha_mode: wgpu::CompositeAlphaMode::Auto,
-            width: self.size.width,
-            height: self.size.height,
-            desired_maximum_frame_latency: 2,
-            present_mode: wgpu::PresentMode::AutoVsync,
-        };
-        self.surface.configure(&self.device, &surface_config);
-    }
-
-    fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
-        self.size = new_size;
+        let sprite_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Sprite Pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_sprite"),
+                buffers: &[sprite::SpriteInstance::desc()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions {
+                    constants: &constants,
+                    ..Default::default()
+                },
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                // A negative size flips the sprite, which also flips the winding
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
 
-        self.configure_surface();
+        Self {
+            main: render_pipeline,
+            sprite: sprite_pipeline,
+        }
     }
+}
 
-    fn render(&mut self, draw_handle: &RendiumDrawHandle, color: types::Color) {
-        if draw_handle.vertices.is_empty() || draw_handle.indices.is_empty() {
-            return;
-        }
+// GPU buffers for one draw handle, made fresh every frame
+struct HandleBuffers {
+    geometry: Option<(wgpu::Buffer, wgpu::Buffer)>,
+    instances: Option<wgpu::Buffer>,
+    gradients: Vec<texture::GPUTexture>,
+}
 
-        let vertex_buffer = self
-            .device
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+impl HandleBuffers {
+    fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        d: &RendiumDrawHandle,
+    ) -> Self {
+        let geometry = (!d.vertices.is_empty() && !d.indices.is_empty()).then(|| {
+            let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: Some("Vertex Buffer"),
-                contents: bytemuck::cast_slice(&draw_handle.vertices),
+                contents: bytemuck::cast_slice(&d.vertices),
                 usage: wgpu::BufferUsages::VERTEX,
             });
-        let index_buffer = self
-            .device
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: Some("Index Buffer"),
-                contents: bytemuck::cast_slice(&draw_handle.indices),
+                contents: bytemuck::cast_slice(&d.indices),
                 usage: wgpu::BufferUsages::INDEX,
             });
-
-        let surface_texture = match self.surface.get_current_texture() {
-            Ok(texture) => texture,
-            Err(wgpu::SurfaceError::Outdated) => {
-                self.configure_surface();
-                return;
-            }
-            Err(e) => {
-                eprintln!("Failed to get surface texture: {:?}", e);
-                return;
-            }
-        };
-        let texture_view = surface_texture
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor {
-                format: Some(self.surface_format.add_srgb_suffix()),
-                ..Default::default()
-            });
-
-        let mut encoder = self.device.create_command_encoder(&Default::default());
-
-        let mut renderpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("Render Pass"),
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &texture_view,
-                depth_slice: None,
-                resolve_target: None,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(color.into()),
-                    store: wgpu::StoreOp::Store,
-                },
-            })],
-            depth_stencil_attachment: None,
-            timestamp_writes: None,
-            occlusion_query_set: None,
+            (vertex_buffer, index_buffer)
         });
 
-        renderpass.set_pipeline(&self.render_pipeline);
-        renderpass.set_vertex_buffer(0, vertex_buffer.slice(..));
-        renderpass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-
-        if let Some(texture) = draw_handle
-            .vertices
-            .first()
-            .map(|v| v.tex_index)
-            .and_then(|idx| {
-                draw_handle
-                    .textures
-                    .iter()
-                    .nth(idx as usize)
-                    .map(|(_, v)| v)
+        let instances = (!d.sprites.is_empty()).then(|| {
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Sprite Instance Buffer"),
+                contents: bytemuck::cast_slice(&d.sprites),
+                usage: wgpu::BufferUsages::VERTEX,
             })
-        {
-            renderpass.set_bind_group(0, &texture.bind_group, &[]);
-        }
+        });
 
-        renderpass.draw_indexed(0..draw_handle.indices.len() as u32, 0, 0..1);
+        let gradients =
+            gradient::create_ramp_textures(device, queue, bind_group_layout, &d.gradient_ramps);
 
-        drop(renderpass);
-        self.queue.submit([encoder.finish()]);
-        self.window.pre_present_notify();
-        surface_texture.present();
+        Self {
+            geometry,
+            instances,
+            gradients,
+        }
     }
 }
 
+// A single mat4x4<f32>
+const GLOBALS_SIZE: u64 = std::mem::size_of::<types::Matrix4>() as u64;
+
+// Maps pixel coordinates to NDC. The origin is the top left corner,
+// or the bottom left one with y going up if `y_up` is set.
+fn screen_projection(size: PhysicalSize<u32>, y_up: bool) -> types::Matrix4 {
+    let w = size.width.max(1) as f32;
+    let h = size.height.max(1) as f32;
+    let (bottom, top) = if y_up { (0.0, h) } else { (h, 0.0) };
+
+    // Left-handed with a 0..1 depth range, so z goes through untouched
+    types::Matrix4::orthographic_lh(0.0, w, bottom, top, 0.0, 1.0)
+}
+
+type Callback = Box<dyn FnMut(&mut RendiumInstance)>;
+type SetupCallback = Box<dyn FnOnce(&mut RendiumInstance)>;
+
 pub struct RendiumInstance {
     state: Option<State>,
     size: winit::dpi::PhysicalSize<u32>,
     title: String,
-    callback: Box<dyn FnMut(&mut Self)>,
-    setup_callback: Option<Box<dyn FnOnce(&mut RendiumInstance)>>,
+    callback: Callback,
+    setup_callback: Option<SetupCallback>,
     input: input::RendiumInput,
     delta_time: Duration,
     last_frame_time: Instant,
     texture_storage: HashMap<String, texture::GPUTexture>,
+    debug_overlay: debug::DebugOverlay,
+    frame_stats: debug::FrameStats,
+    y_up: bool,
+    linear_workflow: bool,
 }
 
 impl RendiumInstance {
-    pub fn new(size: PhysicalSize<u32>, title: String, f: Box<dyn FnMut(&mut Self)>) -> Self {
+    pub fn new(size: PhysicalSize<u32>, title: String, f: Callback) -> Self {
         Self {
             size,
             title,
@@ -262,18 +689,68 @@ impl RendiumInstance {
             delta_time: Duration::ZERO,
             last_frame_time: Instant::now(),
             texture_storage: HashMap::new(),
+            debug_overlay: debug::DebugOverlay::default(),
+            frame_stats: debug::FrameStats::default(),
+            y_up: false,
+            linear_workflow: true,
         }
     }
 
     pub fn draw<F: FnOnce(&mut RendiumDrawHandle)>(&mut self, color: types::Color, f: F) {
         let mut draw_handle = RendiumDrawHandle::new(self.size, self.texture_storage.clone());
+        draw_handle.views[0] = screen_projection(self.size, self.y_up);
 
         f(&mut draw_handle);
+
+        let overlay = self.debug_overlay.is_enabled().then(|| {
+            let scale_factor = self
+                .state
+                .as_ref()
+                .map(|s| s.window.scale_factor())
+                .unwrap_or(1.0);
+            let mut overlay = RendiumDrawHandle::new(self.size, self.texture_storage.clone());
+            self.debug_overlay
+                .draw(&mut overlay, self.frame_stats, &self.input, scale_factor);
+            overlay
+        });
+
         if let Some(state) = &mut self.state {
-            state.render(&draw_handle, color);
+            self.frame_stats = state.render(&draw_handle, overlay.as_ref(), color);
         }
     }
 
+    // Draws into a new texture of the given size instead of the window, and stores it under
+    // `tex_name` for `draw_texture` and sprites. Where nothing is drawn the texture is transparent.
+    pub fn draw_to_texture<F: FnOnce(&mut RendiumDrawHandle)>(
+        &mut self,
+        tex_name: &str,
+        width: u32,
+        height: u32,
+        f: F,
+    ) -> anyhow::Result<()> {
+        let Some(state) = &self.state else {
+            return Ok(());
+        };
+
+        let size = PhysicalSize::new(width, height);
+        let mut draw_handle = RendiumDrawHandle::new(size, self.texture_storage.clone());
+        draw_handle.views[0] = screen_projection(size, self.y_up);
+        f(&mut draw_handle);
+
+        let renderer = &state.renderer;
+        let texture =
+            renderer.render_to_texture(&draw_handle, width, height, types::Color::TRANSPARENT)?;
+        let gpu_texture = texture::create_gpu_texture(
+            &renderer.device,
+            &renderer.queue,
+            &renderer.texture_bind_group_layout,
+            &texture,
+        );
+        self.texture_storage
+            .insert(tex_name.to_string(), gpu_texture);
+        Ok(())
+    }
+
     pub fn get_window_size(&self) -> (u32, u32) {
         (self.size.width, self.size.height)
     }
@@ -281,6 +758,26 @@ impl RendiumInstance {
     pub fn delta_time(&self) -> Duration {
         self.delta_time
     }
+
+    pub fn is_y_up(&self) -> bool {
+        self.y_up
+    }
+
+    pub fn set_y_up(&mut self, y_up: bool) {
+        self.y_up = y_up;
+    }
+
+    pub fn is_linear_workflow(&self) -> bool {
+        self.linear_workflow
+    }
+
+    pub fn frame_stats(&self) -> debug::FrameStats {
+        self.frame_stats
+    }
+
+    pub fn debug_overlay(&mut self) -> &mut debug::DebugOverlay {
+        &mut self.debug_overlay
+    }
 }
 
 impl ApplicationHandler for RendiumInstance {
@@ -295,7 +792,7 @@ impl ApplicationHandler for RendiumInstance {
                 .unwrap(),
         );
 
-        let state = pollster::block_on(State::new(window.clone())).unwrap();
+        let state = pollster::block_on(State::new(window.clone(), self.linear_workflow)).unwrap();
         self.state = Some(state);
 
         let setup_callback = self.setup_callback.take();
@@ -323,6 +820,8 @@ impl ApplicationHandler for RendiumInstance {
                 self.delta_time = now - self.last_frame_time;
                 self.last_frame_time = now;
 
+                self.debug_overlay.update(&self.input, self.delta_time);
+
                 // Since this is Rust, I have to jump through some hoops to make this work
                 // (self.callback)(self);
 
@@ -400,42 +899,59 @@ impl ApplicationHandler for RendiumInstance {
 #[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct Vertex {
     pub position: [f32; 3],
+    // sRGB in 0..1 like `Color`, the vertex shader converts it to linear
     pub color: [f32; 4],
     pub uv: [f32; 2],
     pub tex_index: u32,
+    // A `ShapeKind`, tells the fragment shader what `params` mean
+    pub kind: u32,
+    pub params: [f32; 4],
+}
+
+// What fs_main does with a vertex, the values match the SHAPE_ constants in default.wgsl
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ShapeKind {
+    // Just the texture times the color
+    Plain = 0,
+    // params.x is how far along the gradient, params.z the gradient's row in the ramp texture
+    LinearGradient = 1,
+    // params.xy is the position relative to the center in radii, params.z the row.
+    // A params.w above 0 cuts it to an anti-aliased circle.
+    RadialGradient = 2,
+    // The rest are anti-aliased in the fragment shader. uv is the position relative to
+    // the center of the shape and params.w the outline thickness, 0 fills the shape.
+    // params.x is the radius
+    Circle = 3,
+    // params.xy is half the size and params.z the corner radius. Outlines go inside the
+    // edge instead of centered on it, so their corners keep the radius.
+    RoundedRect = 4,
+    // A segment along the x axis, params.x is half its length and params.y the radius
+    Capsule = 5,
+    // params.xyz are the distances to each edge, positive inside
+    Triangle = 6,
+    // params.xy are the radii
+    Ellipse = 7,
+    // A ring around the x axis, params.x is the radius of its middle, params.y half its
+    // thickness and params.z the angle it goes to on each side, PI for a full ring
+    Arc = 8,
 }
 
 impl Vertex {
     fn desc() -> wgpu::VertexBufferLayout<'static> {
+        const ATTRIBUTES: [wgpu::VertexAttribute; 6] = wgpu::vertex_attr_array![
+            0 => Float32x3,
+            1 => Float32x4,
+            2 => Float32x2,
+            3 => Uint32,
+            4 => Uint32,
+            5 => Float32x4,
+        ];
+
         wgpu::VertexBufferLayout {
             array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
             step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &[
-                wgpu::VertexAttribute {
-                    offset: 0,
-                    shader_location: 0,
-                    format: wgpu::VertexFormat::Float32x3,
-                },
-                wgpu::VertexAttribute {
-                    offset: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
-                    shader_location: 1,
-                    format: wgpu::VertexFormat::Float32x4,
-                },
-                wgpu::VertexAttribute {
-                    offset: (std::mem::size_of::<[f32; 3]>() + std::mem::size_of::<[f32; 4]>())
-                        as wgpu::BufferAddress,
-                    shader_location: 2,
-                    format: wgpu::VertexFormat::Float32x2,
-                },
-                wgpu::VertexAttribute {
-                    offset: (std::mem::size_of::<[f32; 3]>()
-                        + std::mem::size_of::<[f32; 4]>()
-                        + std::mem::size_of::<[f32; 2]>())
-                        as wgpu::BufferAddress,
-                    shader_location: 3,
-                    format: wgpu::VertexFormat::Uint32,
-                },
-            ],
+            attributes: &ATTRIBUTES,
         }
     }
 
@@ -445,6 +961,8 @@ impl Vertex {
             color: col.into(),
             uv,
             tex_index,
+            kind: ShapeKind::Plain as u32,
+            params: [0.0; 4],
         }
     }
 }
@@ -452,7 +970,10 @@ impl Vertex {
 pub struct RendiumBuilder {
     size: winit::dpi::PhysicalSize<u32>,
     title: String,
-    setup_callback: Option<Box<dyn FnOnce(&mut RendiumInstance)>>,
+    setup_callback: Option<SetupCallback>,
+    debug_overlay_key: input::Key,
+    y_up: bool,
+    linear_workflow: bool,
 }
 
 impl Default for RendiumBuilder {
@@ -467,6 +988,9 @@ impl RendiumBuilder {
             size: winit::dpi::PhysicalSize::new(600, 600),
             title: "Window".to_string(),
             setup_callback: None,
+            debug_overlay_key: input::Key::BackTick,
+            y_up: false,
+            linear_workflow: true,
         }
     }
 
@@ -480,6 +1004,26 @@ impl RendiumBuilder {
         self
     }
 
+    // Puts the origin in the bottom left corner, with y going up
+    pub fn with_y_up(mut self, y_up: bool) -> Self {
+        self.y_up = y_up;
+        self
+    }
+
+    // On by default: the window is an sRGB target, so translucent colors and anti-aliased edges
+    // blend in linear light. Off blends the sRGB values in the framebuffer directly, like browsers
+    // and most 2D libraries do. Only that blending changes: vertex colors, gradients and texture
+    // filtering are interpolated in linear light either way, and solid colors come out the same.
+    pub fn with_linear_workflow(mut self, linear_workflow: bool) -> Self {
+        self.linear_workflow = linear_workflow;
+        self
+    }
+
+    pub fn with_debug_overlay_key(mut self, key: input::Key) -> Self {
+        self.debug_overlay_key = key;
+        self
+    }
+
     pub fn run<F: 'static + FnMut(&mut RendiumInstance)>(&mut self, f: F) -> anyhow::Result<()> {
         env_logger::init();
 
@@ -488,6 +1032,11 @@ impl RendiumBuilder {
         event_loop.set_control_flow(ControlFlow::Poll);
 
         let mut app = RendiumInstance::new(self.size, self.title.clone(), Box::new(f));
+        app.setup_callback = self.setup_callback.take();
+        app.y_up = self.y_up;
+        app.linear_workflow = self.linear_workflow;
+        app.debug_overlay
+            .set_toggle_key(self.debug_overlay_key.clone());
 
         event_loop.run_app(&mut app)?;
 
@@ -500,11 +1049,46 @@ impl RendiumBuilder {
     }
 }
 
+// One draw call worth of content, in the order it was drawn
+struct Batch {
+    tex_index: u32,
+    view: u32,
+    kind: BatchKind,
+}
+
+enum BatchKind {
+    Geometry(std::ops::Range<u32>),
+    Sprites(std::ops::Range<u32>),
+}
+
+// A run of sprites, and how many indices had been added when it was drawn
+struct SpriteRun {
+    index_pos: u32,
+    tex_index: u32,
+    view: u32,
+    instances: std::ops::Range<u32>,
+}
+
 pub struct RendiumDrawHandle {
     vertices: Vec<Vertex>,
+    // The view each vertex was drawn with, an index into `views`
+    vertex_views: Vec<u32>,
     indices: Vec<u32>,
+    sprites: Vec<sprite::SpriteInstance>,
+    sprite_runs: Vec<SpriteRun>,
     window_size: PhysicalSize<u32>,
+    // View-projection matrices, the first one is plain screen space
+    views: Vec<types::Matrix4>,
+    current_view: u32,
+    // Applied to every vertex on the CPU, before the view
+    transform: types::Matrix3,
+    transform_stack: Vec<types::Matrix3>,
     textures: HashMap<String, texture::GPUTexture>,
+    // One baked row of colors per distinct gradient, uploaded as ramp textures at render time
+    gradient_ramps: Vec<Vec<types::Color>>,
+    // The row of each sorted stop list in `gradient_ramps`, as offset bits and colors
+    gradient_rows: HashMap<Vec<(u32, types::Color)>, usize>,
+    anti_aliasing: bool,
 }
 
 impl RendiumDrawHandle {
@@ -514,31 +1098,485 @@ impl RendiumDrawHandle {
     ) -> Self {
         Self {
             vertices: Vec::new(),
+            vertex_views: Vec::new(),
             indices: Vec::new(),
+            sprites: Vec::new(),
+            sprite_runs: Vec::new(),
             window_size,
+            views: vec![screen_projection(window_size, false)],
+            current_view: 0,
+            transform: types::Matrix3::IDENTITY,
+            transform_stack: Vec::new(),
             textures,
+            gradient_ramps: Vec::new(),
+            gradient_rows: HashMap::new(),
+            anti_aliasing: true,
         }
     }
 
+    // Everything drawn inside `f` goes through the camera, anything after is back to how it was.
+    // The camera sits inside the current view and transform, so cameras can be nested.
+    pub fn with_camera<F: FnOnce(&mut Self)>(&mut self, camera: &camera::Camera2D, f: F) {
+        let previous = self.current_view;
+        let outer = self.views[previous as usize] * types::Matrix4::from(self.transform);
+        self.views
+            .push(outer * types::Matrix4::from(camera.matrix()));
+        self.current_view = self.views.len() as u32 - 1;
+
+        // The outer transform is part of the view now, inside starts from scratch
+        let transform = std::mem::replace(&mut self.transform, types::Matrix3::IDENTITY);
+        let transform_stack = std::mem::take(&mut self.transform_stack);
+
+        f(self);
+
+        self.current_view = previous;
+        self.transform = transform;
+        self.transform_stack = transform_stack;
+    }
+
+    // Saves the current transform, so `pop_transform` can go back to it
+    pub fn push_transform(&mut self) {
+        self.transform_stack.push(self.transform);
+    }
+
+    pub fn pop_transform(&mut self) {
+        if let Some(transform) = self.transform_stack.pop() {
+            self.transform = transform;
+        }
+    }
+
+    // These apply in local space, so the last one called affects vertices first
+    pub fn translate(&mut self, offset: types::Vector2) {
+        self.transform = self.transform * types::Matrix3::translation(offset);
+    }
+
+    pub fn rotate(&mut self, angle: f32) {
+        self.transform = self.transform * types::Matrix3::rotation(angle);
+    }
+
+    pub fn scale(&mut self, factor: types::Vector2) {
+        self.transform = self.transform * types::Matrix3::scale(factor);
+    }
+
+    // Any other transform, like a skew, applied the same way as the ones above
+    pub fn apply_transform(&mut self, matrix: types::Matrix3) {
+        self.transform = self.transform * matrix;
+    }
+
+    // On by default, shapes get smooth edges from the fragment shader.
+    // Off draws them as plain triangles, with the segment counts passed in.
+    pub fn set_anti_aliasing(&mut self, anti_aliasing: bool) {
+        self.anti_aliasing = anti_aliasing;
+    }
+
+    pub fn is_anti_aliased(&self) -> bool {
+        self.anti_aliasing
+    }
+
+    // How big one unit of the current coordinates is on screen, in pixels,
+    // through the transform and the current view
+    pub fn pixels_per_unit(&self) -> f32 {
+        let view = self.views[self.current_view as usize].0;
+        let t = self.transform.0;
+        let view_det = view[0][0] * view[1][1] - view[1][0] * view[0][1];
+        let transform_det = t[0][0] * t[1][1] - t[1][0] * t[0][1];
+        // NDC is 2 units across the window
+        let ndc_to_pixels = self.window_size.width as f32 * self.window_size.height as f32 / 4.0;
+        (view_det * transform_det * ndc_to_pixels).abs().sqrt()
+    }
+
+    pub fn get_transform(&self) -> types::Matrix3 {
+        self.transform
+    }
+
+    // Positions are in pixels, the projection turns them into NDC on the GPU
     pub fn add_vertex(&mut self, pos: [f32; 3], col: types::Color, uv: [f32; 2], tex_index: u32) {
-        let size = self.window_size;
-        let ndc_x = (pos[0] / size.width as f32) * 2.0 - 1.0;
-        let ndc_y = 1.0 - (pos[1] / size.height as f32) * 2.0;
-        let ndc_pos = [ndc_x, ndc_y, pos[2]];
-        self.vertices.push(Vertex::new(ndc_pos, col, uv, tex_index));
+        let types::Vector2(x, y) = self
+            .transform
+            .transform_point(types::Vector2(pos[0], pos[1]));
+        self.vertices
+            .push(Vertex::new([x, y, pos[2]], col, uv, tex_index));
+        self.vertex_views.push(self.current_view);
+    }
+
+    // Like `add_vertex`, for vertices the fragment shader does more with than sampling a texture
+    pub(crate) fn add_shape_vertex(
+        &mut self,
+        pos: types::Vector2,
+        col: types::Color,
+        uv: types::Vector2,
+        kind: ShapeKind,
+        params: [f32; 4],
+        tex_index: u32,
+    ) {
+        self.add_vertex([pos.0, pos.1, 0.0], col, uv.into(), tex_index);
+        if let Some(v) = self.vertices.last_mut() {
+            v.kind = kind as u32;
+            v.params = params;
+        }
     }
 
     pub fn add_index(&mut self, i: u32) {
         self.indices.push(i);
     }
+
+    pub(crate) fn add_sprite(&mut self, instance: sprite::SpriteInstance, tex_index: u32) {
+        let index_pos = self.indices.len() as u32;
+        let view = self.current_view;
+        let i = self.sprites.len() as u32;
+        self.sprites.push(instance);
+
+        match self.sprite_runs.last_mut() {
+            Some(run)
+                if run.index_pos == index_pos && run.tex_index == tex_index && run.view == view =>
+            {
+                run.instances.end = i + 1;
+            }
+            _ => self.sprite_runs.push(SpriteRun {
+                index_pos,
+                tex_index,
+                view,
+                instances: i..i + 1,
+            }),
+        }
+    }
+
+    // Splits everything drawn into draw calls, keeping the order it was drawn in.
+    // Triangles are grouped while they share a texture and a view, sprites go in between them.
+    fn batches(&self) -> Vec<Batch> {
+        let mut batches: Vec<Batch> = Vec::new();
+        let mut runs = self.sprite_runs.iter().peekable();
+
+        for (i, tri) in self.indices.chunks(3).enumerate() {
+            let start = (i * 3) as u32;
+            while let Some(run) = runs.next_if(|run| run.index_pos <= start) {
+                batches.push(run.into());
+            }
+
+            let first = tri[0] as usize;
+            let tex_index = self.vertices.get(first).map(|v| v.tex_index).unwrap_or(0);
+            let view = self.vertex_views.get(first).copied().unwrap_or(0);
+            let end = start + tri.len() as u32;
+
+            match batches.last_mut() {
+                Some(Batch {
+                    tex_index: last_tex,
+                    view: last_view,
+                    kind: BatchKind::Geometry(indices),
+                }) if *last_tex == tex_index && *last_view == view => indices.end = end,
+                _ => batches.push(Batch {
+                    tex_index,
+                    view,
+                    kind: BatchKind::Geometry(start..end),
+                }),
+            }
+        }
+
+        batches.extend(runs.map(Batch::from));
+
+        batches
+    }
 }
 
+impl From<&SpriteRun> for Batch {
+    fn from(run: &SpriteRun) -> Self {
+        Self {
+            tex_index: run.tex_index,
+            view: run.view,
+            kind: BatchKind::Sprites(run.instances.clone()),
+        }
+    }
+}
+
+pub mod camera;
+pub mod collision;
+pub mod color;
+pub mod curves;
+pub mod debug;
+mod decode;
+pub mod gradient;
+pub mod image;
 pub mod input;
 mod input_wrapper;
+#[cfg(any(feature = "glam", feature = "mint"))]
+mod interop;
+pub mod path;
 pub mod shapes;
+pub mod sprite;
+pub mod stroke;
+#[cfg(feature = "svg")]
+pub mod svg;
+pub mod tessellate;
 pub mod texture;
 pub mod types;
 
 pub fn init() -> RendiumBuilder {
     RendiumBuilder::new()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shapes::DrawShape;
+    use types::{Color, Vector2};
+
+    // None on machines without a GPU or a software fallback, the tests skip then
+    fn headless_renderer(linear_workflow: bool) -> Option<Renderer> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter =
+            pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+                .ok()?;
+        let format = if linear_workflow {
+            wgpu::TextureFormat::Rgba8UnormSrgb
+        } else {
+            wgpu::TextureFormat::Rgba8Unorm
+        };
+        pollster::block_on(Renderer::new(&adapter, format, linear_workflow)).ok()
+    }
+
+    // Fills the left half and leaves the right half cleared, both with `color`
+    fn clear_and_fill(renderer: &Renderer, color: Color) -> texture::Texture {
+        let size = PhysicalSize::new(16, 8);
+        let mut d = RendiumDrawHandle::new(size, HashMap::new());
+        d.draw_rect_v(Vector2(0.0, 0.0), Vector2(8.0, 8.0), color);
+        renderer.render_to_texture(&d, 16, 8, color).unwrap()
+    }
+
+    fn assert_clear_matches_fill(linear_workflow: bool) {
+        let Some(renderer) = headless_renderer(linear_workflow) else {
+            eprintln!("no GPU adapter, skipping");
+            return;
+        };
+
+        let colors = [
+            Color::BLACK,
+            Color::WHITE,
+            Color::GRAY,
+            Color::rgb(200, 100, 50),
+            Color::rgb(1, 2, 3),
+            types::css::CORNFLOWERBLUE,
+        ];
+        for color in colors {
+            let texture = clear_and_fill(&renderer, color);
+            let pixel = |x: usize, y: usize| {
+                let i = (y * 16 + x) * 4;
+                &texture.data[i..i + 4]
+            };
+            // Away from the edge of the rect
+            let fill = pixel(3, 4);
+            let clear = pixel(12, 4);
+            assert_eq!(fill, clear, "{color:?}, linear workflow: {linear_workflow}");
+            assert_eq!(fill, [color.0, color.1, color.2, color.3], "{color:?}");
+        }
+    }
+
+    #[test]
+    fn more_gradients_than_one_ramp_texture() {
+        use gradient::{ColorStop, DrawGradient};
+
+        let Some(renderer) = headless_renderer(false) else {
+            eprintln!("no GPU adapter, skipping");
+            return;
+        };
+
+        // 512 solid gradients in 4x4 squares, two ramp textures' worth
+        let size = PhysicalSize::new(128, 64);
+        let mut d = RendiumDrawHandle::new(size, HashMap::new());
+        d.set_anti_aliasing(false);
+        let color = |i: u32| Color::rgb(i as u8, (i / 256) as u8, 7);
+        for i in 0..512 {
+            let pos = Vector2((i % 32 * 4) as f32, (i / 32 * 4) as f32);
+            let stops = [ColorStop::new(0.0, color(i)), ColorStop::new(1.0, color(i))];
+            d.draw_rect_gradient_h(pos, Vector2(4.0, 4.0), &stops);
+        }
+
+        let texture = renderer
+            .render_to_texture(&d, 128, 64, Color::TRANSPARENT)
+            .unwrap();
+        for i in 0..512 {
+            let (x, y) = (i % 32 * 4 + 2, i / 32 * 4 + 2);
+            let at = (y * 128 + x) as usize * 4;
+            let c = color(i);
+            assert_eq!(
+                texture.data[at..at + 4],
+                [c.0, c.1, c.2, c.3],
+                "gradient {i}"
+            );
+        }
+    }
+
+    // Draws translucent strokes that overlap themselves, none of which may be drawn twice anywhere
+    fn assert_drawn_once(draw: impl FnOnce(&mut RendiumDrawHandle, Color)) {
+        let Some(renderer) = headless_renderer(false) else {
+            eprintln!("no GPU adapter, skipping");
+            return;
+        };
+
+        let size = PhysicalSize::new(128, 128);
+        let mut d = RendiumDrawHandle::new(size, HashMap::new());
+        draw(&mut d, Color(255, 0, 0, 128));
+        let texture = renderer
+            .render_to_texture(&d, 128, 128, Color::TRANSPARENT)
+            .unwrap();
+        let alphas: Vec<u8> = texture.data.chunks(4).map(|p| p[3]).collect();
+        // Fringes only fade out, so anything above the color's alpha was drawn twice. A pixel
+        // center right on the edge between a fringe and the fill can be, a seam is whole rows.
+        let twice = alphas.iter().filter(|&&alpha| alpha > 128).count();
+        assert!(twice < 10, "{twice} pixels drawn twice");
+        assert!(alphas.iter().filter(|&&alpha| alpha == 128).count() > 1000);
+    }
+
+    // Not so pointy that the holes get narrower than a pixel, where the fringes from both sides
+    // of a hole overlap
+    fn star(center: Vector2) -> Vec<Vector2> {
+        (0..10)
+            .map(|i| {
+                let angle = i as f32 * std::f32::consts::PI / 5.0;
+                let radius = if i % 2 == 0 { 28.0 } else { 16.0 };
+                center + Vector2(angle.cos(), angle.sin()) * radius
+            })
+            .collect()
+    }
+
+    #[test]
+    fn translucent_polylines_have_no_seams() {
+        use stroke::{LineCap, LineJoin};
+
+        assert_drawn_once(|d, color| {
+            // The inner side of every join and the round caps overlap the segments
+            let zigzag = [
+                Vector2(16.0, 8.0),
+                Vector2(48.0, 16.0),
+                Vector2(18.0, 36.0),
+                Vector2(50.0, 56.0),
+            ];
+            d.draw_polyline(
+                &zigzag,
+                10.0,
+                LineJoin::Miter(f32::INFINITY),
+                LineCap::Round,
+                false,
+                color,
+            );
+            let star_joins = [(LineJoin::Bevel, 32.0), (LineJoin::Round, 96.0)];
+            for (join, x) in star_joins {
+                d.draw_polyline(
+                    &star(Vector2(x, 96.0)),
+                    6.0,
+                    join,
+                    LineCap::Butt,
+                    true,
+                    color,
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn translucent_dashes_and_outlines_have_no_seams() {
+        use stroke::{LineCap, StrokeAlign, StrokeStyle};
+
+        assert_drawn_once(|d, color| {
+            // Dashes going round a corner overlap themselves, and square caps fill the gaps
+            let style = StrokeStyle::new(8.0)
+                .with_cap(LineCap::Square)
+                .with_dash(&[20.0, 4.0], 0.0);
+            d.draw_rect_lines_styled(Vector2(8.0, 8.0), Vector2(48.0, 48.0), &style, color);
+            let style = StrokeStyle::new(6.0).with_dash(&[30.0, 6.0], 3.0);
+            d.draw_polygon_lines_styled(&star(Vector2(96.0, 32.0)), &style, color);
+            d.draw_polygon_lines(&star(Vector2(32.0, 96.0)), 6.0, StrokeAlign::Inner, color);
+            let plus: Vec<Vector2> = [
+                (-1, -3),
+                (1, -3),
+                (1, -1),
+                (3, -1),
+                (3, 1),
+                (1, 1),
+                (1, 3),
+                (-1, 3),
+                (-1, 1),
+                (-3, 1),
+                (-3, -1),
+                (-1, -1),
+            ]
+            .iter()
+            .map(|&(x, y)| Vector2(96.0, 96.0) + Vector2(x as f32, y as f32) * 7.0)
+            .collect();
+            d.draw_polygon_lines(&plus, 6.0, StrokeAlign::Outer, color);
+        });
+    }
+
+    // The alpha of every pixel after `draw` on a transparent 64x64 target
+    fn render_alphas(draw: impl FnOnce(&mut RendiumDrawHandle)) -> Option<Vec<u8>> {
+        let Some(renderer) = headless_renderer(false) else {
+            eprintln!("no GPU adapter, skipping");
+            return None;
+        };
+
+        let mut d = RendiumDrawHandle::new(PhysicalSize::new(64, 64), HashMap::new());
+        draw(&mut d);
+        let texture = renderer
+            .render_to_texture(&d, 64, 64, Color::TRANSPARENT)
+            .unwrap();
+        Some(texture.data.chunks(4).map(|p| p[3]).collect())
+    }
+
+    #[test]
+    fn thick_rect_outlines_have_square_corners() {
+        let Some(alphas) = render_alphas(|d| {
+            d.draw_rect_lines(Vector2(10.0, 10.0), 30, 30, 12.0, Color::WHITE);
+        }) else {
+            return;
+        };
+        let alpha = |x: usize, y: usize| alphas[y * 64 + x];
+
+        // The outer corners are covered, not rounded off by half the thickness
+        for (x, y) in [(10, 10), (39, 10), (39, 39), (10, 39)] {
+            assert_eq!(alpha(x, y), 255, "corner at ({x}, {y})");
+        }
+        // So are the inner corners, and the hole is empty
+        assert_eq!(alpha(20, 20), 255);
+        assert_eq!(alpha(22, 22), 0);
+        assert_eq!(alpha(25, 25), 0);
+        assert_eq!(alpha(8, 8), 0);
+    }
+
+    #[test]
+    fn thick_rounded_outlines_keep_small_radii() {
+        use stroke::StrokeAlign;
+
+        let Some(alphas) = render_alphas(|d| {
+            d.draw_rect_rounded_lines(
+                Vector2(10.0, 10.0),
+                Vector2(30.0, 30.0),
+                [2.0; 4],
+                12.0,
+                StrokeAlign::Inner,
+                Color::WHITE,
+            );
+        }) else {
+            return;
+        };
+        let alpha = |x: usize, y: usize| alphas[y * 64 + x];
+
+        // Inside the 2 pixel radius, which half the thickness would have rounded off
+        for (x, y) in [(11, 11), (38, 11), (38, 38), (11, 38)] {
+            assert_eq!(alpha(x, y), 255, "corner at ({x}, {y})");
+        }
+        // The corner pixel itself is only partly inside the curve
+        let corner = alpha(10, 10);
+        assert!(corner > 0 && corner < 200, "{corner}");
+        assert_eq!(alpha(20, 20), 255);
+        assert_eq!(alpha(25, 25), 0);
+    }
+
+    #[test]
+    fn clear_matches_fill_srgb() {
+        assert_clear_matches_fill(false);
+    }
+
+    #[test]
+    fn clear_matches_fill_linear_workflow() {
+        assert_clear_matches_fill(true);
+    }
+}
@@ -0,0 +1,405 @@
+// This module has paths built out of lines and curves, like the ones in SVG and the canvas API.
+// Filling and stroking them go through the same tessellator as polygons and outlines.
+use crate::RendiumDrawHandle;
+use crate::curves::{CubicBezier, QuadBezier, flatten_tolerance};
+use crate::stroke::{StrokeStyle, add_stroke};
+use crate::tessellate::{FillRule, add_fill};
+use crate::types::{Color, Rect, Vector2};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PathCommand {
+    MoveTo(Vector2),
+    LineTo(Vector2),
+    // The control point, then the end
+    QuadTo(Vector2, Vector2),
+    // Both control points, then the end
+    CubicTo(Vector2, Vector2, Vector2),
+    Close,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Path {
+    commands: Vec<PathCommand>,
+    // Where the current sub-path started, closing it goes back there
+    start: Vector2,
+    current: Option<Vector2>,
+}
+
+impl Default for Path {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+            start: Vector2::zero(),
+            current: None,
+        }
+    }
+
+    pub fn commands(&self) -> &[PathCommand] {
+        &self.commands
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    // Where the next line or curve starts from, None before the first `move_to`
+    pub fn current_point(&self) -> Option<Vector2> {
+        self.current
+    }
+
+    // Starts a new sub-path
+    pub fn move_to(&mut self, to: Vector2) -> &mut Self {
+        self.commands.push(PathCommand::MoveTo(to));
+        self.start = to;
+        self.current = Some(to);
+        self
+    }
+
+    // Without a current point this is the same as `move_to`, like in the canvas API
+    pub fn line_to(&mut self, to: Vector2) -> &mut Self {
+        if self.current.is_none() {
+            return self.move_to(to);
+        }
+        self.commands.push(PathCommand::LineTo(to));
+        self.current = Some(to);
+        self
+    }
+
+    pub fn quad_to(&mut self, control: Vector2, to: Vector2) -> &mut Self {
+        if self.current.is_none() {
+            self.move_to(control);
+        }
+        self.commands.push(PathCommand::QuadTo(control, to));
+        self.current = Some(to);
+        self
+    }
+
+    pub fn cubic_to(&mut self, control1: Vector2, control2: Vector2, to: Vector2) -> &mut Self {
+        if self.current.is_none() {
+            self.move_to(control1);
+        }
+        self.commands
+            .push(PathCommand::CubicTo(control1, control2, to));
+        self.current = Some(to);
+        self
+    }
+
+    // An elliptical arc to `to`, the same as the A command in SVG. Of the four arcs with
+    // these radii and rotation (in radians) that fit, `large_arc` picks one of the two going
+    // more than half way round, and `sweep` one going clockwise on a y-down screen.
+    // Radii too small to reach are scaled up until they do.
+    pub fn arc_to(
+        &mut self,
+        radii: Vector2,
+        x_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: Vector2,
+    ) -> &mut Self {
+        let Some(from) = self.current else {
+            return self.move_to(to);
+        };
+        if from == to {
+            return self;
+        }
+        let (mut rx, mut ry) = (radii.0.abs(), radii.1.abs());
+        if rx == 0.0 || ry == 0.0 {
+            return self.line_to(to);
+        }
+
+        // Follows the SVG spec's conversion to a center and angles, in the ellipse's own rotation
+        let p = ((from - to) / 2.0).rotate(-x_rotation);
+        let scale = (p.0 * p.0) / (rx * rx) + (p.1 * p.1) / (ry * ry);
+        if scale > 1.0 {
+            rx *= scale.sqrt();
+            ry *= scale.sqrt();
+        }
+        let (rx2, ry2) = (rx * rx, ry * ry);
+        let num = rx2 * ry2 - rx2 * p.1 * p.1 - ry2 * p.0 * p.0;
+        let den = rx2 * p.1 * p.1 + ry2 * p.0 * p.0;
+        let mut coef = (num / den).max(0.0).sqrt();
+        if large_arc == sweep {
+            coef = -coef;
+        }
+        let c = Vector2(coef * rx * p.1 / ry, -coef * ry * p.0 / rx);
+        let center = c.rotate(x_rotation) + (from + to) / 2.0;
+
+        let u = Vector2((p.0 - c.0) / rx, (p.1 - c.1) / ry);
+        let v = Vector2((-p.0 - c.0) / rx, (-p.1 - c.1) / ry);
+        let start_angle = u.angle();
+        let mut delta = u.angle_to(v);
+        if sweep && delta < 0.0 {
+            delta += std::f32::consts::TAU;
+        } else if !sweep && delta > 0.0 {
+            delta -= std::f32::consts::TAU;
+        }
+
+        // A cubic per quarter turn at most is as close as it needs to be
+        let pieces = (delta.abs() / std::f32::consts::FRAC_PI_2).ceil().max(1.0) as usize;
+        let step = delta / pieces as f32;
+        let k = 4.0 / 3.0 * (step / 4.0).tan();
+        let on_ellipse = |p: Vector2| Vector2(p.0 * rx, p.1 * ry).rotate(x_rotation) + center;
+        for i in 0..pieces {
+            let a1 = start_angle + step * i as f32;
+            let a2 = a1 + step;
+            let p1 = Vector2(a1.cos(), a1.sin());
+            let p2 = Vector2(a2.cos(), a2.sin());
+            let end = if i + 1 == pieces { to } else { on_ellipse(p2) };
+            self.cubic_to(
+                on_ellipse(p1 + p1.perpendicular() * k),
+                on_ellipse(p2 - p2.perpendicular() * k),
+                end,
+            );
+        }
+        self
+    }
+
+    // Goes back to where the sub-path started, the next one starts there too
+    pub fn close(&mut self) -> &mut Self {
+        if self.current.is_some() {
+            self.commands.push(PathCommand::Close);
+            self.current = Some(self.start);
+        }
+        self
+    }
+
+    // The box around the path's lines and curves, None for an empty path
+    pub fn bounds(&self) -> Option<Rect> {
+        let points = self.commands.iter().flat_map(|command| match *command {
+            PathCommand::MoveTo(p) | PathCommand::LineTo(p) => vec![p],
+            PathCommand::QuadTo(c, p) => vec![c, p],
+            PathCommand::CubicTo(c1, c2, p) => vec![c1, c2, p],
+            PathCommand::Close => vec![],
+        });
+        // The control points are a loose fit, flattening finely against their size makes it tight
+        let hull = bounding_box(points)?;
+        let tolerance = (hull.size.length() * 1e-4).max(f32::MIN_POSITIVE);
+        bounding_box(
+            self.flatten(tolerance)
+                .into_iter()
+                .flat_map(|(points, _)| points),
+        )
+        .or(Some(hull))
+    }
+
+    // The sub-paths as points no further than `tolerance` from the curves, and whether they're closed
+    pub(crate) fn flatten(&self, tolerance: f32) -> Vec<(Vec<Vector2>, bool)> {
+        let mut lines = Vec::new();
+        let mut points: Vec<Vector2> = Vec::new();
+        let mut start = Vector2::zero();
+
+        for command in &self.commands {
+            match *command {
+                PathCommand::MoveTo(to) => {
+                    // A sub-path that's only a move draws nothing
+                    if points.len() > 1 {
+                        lines.push((std::mem::take(&mut points), false));
+                    }
+                    points = vec![to];
+                    start = to;
+                }
+                PathCommand::LineTo(to) => {
+                    if points.is_empty() {
+                        points.push(start);
+                    }
+                    points.push(to);
+                }
+                PathCommand::QuadTo(control, to) => {
+                    let from = points.last().copied().unwrap_or(start);
+                    let flat = QuadBezier::new(from, control, to).flatten(tolerance);
+                    if points.is_empty() {
+                        points.push(from);
+                    }
+                    points.extend_from_slice(&flat[1..]);
+                }
+                PathCommand::CubicTo(control1, control2, to) => {
+                    let from = points.last().copied().unwrap_or(start);
+                    let flat = CubicBezier::new(from, control1, control2, to).flatten(tolerance);
+                    if points.is_empty() {
+                        points.push(from);
+                    }
+                    points.extend_from_slice(&flat[1..]);
+                }
+                PathCommand::Close => {
+                    if !points.is_empty() {
+                        lines.push((std::mem::take(&mut points), true));
+                    }
+                }
+            }
+        }
+        if points.len() > 1 {
+            lines.push((points, false));
+        }
+        lines
+    }
+}
+
+fn bounding_box(mut points: impl Iterator<Item = Vector2>) -> Option<Rect> {
+    let first = points.next()?;
+    let (min, max) = points.fold((first, first), |(min, max), p| (min.min(p), max.max(p)));
+    Some(Rect::from_corners(min, max))
+}
+
+pub trait DrawPath {
+    // Every sub-path is closed for filling, open or not
+    fn fill_path(&mut self, path: &Path, rule: FillRule, col: Color);
+    fn stroke_path(&mut self, path: &Path, style: &StrokeStyle, col: Color);
+}
+
+impl DrawPath for RendiumDrawHandle {
+    fn fill_path(&mut self, path: &Path, rule: FillRule, col: Color) {
+        let contours: Vec<Vec<Vector2>> = path
+            .flatten(flatten_tolerance(self))
+            .into_iter()
+            .map(|(points, _)| points)
+            .collect();
+        add_fill(self, &contours, rule, col);
+    }
+
+    fn stroke_path(&mut self, path: &Path, style: &StrokeStyle, col: Color) {
+        let lines = path.flatten(flatten_tolerance(self));
+        add_stroke(self, &lines, style, col);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The flattened points of `path`, all in one list
+    fn flat_points(path: &Path) -> Vec<Vector2> {
+        path.flatten(0.01)
+            .into_iter()
+            .flat_map(|(points, _)| points)
+            .collect()
+    }
+
+    fn assert_on_circle(path: &Path, center: Vector2, radius: f32) {
+        for p in flat_points(path) {
+            let distance = p.distance(center);
+            assert!(
+                (distance - radius).abs() < 0.02,
+                "{p:?} is {distance} from {center:?}"
+            );
+        }
+    }
+
+    fn arc(radii: Vector2, large_arc: bool, sweep: bool) -> Path {
+        let mut path = Path::new();
+        path.move_to(Vector2(0.0, 0.0))
+            .arc_to(radii, 0.0, large_arc, sweep, Vector2(20.0, 0.0));
+        path
+    }
+
+    #[test]
+    fn arc_to_picks_each_of_the_four_arcs() {
+        // Two circles of radius 20 go through both ends, centered above and below them
+        let h = 300.0f32.sqrt();
+        let (above, below) = (Vector2(10.0, -h), Vector2(10.0, h));
+        // (large arc, sweep, center, top and bottom of the arc)
+        let cases = [
+            (false, true, below, (h - 20.0, 0.0)),
+            (true, true, above, (-h - 20.0, 0.0)),
+            (false, false, above, (0.0, 20.0 - h)),
+            (true, false, below, (0.0, h + 20.0)),
+        ];
+        for (large_arc, sweep, center, (top, bottom)) in cases {
+            let path = arc(Vector2(20.0, 20.0), large_arc, sweep);
+            assert_eq!(path.current_point(), Some(Vector2(20.0, 0.0)));
+            assert_on_circle(&path, center, 20.0);
+
+            let bounds = path.bounds().unwrap();
+            let case = format!("large arc {large_arc}, sweep {sweep}");
+            assert!((bounds.min().1 - top).abs() < 0.01, "{case}: {bounds:?}");
+            assert!((bounds.max().1 - bottom).abs() < 0.01, "{case}: {bounds:?}");
+        }
+
+        // A cubic per quarter turn at most
+        assert_eq!(arc(Vector2(20.0, 20.0), false, true).commands().len(), 2);
+        assert_eq!(arc(Vector2(20.0, 20.0), true, true).commands().len(), 5);
+    }
+
+    #[test]
+    fn arc_to_scales_up_radii_that_are_too_small() {
+        // Half a circle of radius 10 is the smallest that reaches, whichever arc is asked for
+        for large_arc in [false, true] {
+            let path = arc(Vector2(5.0, 5.0), large_arc, true);
+            assert_on_circle(&path, Vector2(10.0, 0.0), 10.0);
+            let bounds = path.bounds().unwrap();
+            assert!((bounds.min().1 + 10.0).abs() < 0.01, "{bounds:?}");
+        }
+
+        // Ellipses keep their shape
+        let bounds = arc(Vector2(5.0, 2.5), false, false).bounds().unwrap();
+        assert!((bounds.max().1 - 5.0).abs() < 0.01, "{bounds:?}");
+        assert!((bounds.size.0 - 20.0).abs() < 0.01, "{bounds:?}");
+    }
+
+    #[test]
+    fn arc_to_degenerate_cases() {
+        let mut path = Path::new();
+        path.arc_to(Vector2(5.0, 5.0), 0.0, false, true, Vector2(1.0, 2.0));
+        assert_eq!(path.commands(), [PathCommand::MoveTo(Vector2(1.0, 2.0))]);
+
+        // The same point draws nothing, a zero radius is a straight line
+        path.arc_to(Vector2(5.0, 5.0), 0.0, false, true, Vector2(1.0, 2.0));
+        assert_eq!(path.commands().len(), 1);
+        path.arc_to(Vector2(0.0, 5.0), 0.0, false, true, Vector2(4.0, 2.0));
+        assert_eq!(path.commands()[1], PathCommand::LineTo(Vector2(4.0, 2.0)));
+    }
+
+    #[test]
+    fn flatten_line_after_close_starts_at_the_sub_path_start() {
+        let mut path = Path::new();
+        path.move_to(Vector2(0.0, 0.0))
+            .line_to(Vector2(10.0, 0.0))
+            .line_to(Vector2(10.0, 10.0))
+            .close()
+            .line_to(Vector2(0.0, 10.0));
+        assert_eq!(path.current_point(), Some(Vector2(0.0, 10.0)));
+        assert_eq!(
+            path.flatten(0.1),
+            [
+                (
+                    vec![Vector2(0.0, 0.0), Vector2(10.0, 0.0), Vector2(10.0, 10.0)],
+                    true
+                ),
+                (vec![Vector2(0.0, 0.0), Vector2(0.0, 10.0)], false),
+            ]
+        );
+
+        // Curves after a close start there too
+        let mut path = Path::new();
+        path.move_to(Vector2(5.0, 5.0))
+            .line_to(Vector2(6.0, 5.0))
+            .close()
+            .quad_to(Vector2(5.0, 0.0), Vector2(0.0, 0.0));
+        let lines = path.flatten(0.1);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].0.first(), Some(&Vector2(5.0, 5.0)));
+        assert_eq!(lines[1].0.last(), Some(&Vector2(0.0, 0.0)));
+        assert!(!lines[1].1);
+    }
+
+    #[test]
+    fn flatten_drops_lone_moves() {
+        let mut path = Path::new();
+        path.move_to(Vector2(1.0, 1.0))
+            .move_to(Vector2(2.0, 2.0))
+            .line_to(Vector2(3.0, 2.0))
+            .move_to(Vector2(9.0, 9.0));
+        assert_eq!(
+            path.flatten(0.1),
+            [(vec![Vector2(2.0, 2.0), Vector2(3.0, 2.0)], false)]
+        );
+        assert_eq!(Path::new().bounds(), None);
+    }
+}
@@ -1,9 +1,27 @@
-// This module has methods for drawing shapes
-use crate::RendiumDrawHandle;
-use crate::types::{Color, Vector2};
+// This module has methods for drawing shapes.
+// With anti-aliasing on they are quads the fragment shader cuts the shape out of
+// with a signed distance function, so their edges are smooth at any size.
+//
+// Only polygons, their outlines, ellipse outlines and styled lines go through the tessellator
+// like paths do. Rects, circles, ellipses, capsules, rounded rects, rings, arcs, sectors and
+// triangles stay on the signed distance functions on purpose: one quad each, with edges that
+// are exact at any zoom instead of flattened to segments. Without anti-aliasing they're convex
+// fans or strips that never overlap themselves, which the tessellator would only cut up.
+use crate::stroke::{LineCap, LineJoin, StrokeAlign, StrokeStyle, add_stroke, offset_polygon};
+use crate::tessellate::{FillRule, add_fill, signed_area};
+use crate::types::{Color, Rect, Vector2};
+use crate::{RendiumDrawHandle, ShapeKind};
 
+// The methods taking whole numbers round positions and sizes down, which makes moving shapes
+// jitter. The `_v` and `_pro` ones take floats. Rotations are in radians, clockwise on screen.
+// The `_lines` ones draw outlines, which are one shape, so translucent ones have no darker
+// overlaps. `draw_rect_lines` keeps its outline inside the rect, the others can put it anywhere.
 pub trait DrawShape {
     fn draw_rect(&mut self, pos: Vector2, width: i32, height: i32, col: Color);
+    fn draw_rect_v(&mut self, pos: Vector2, size: Vector2, col: Color);
+    // `origin` is a point in the rect, from its top left corner. It goes at `rect.pos`,
+    // and the rect turns around it.
+    fn draw_rect_pro(&mut self, rect: Rect, origin: Vector2, rotation: f32, col: Color);
     fn draw_rect_lines(
         &mut self,
         pos: Vector2,
@@ -12,34 +30,163 @@ pub trait DrawShape {
         thickness: f32,
         col: Color,
     );
+    fn draw_rect_lines_v(
+        &mut self,
+        pos: Vector2,
+        size: Vector2,
+        thickness: f32,
+        align: StrokeAlign,
+        col: Color,
+    );
     fn draw_circle(&mut self, pos: Vector2, radius: i32, col: Color, segments: usize);
+    fn draw_circle_v(&mut self, center: Vector2, radius: f32, col: Color);
+    fn draw_circle_lines(
+        &mut self,
+        center: Vector2,
+        radius: f32,
+        thickness: f32,
+        align: StrokeAlign,
+        col: Color,
+    );
     fn draw_triangle(&mut self, p1: Vector2, p2: Vector2, p3: Vector2, col: Color);
+    // The points are from `center`, and turn around it
+    fn draw_triangle_pro(
+        &mut self,
+        center: Vector2,
+        points: [Vector2; 3],
+        rotation: f32,
+        col: Color,
+    );
+    fn draw_triangle_lines(
+        &mut self,
+        points: [Vector2; 3],
+        thickness: f32,
+        align: StrokeAlign,
+        col: Color,
+    );
     fn draw_line(&mut self, from: Vector2, to: Vector2, thickness: i32, col: Color);
+    fn draw_line_v(&mut self, from: Vector2, to: Vector2, thickness: f32, col: Color);
+    // A line with round ends, `radius` is half its thickness
+    fn draw_capsule(&mut self, from: Vector2, to: Vector2, radius: f32, col: Color);
+    // Any polygon, concave or touching itself. Parts where it overlaps itself are filled.
+    fn draw_polygon(&mut self, points: &[Vector2], col: Color);
+    // Holes can go either way round
+    fn draw_polygon_with_holes(&mut self, outline: &[Vector2], holes: &[&[Vector2]], col: Color);
+    // Corners stay sharp however thick the outline is
+    fn draw_polygon_lines(
+        &mut self,
+        points: &[Vector2],
+        thickness: f32,
+        align: StrokeAlign,
+        col: Color,
+    );
+    // `radii` go top left, top right, bottom right, bottom left, like in CSS
+    fn draw_rect_rounded(&mut self, pos: Vector2, size: Vector2, radii: [f32; 4], col: Color);
+    fn draw_rect_rounded_lines(
+        &mut self,
+        pos: Vector2,
+        size: Vector2,
+        radii: [f32; 4],
+        thickness: f32,
+        align: StrokeAlign,
+        col: Color,
+    );
+    fn draw_ellipse(&mut self, center: Vector2, radii: Vector2, col: Color);
+    fn draw_ellipse_pro(&mut self, center: Vector2, radii: Vector2, rotation: f32, col: Color);
+    fn draw_ellipse_lines(
+        &mut self,
+        center: Vector2,
+        radii: Vector2,
+        thickness: f32,
+        align: StrokeAlign,
+        col: Color,
+    );
+    // Angles are in radians from the positive x axis, the thickness is centered on `radius`
+    fn draw_arc(
+        &mut self,
+        center: Vector2,
+        radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        thickness: f32,
+        col: Color,
+    );
+    fn draw_ring(&mut self, center: Vector2, inner_radius: f32, outer_radius: f32, col: Color);
+    // A pie slice
+    fn draw_sector(
+        &mut self,
+        center: Vector2,
+        radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        col: Color,
+    );
+    // A line through all the points. Where it overlaps itself it's only drawn once,
+    // so translucent lines don't get darker at the joins.
+    fn draw_polyline(
+        &mut self,
+        points: &[Vector2],
+        thickness: f32,
+        join: LineJoin,
+        cap: LineCap,
+        closed: bool,
+        col: Color,
+    );
+    // These draw outlines with a stroke style, for dashed and dotted lines. Dashes carry on
+    // round corners, and moving `dash_offset` every frame makes them march along.
+    fn draw_line_styled(&mut self, from: Vector2, to: Vector2, style: &StrokeStyle, col: Color);
+    // Inside the rect like `draw_rect_lines`, the dashes start at the top left corner
+    fn draw_rect_lines_styled(
+        &mut self,
+        pos: Vector2,
+        size: Vector2,
+        style: &StrokeStyle,
+        col: Color,
+    );
+    // Centered on the radius, the dashes start at the right and go clockwise on screen
+    fn draw_circle_lines_styled(
+        &mut self,
+        center: Vector2,
+        radius: f32,
+        style: &StrokeStyle,
+        col: Color,
+    );
+    // The dashes start at the first point
+    fn draw_polygon_lines_styled(&mut self, points: &[Vector2], style: &StrokeStyle, col: Color);
 }
 
 impl DrawShape for RendiumDrawHandle {
     fn draw_rect(&mut self, pos: Vector2, width: i32, height: i32, col: Color) {
-        let base = self.vertices.len() as u32;
-        let x = pos.0 as i32;
-        let y = pos.1 as i32;
-
-        self.add_vertex([x as f32, y as f32, 0.0], col, [0.0, 0.0], 0);
-        self.add_vertex([x as f32, (y + height) as f32, 0.0], col, [0.0, 0.0], 0);
-        self.add_vertex([(x + width) as f32, y as f32, 0.0], col, [0.0, 0.0], 0);
-        self.add_vertex(
-            [(x + width) as f32, (y + height) as f32, 0.0],
-            col,
-            [0.0, 0.0],
-            0,
-        );
+        let pos = Vector2(pos.0 as i32 as f32, pos.1 as i32 as f32);
+        self.draw_rect_v(pos, Vector2(width as f32, height as f32), col);
+    }
 
-        self.add_index(base);
-        self.add_index(base + 1);
-        self.add_index(base + 2);
+    fn draw_rect_v(&mut self, pos: Vector2, size: Vector2, col: Color) {
+        self.draw_rect_pro(Rect::new(pos, size), Vector2::zero(), 0.0, col);
+    }
 
-        self.add_index(base + 2);
-        self.add_index(base + 1);
-        self.add_index(base + 3);
+    fn draw_rect_pro(&mut self, rect: Rect, origin: Vector2, rotation: f32, col: Color) {
+        let x_axis = Vector2(rotation.cos(), rotation.sin());
+        let center = rect.pos + (rect.size * 0.5 - origin).rotate(rotation);
+        let half = rect.size.abs() * 0.5;
+
+        if self.anti_aliasing {
+            add_sdf_quad(
+                self,
+                center,
+                x_axis,
+                half,
+                col,
+                ShapeKind::RoundedRect,
+                [half.0, half.1, 0.0, 0.0],
+            );
+            return;
+        }
+
+        let y_axis = x_axis.perpendicular();
+        let corners = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+            .map(|(sx, sy)| center + x_axis * (half.0 * sx) + y_axis * (half.1 * sy));
+        add_convex_fan(self, &corners, col);
     }
 
     fn draw_rect_lines(
@@ -50,25 +197,62 @@ impl DrawShape for RendiumDrawHandle {
         thickness: f32,
         col: Color,
     ) {
-        let x = pos.0;
-        let y = pos.1;
-        self.draw_rect(pos.clone(), width, thickness as i32, col);
-        self.draw_rect(
-            (x, y + height as f32 - thickness).into(),
-            width,
-            thickness as i32,
-            col,
-        );
-        self.draw_rect(pos, thickness as i32, height, col);
-        self.draw_rect(
-            (x + width as f32 - thickness, y).into(),
-            thickness as i32,
-            height,
+        let size = Vector2(width as f32, height as f32);
+        self.draw_rect_lines_v(pos, size, thickness, StrokeAlign::Inner, col);
+    }
+
+    fn draw_rect_lines_v(
+        &mut self,
+        pos: Vector2,
+        size: Vector2,
+        thickness: f32,
+        align: StrokeAlign,
+        col: Color,
+    ) {
+        // Drawn inside a rect grown so its inner edge is where the outline should be
+        let thickness = thickness.abs();
+        let grow = align.offset(thickness) + thickness / 2.0;
+        let min = pos.min(pos + size) - Vector2(grow, grow);
+        let size = size.abs() + Vector2(grow, grow) * 2.0;
+        let thickness = thickness.min(size.0.min(size.1) / 2.0);
+
+        if self.anti_aliasing {
+            // One frame between the rect and the rect inset by the thickness, so translucent
+            // corners aren't drawn twice and stay square
+            let half = size * 0.5;
+            let extent = half + Vector2(1.0, 1.0) * aa_margin(self);
+            add_sdf_local_quad(
+                self,
+                min + half,
+                Vector2(1.0, 0.0),
+                -extent,
+                extent,
+                col,
+                ShapeKind::RoundedRect,
+                [half.0, half.1, 0.0, thickness],
+            );
+            return;
+        }
+
+        // The sides go between the top and bottom, so the corners aren't drawn twice
+        let side = Vector2(thickness, size.1 - thickness * 2.0);
+        self.draw_rect_v(min, Vector2(size.0, thickness), col);
+        self.draw_rect_v(
+            min + Vector2(0.0, size.1 - thickness),
+            Vector2(size.0, thickness),
             col,
         );
+        self.draw_rect_v(min + Vector2(0.0, thickness), side, col);
+        self.draw_rect_v(min + Vector2(size.0 - thickness, thickness), side, col);
     }
 
+    // `segments` is only used with anti-aliasing off
     fn draw_circle(&mut self, pos: Vector2, radius: i32, col: Color, segments: usize) {
+        if self.anti_aliasing {
+            self.draw_circle_v(pos, radius as f32, col);
+            return;
+        }
+
         if segments < 3 {
             return;
         }
@@ -96,7 +280,43 @@ impl DrawShape for RendiumDrawHandle {
         }
     }
 
+    fn draw_circle_v(&mut self, center: Vector2, radius: f32, col: Color) {
+        let radius = radius.abs();
+        if self.anti_aliasing {
+            add_sdf_quad(
+                self,
+                center,
+                Vector2(1.0, 0.0),
+                Vector2(radius, radius),
+                col,
+                ShapeKind::Circle,
+                [radius, 0.0, 0.0, 0.0],
+            );
+            return;
+        }
+        self.draw_ellipse(center, Vector2(radius, radius), col);
+    }
+
+    fn draw_circle_lines(
+        &mut self,
+        center: Vector2,
+        radius: f32,
+        thickness: f32,
+        align: StrokeAlign,
+        col: Color,
+    ) {
+        let thickness = thickness.abs();
+        let middle = radius.abs() + align.offset(thickness);
+        let outer = middle + thickness / 2.0;
+        self.draw_ring(center, (outer - thickness).max(0.0), outer, col);
+    }
+
     fn draw_triangle(&mut self, p1: Vector2, p2: Vector2, p3: Vector2, col: Color) {
+        if self.anti_aliasing {
+            add_sdf_triangle(self, [p1, p2, p3], col);
+            return;
+        }
+
         let x1 = p1.0;
         let y1 = p1.1;
         let x2 = p2.0;
@@ -115,49 +335,846 @@ impl DrawShape for RendiumDrawHandle {
         self.add_index(base + 2);
     }
 
-    fn draw_line(&mut self, from: Vector2, to: Vector2, thickness: i32, col: Color) {
-        let x1 = from.0;
-        let y1 = from.1;
-        let x2 = to.0;
-        let y2 = to.1;
+    fn draw_triangle_lines(
+        &mut self,
+        points: [Vector2; 3],
+        thickness: f32,
+        align: StrokeAlign,
+        col: Color,
+    ) {
+        add_polygon_outline(self, &points, thickness, align, col);
+    }
+
+    fn draw_triangle_pro(
+        &mut self,
+        center: Vector2,
+        points: [Vector2; 3],
+        rotation: f32,
+        col: Color,
+    ) {
+        let [p1, p2, p3] = points.map(|p| center + p.rotate(rotation));
+        self.draw_triangle(p1, p2, p3, col);
+    }
 
-        let dx = x2 - x1;
-        let dy = y2 - y1;
+    fn draw_line(&mut self, from: Vector2, to: Vector2, thickness: i32, col: Color) {
+        self.draw_line_v(from, to, thickness as f32, col);
+    }
 
-        let length = (dx * dx + dy * dy).sqrt();
+    fn draw_line_v(&mut self, from: Vector2, to: Vector2, thickness: f32, col: Color) {
+        let length = from.distance(to);
         if length == 0.0 {
             return;
         }
+        let x_axis = (to - from) / length;
+        let half = Vector2(length / 2.0, thickness.abs() / 2.0);
 
-        let dir_x = dx / length;
-        let dir_y = dy / length;
+        if self.anti_aliasing {
+            add_sdf_quad(
+                self,
+                (from + to) * 0.5,
+                x_axis,
+                half,
+                col,
+                ShapeKind::RoundedRect,
+                [half.0, half.1, 0.0, 0.0],
+            );
+            return;
+        }
 
-        let nx = -dir_y;
-        let ny = dir_x;
+        let offset = x_axis.perpendicular() * half.1;
+        add_convex_fan(
+            self,
+            &[from - offset, to - offset, to + offset, from + offset],
+            col,
+        );
+    }
 
-        let half_thickness = thickness as f32 / 2.0;
+    fn draw_capsule(&mut self, from: Vector2, to: Vector2, radius: f32, col: Color) {
+        let radius = radius.abs();
+        let length = from.distance(to);
+        let x_axis = if length > 0.0 {
+            (to - from) / length
+        } else {
+            Vector2(1.0, 0.0)
+        };
 
-        let offset_x = nx * half_thickness;
-        let offset_y = ny * half_thickness;
+        if self.anti_aliasing {
+            add_sdf_quad(
+                self,
+                (from + to) * 0.5,
+                x_axis,
+                Vector2(length / 2.0 + radius, radius),
+                col,
+                ShapeKind::Capsule,
+                [length / 2.0, radius, 0.0, 0.0],
+            );
+            return;
+        }
 
-        let p1 = [x1 + offset_x, y1 + offset_y, 0.0];
-        let p2 = [x1 - offset_x, y1 - offset_y, 0.0];
-        let p3 = [x2 + offset_x, y2 + offset_y, 0.0];
-        let p4 = [x2 - offset_x, y2 - offset_y, 0.0];
+        // Two half circles joined into one convex outline
+        let segments = circle_segments(radius * self.pixels_per_unit()) / 2;
+        let normal = x_axis.perpendicular();
+        let mut points = Vec::with_capacity(segments * 2 + 2);
+        for (end, side) in [(to, 1.0), (from, -1.0)] {
+            for i in 0..=segments {
+                let theta = i as f32 / segments as f32 * std::f32::consts::PI;
+                let dir = x_axis * (theta.sin() * side) - normal * (theta.cos() * side);
+                points.push(end + dir * radius);
+            }
+        }
+        add_convex_fan(self, &points, col);
+    }
 
-        let base = self.vertices.len() as u32;
+    fn draw_polygon(&mut self, points: &[Vector2], col: Color) {
+        add_fill(self, &[points.to_vec()], FillRule::NonZero, col);
+    }
 
-        self.add_vertex(p1, col, [0.0, 0.0], 0);
-        self.add_vertex(p2, col, [0.0, 0.0], 0);
-        self.add_vertex(p3, col, [0.0, 0.0], 0);
-        self.add_vertex(p4, col, [0.0, 0.0], 0);
+    fn draw_polygon_with_holes(&mut self, outline: &[Vector2], holes: &[&[Vector2]], col: Color) {
+        // With the holes going against the outline the non-zero rule cuts them out
+        let clockwise = signed_area(outline) > 0.0;
+        let mut contours = vec![outline.to_vec()];
+        for hole in holes {
+            let mut hole = hole.to_vec();
+            if (signed_area(&hole) > 0.0) == clockwise {
+                hole.reverse();
+            }
+            contours.push(hole);
+        }
+        add_fill(self, &contours, FillRule::NonZero, col);
+    }
 
-        self.add_index(base);
-        self.add_index(base + 2);
-        self.add_index(base + 1);
+    fn draw_polygon_lines(
+        &mut self,
+        points: &[Vector2],
+        thickness: f32,
+        align: StrokeAlign,
+        col: Color,
+    ) {
+        add_polygon_outline(self, points, thickness, align, col);
+    }
 
-        self.add_index(base + 2);
-        self.add_index(base + 3);
-        self.add_index(base + 1);
+    fn draw_rect_rounded(&mut self, pos: Vector2, size: Vector2, radii: [f32; 4], col: Color) {
+        add_rounded_rect(self, pos, size, radii, 0.0, col);
+    }
+
+    fn draw_rect_rounded_lines(
+        &mut self,
+        pos: Vector2,
+        size: Vector2,
+        radii: [f32; 4],
+        thickness: f32,
+        align: StrokeAlign,
+        col: Color,
+    ) {
+        // Like `draw_rect_lines_v`, the corners grow with the rect so the outline stays even
+        let thickness = thickness.abs();
+        let grow = align.offset(thickness) + thickness / 2.0;
+        let min = pos.min(pos + size) - Vector2(grow, grow);
+        let size = size.abs() + Vector2(grow, grow) * 2.0;
+        let radii = radii.map(|r| if r > 0.0 { r + grow } else { 0.0 });
+        add_rounded_rect(self, min, size, radii, thickness, col);
+    }
+
+    fn draw_ellipse(&mut self, center: Vector2, radii: Vector2, col: Color) {
+        self.draw_ellipse_pro(center, radii, 0.0, col);
+    }
+
+    fn draw_ellipse_pro(&mut self, center: Vector2, radii: Vector2, rotation: f32, col: Color) {
+        let radii = radii.abs();
+        let x_axis = Vector2(rotation.cos(), rotation.sin());
+        if self.anti_aliasing {
+            add_sdf_quad(
+                self,
+                center,
+                x_axis,
+                radii,
+                col,
+                ShapeKind::Ellipse,
+                [radii.0, radii.1, 0.0, 0.0],
+            );
+            return;
+        }
+
+        let segments = circle_segments(radii.0.max(radii.1) * self.pixels_per_unit());
+        let points: Vec<Vector2> = (0..segments)
+            .map(|i| {
+                let theta = i as f32 / segments as f32 * std::f32::consts::TAU;
+                center + (Vector2(theta.cos(), theta.sin()) * radii).rotate(rotation)
+            })
+            .collect();
+        add_convex_fan(self, &points, col);
+    }
+
+    fn draw_ellipse_lines(
+        &mut self,
+        center: Vector2,
+        radii: Vector2,
+        thickness: f32,
+        align: StrokeAlign,
+        col: Color,
+    ) {
+        let radii = radii.abs();
+        let thickness = thickness.abs();
+        if thickness == 0.0 {
+            return;
+        }
+        // The middle of the outline is the same distance from the ellipse all the way round,
+        // which isn't an ellipse itself, so it's moved out along the normal at each point
+        let offset = align.offset(thickness);
+        let segments = circle_segments((radii.0.max(radii.1) + offset) * self.pixels_per_unit());
+        let points = (0..segments)
+            .map(|i| {
+                let (sin, cos) = (i as f32 / segments as f32 * std::f32::consts::TAU).sin_cos();
+                let normal = Vector2(radii.1 * cos, radii.0 * sin).normalized();
+                center + Vector2(cos, sin) * radii + normal * offset
+            })
+            .collect();
+        let style = StrokeStyle::new(thickness).with_join(LineJoin::Round);
+        add_stroke(self, &[(points, true)], &style, col);
+    }
+
+    fn draw_arc(
+        &mut self,
+        center: Vector2,
+        radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        thickness: f32,
+        col: Color,
+    ) {
+        let half = thickness.abs() / 2.0;
+        add_annular_sector(
+            self,
+            center,
+            (radius - half).max(0.0),
+            radius + half,
+            start_angle,
+            end_angle,
+            col,
+        );
+    }
+
+    fn draw_ring(&mut self, center: Vector2, inner_radius: f32, outer_radius: f32, col: Color) {
+        add_annular_sector(
+            self,
+            center,
+            inner_radius,
+            outer_radius,
+            0.0,
+            std::f32::consts::TAU,
+            col,
+        );
+    }
+
+    fn draw_sector(
+        &mut self,
+        center: Vector2,
+        radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        col: Color,
+    ) {
+        add_annular_sector(self, center, 0.0, radius, start_angle, end_angle, col);
+    }
+
+    fn draw_polyline(
+        &mut self,
+        points: &[Vector2],
+        thickness: f32,
+        join: LineJoin,
+        cap: LineCap,
+        closed: bool,
+        col: Color,
+    ) {
+        let style = StrokeStyle::new(thickness).with_join(join).with_cap(cap);
+        add_stroke(self, &[(points.to_vec(), closed)], &style, col);
+    }
+
+    fn draw_line_styled(&mut self, from: Vector2, to: Vector2, style: &StrokeStyle, col: Color) {
+        add_stroke(self, &[(vec![from, to], false)], style, col);
+    }
+
+    fn draw_rect_lines_styled(
+        &mut self,
+        pos: Vector2,
+        size: Vector2,
+        style: &StrokeStyle,
+        col: Color,
+    ) {
+        let min = pos.min(pos + size);
+        let size = size.abs();
+        // Moved in by half the width, and never past the middle
+        let inset = (style.width.abs() / 2.0).min(size.0.min(size.1) / 2.0);
+        let (min, max) = (
+            min + Vector2(inset, inset),
+            min + size - Vector2(inset, inset),
+        );
+        let points = vec![min, Vector2(max.0, min.1), max, Vector2(min.0, max.1)];
+        add_stroke(self, &[(points, true)], style, col);
+    }
+
+    fn draw_circle_lines_styled(
+        &mut self,
+        center: Vector2,
+        radius: f32,
+        style: &StrokeStyle,
+        col: Color,
+    ) {
+        let radius = radius.abs();
+        let segments = circle_segments(radius * self.pixels_per_unit());
+        let points = (0..segments)
+            .map(|i| {
+                let theta = i as f32 / segments as f32 * std::f32::consts::TAU;
+                center + Vector2(theta.cos(), theta.sin()) * radius
+            })
+            .collect();
+        add_stroke(self, &[(points, true)], style, col);
+    }
+
+    fn draw_polygon_lines_styled(&mut self, points: &[Vector2], style: &StrokeStyle, col: Color) {
+        add_stroke(self, &[(points.to_vec(), true)], style, col);
+    }
+}
+
+// The outline of a polygon with sharp corners, which with inner alignment line up with
+// the corners of the filled polygon however sharp they are
+fn add_polygon_outline(
+    d: &mut RendiumDrawHandle,
+    points: &[Vector2],
+    thickness: f32,
+    align: StrokeAlign,
+    col: Color,
+) {
+    let thickness = thickness.abs();
+    if thickness == 0.0 {
+        return;
+    }
+    let middle = offset_polygon(points, align.offset(thickness));
+    let style = StrokeStyle::new(thickness).with_join(LineJoin::Miter(f32::INFINITY));
+    add_stroke(d, &[(middle, true)], &style, col);
+}
+
+// Enough segments that a circle this big on screen is off by at most a quarter of a pixel
+pub(crate) fn circle_segments(radius: f32) -> usize {
+    const TOLERANCE: f32 = 0.25;
+    if radius <= TOLERANCE {
+        return 8;
+    }
+    let step = 2.0 * (1.0 - TOLERANCE / radius).acos();
+    ((std::f32::consts::TAU / step).ceil() as usize).clamp(8, 512)
+}
+
+// Room around anti-aliased shapes for the soft edge, one pixel in the current coordinates
+pub(crate) fn aa_margin(d: &RendiumDrawHandle) -> f32 {
+    1.0 / d.pixels_per_unit().max(1e-6)
+}
+
+// A quad around a shape the fragment shader cuts out. `half` is half the size of the shape
+// along `x_axis` (a unit vector) and its perpendicular, the quad gets the margin and
+// half the outline thickness added.
+pub(crate) fn add_sdf_quad(
+    d: &mut RendiumDrawHandle,
+    center: Vector2,
+    x_axis: Vector2,
+    half: Vector2,
+    col: Color,
+    kind: ShapeKind,
+    params: [f32; 4],
+) {
+    let margin = aa_margin(d) + params[3] / 2.0;
+    let half = half + Vector2(margin, margin);
+    add_sdf_local_quad(d, center, x_axis, -half, half, col, kind, params);
+}
+
+// A quad covering `min` to `max` in the shape's own coordinates, which are centered on
+// `center` and turned to `x_axis`
+#[allow(clippy::too_many_arguments)]
+fn add_sdf_local_quad(
+    d: &mut RendiumDrawHandle,
+    center: Vector2,
+    x_axis: Vector2,
+    min: Vector2,
+    max: Vector2,
+    col: Color,
+    kind: ShapeKind,
+    params: [f32; 4],
+) {
+    let y_axis = x_axis.perpendicular();
+    let base = d.vertices.len() as u32;
+
+    for local in [min, Vector2(max.0, min.1), max, Vector2(min.0, max.1)] {
+        let pos = center + x_axis * local.0 + y_axis * local.1;
+        d.add_shape_vertex(pos, col, local, kind, params, 0);
+    }
+
+    for i in [0, 1, 2, 0, 2, 3] {
+        d.add_index(base + i);
+    }
+}
+
+// A rounded rect, or its outline when `thickness` isn't 0
+fn add_rounded_rect(
+    d: &mut RendiumDrawHandle,
+    pos: Vector2,
+    size: Vector2,
+    radii: [f32; 4],
+    thickness: f32,
+    col: Color,
+) {
+    let half = size.abs() * 0.5;
+    let center = pos + size * 0.5;
+    let thickness = thickness.abs().min(half.0.min(half.1));
+    let radii = radii.map(|r| r.clamp(0.0, half.0.min(half.1)));
+
+    if d.anti_aliasing {
+        // The shader insets the outline's inner edge by the thickness itself
+        let extent = half + Vector2(1.0, 1.0) * aa_margin(d);
+
+        // One quad per corner, since each only needs the radius of its own corner
+        let corners = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+        for (corner, radius) in corners.into_iter().zip(radii) {
+            let far = Vector2::from(corner) * extent;
+            add_sdf_local_quad(
+                d,
+                center,
+                Vector2(1.0, 0.0),
+                far.min(Vector2::zero()),
+                far.max(Vector2::zero()),
+                col,
+                ShapeKind::RoundedRect,
+                [half.0, half.1, radius, thickness],
+            );
+        }
+        return;
+    }
+
+    // Every corner gets the same number of points, so the outline's edges line up
+    let max_radius = radii.iter().fold(0.0f32, |a, &r| a.max(r));
+    let segments = (circle_segments(max_radius * d.pixels_per_unit()) / 4).max(1);
+    let outer = rounded_rect_points(center, half, radii, segments);
+    if thickness == 0.0 {
+        add_convex_fan(d, &outer, col);
+        return;
+    }
+
+    let inner_half = half - Vector2(thickness, thickness);
+    let inner_radii = radii.map(|r| (r - thickness).max(0.0));
+    let inner = rounded_rect_points(center, inner_half, inner_radii, segments);
+    add_closed_strip(d, &outer, &inner, col);
+}
+
+// Points around a rounded rect, `segments + 1` per corner, clockwise on screen
+// starting at the top left corner
+pub(crate) fn rounded_rect_points(
+    center: Vector2,
+    half: Vector2,
+    radii: [f32; 4],
+    segments: usize,
+) -> Vec<Vector2> {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    let corners = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+    let mut points = Vec::with_capacity(4 * (segments + 1));
+    for (i, (corner, radius)) in corners.into_iter().zip(radii).enumerate() {
+        let corner = Vector2::from(corner);
+        let arc_center = center + corner * (half - Vector2(radius, radius));
+        // The top left corner's arc goes from pointing left to pointing up
+        let start = PI + i as f32 * FRAC_PI_2;
+        for s in 0..=segments {
+            let theta = start + s as f32 / segments as f32 * FRAC_PI_2;
+            points.push(arc_center + Vector2(theta.cos(), theta.sin()) * radius);
+        }
+    }
+    points
+}
+
+// A ring, or part of one, between two radii. Angles go from start to end either way round.
+#[allow(clippy::too_many_arguments)]
+fn add_annular_sector(
+    d: &mut RendiumDrawHandle,
+    center: Vector2,
+    inner_radius: f32,
+    outer_radius: f32,
+    start_angle: f32,
+    end_angle: f32,
+    col: Color,
+) {
+    use std::f32::consts::{PI, TAU};
+
+    let (inner_radius, outer_radius) = (
+        inner_radius.abs().min(outer_radius.abs()),
+        inner_radius.abs().max(outer_radius.abs()),
+    );
+    let start = start_angle.min(end_angle);
+    let sweep = (end_angle - start_angle).abs().min(TAU);
+    if outer_radius == 0.0 || sweep == 0.0 {
+        return;
+    }
+    let full = sweep >= TAU;
+
+    let ppu = d.pixels_per_unit();
+    let segments =
+        ((circle_segments(outer_radius * ppu) as f32 * sweep / TAU).ceil() as usize).max(1);
+
+    if !d.anti_aliasing {
+        let outer = arc_points(center, outer_radius, start, sweep, segments);
+        let inner = arc_points(center, inner_radius, start, sweep, segments);
+        add_strip(d, &outer, &inner, col);
+        return;
+    }
+
+    // The geometry only has to cover the shape, the fragment shader cuts out the real edges.
+    // It's grown by the margin, and outwards enough that the straight segments
+    // don't cut off the outer curve.
+    let margin = aa_margin(d);
+    let angle_margin = if full { 0.0 } else { margin / outer_radius };
+    let geometry_start = start - angle_margin;
+    let geometry_sweep = (sweep + angle_margin * 2.0).min(TAU);
+    let step = geometry_sweep / segments as f32;
+    let outer = arc_points(
+        center,
+        (outer_radius + margin) / (step / 2.0).cos(),
+        geometry_start,
+        geometry_sweep,
+        segments,
+    );
+    let inner = arc_points(
+        center,
+        (inner_radius - margin).max(0.0),
+        geometry_start,
+        geometry_sweep,
+        segments,
+    );
+
+    // In the shape's coordinates the middle of the arc is on the x axis
+    let mid = start + sweep / 2.0;
+    let half_angle = if full { PI } else { sweep / 2.0 };
+    let params = [
+        (inner_radius + outer_radius) / 2.0,
+        (outer_radius - inner_radius) / 2.0,
+        half_angle,
+        0.0,
+    ];
+
+    let base = d.vertices.len() as u32;
+    for (o, i) in outer.iter().zip(&inner) {
+        for &p in [o, i] {
+            let local = (p - center).rotate(-mid);
+            d.add_shape_vertex(p, col, local, ShapeKind::Arc, params, 0);
+        }
+    }
+    add_strip_indices(d, base, outer.len() as u32);
+}
+
+// `segments + 1` points along an arc
+fn arc_points(
+    center: Vector2,
+    radius: f32,
+    start: f32,
+    sweep: f32,
+    segments: usize,
+) -> Vec<Vector2> {
+    (0..=segments)
+        .map(|i| {
+            let theta = start + i as f32 / segments as f32 * sweep;
+            center + Vector2(theta.cos(), theta.sin()) * radius
+        })
+        .collect()
+}
+
+// Fills the band between two lines of points with the same length
+pub(crate) fn add_strip(d: &mut RendiumDrawHandle, a: &[Vector2], b: &[Vector2], col: Color) {
+    let base = d.vertices.len() as u32;
+    for (p, q) in a.iter().zip(b) {
+        d.add_vertex([p.0, p.1, 0.0], col, [0.0, 0.0], 0);
+        d.add_vertex([q.0, q.1, 0.0], col, [0.0, 0.0], 0);
+    }
+    add_strip_indices(d, base, a.len().min(b.len()) as u32);
+}
+
+// Like `add_strip`, for two closed loops
+pub(crate) fn add_closed_strip(
+    d: &mut RendiumDrawHandle,
+    a: &[Vector2],
+    b: &[Vector2],
+    col: Color,
+) {
+    let close = |points: &[Vector2]| {
+        let mut points = points.to_vec();
+        points.extend(points.first().copied());
+        points
+    };
+    add_strip(d, &close(a), &close(b), col);
+}
+
+// Two triangles between each pair of neighbouring point pairs, which are interleaved from `base`
+fn add_strip_indices(d: &mut RendiumDrawHandle, base: u32, pairs: u32) {
+    for i in 0..pairs.saturating_sub(1) {
+        let a = base + i * 2;
+        for index in [a, a + 1, a + 2, a + 2, a + 1, a + 3] {
+            d.add_index(index);
+        }
+    }
+}
+
+// A triangle grown by the margin, each vertex knows how far it is from the original edges
+pub(crate) fn add_sdf_triangle(d: &mut RendiumDrawHandle, points: [Vector2; 3], col: Color) {
+    let area = (points[1] - points[0]).cross(points[2] - points[0]);
+    if area == 0.0 {
+        return;
+    }
+
+    // Unit normals of each edge, pointing inside whatever the winding
+    let normals: [Vector2; 3] = std::array::from_fn(|i| {
+        let edge = points[(i + 1) % 3] - points[i];
+        (edge.perpendicular() * area.signum()).normalized()
+    });
+
+    let margin = aa_margin(d);
+    let base = d.vertices.len() as u32;
+    for i in 0..3 {
+        // Moves the corner out so both of its edges move out by the margin. Very sharp
+        // corners are limited so they don't shoot off, they just lose a bit of softness.
+        let (n1, n2) = (normals[(i + 2) % 3], normals[i]);
+        let k = margin / (1.0 + n1.dot(n2)).max(0.05);
+        let p = points[i] - (n1 + n2) * k;
+
+        let distances: [f32; 3] = std::array::from_fn(|e| (p - points[e]).dot(normals[e]));
+        d.add_shape_vertex(
+            p,
+            col,
+            Vector2::zero(),
+            ShapeKind::Triangle,
+            [distances[0], distances[1], distances[2], 0.0],
+            0,
+        );
+    }
+
+    for i in 0..3 {
+        d.add_index(base + i);
+    }
+}
+
+// Plain triangles fanning out from the first point
+pub(crate) fn add_convex_fan(d: &mut RendiumDrawHandle, points: &[Vector2], col: Color) {
+    if points.len() < 3 {
+        return;
+    }
+
+    let base = d.vertices.len() as u32;
+    for p in points {
+        d.add_vertex([p.0, p.1, 0.0], col, [0.0, 0.0], 0);
+    }
+    for i in 1..points.len() as u32 - 1 {
+        d.add_index(base);
+        d.add_index(base + i);
+        d.add_index(base + i + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::Camera2D;
+    use std::collections::HashMap;
+    use std::f32::consts::FRAC_PI_2;
+    use winit::dpi::PhysicalSize;
+
+    fn handle(anti_aliasing: bool) -> RendiumDrawHandle {
+        let mut d = RendiumDrawHandle::new(PhysicalSize::new(800, 600), HashMap::new());
+        d.set_anti_aliasing(anti_aliasing);
+        d
+    }
+
+    fn positions(d: &RendiumDrawHandle) -> Vec<Vector2> {
+        d.vertices
+            .iter()
+            .map(|v| Vector2(v.position[0], v.position[1]))
+            .collect()
+    }
+
+    fn bounds(points: &[Vector2]) -> (Vector2, Vector2) {
+        points.iter().fold(
+            (Vector2(f32::MAX, f32::MAX), Vector2(f32::MIN, f32::MIN)),
+            |(min, max), &p| {
+                (
+                    Vector2(min.0.min(p.0), min.1.min(p.1)),
+                    Vector2(max.0.max(p.0), max.1.max(p.1)),
+                )
+            },
+        )
+    }
+
+    fn assert_near(a: Vector2, b: Vector2) {
+        assert!(a.approx_eq(b, 1e-3), "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn rect_pro_turns_around_the_origin() {
+        let rect = Rect::new(Vector2(100.0, 50.0), Vector2(40.0, 20.0));
+        for (origin, rotation) in [
+            (Vector2::zero(), 0.0),
+            (Vector2(20.0, 10.0), 0.0),
+            (Vector2::zero(), FRAC_PI_2),
+            (Vector2(40.0, 20.0), 0.6),
+        ] {
+            let mut d = handle(false);
+            d.draw_rect_pro(rect, origin, rotation, Color::WHITE);
+            // The origin is the point of the rect that ends up at its position
+            let expected = [(0.0, 0.0), (40.0, 0.0), (40.0, 20.0), (0.0, 20.0)]
+                .map(|(x, y)| rect.pos + (Vector2(x, y) - origin).rotate(rotation));
+            let drawn = positions(&d);
+            assert_eq!(drawn.len(), 4);
+            for corner in expected {
+                assert!(
+                    drawn.iter().any(|p| p.approx_eq(corner, 1e-3)),
+                    "{corner:?} not in {drawn:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rect_pro_quarter_turn_is_clockwise() {
+        let mut d = handle(false);
+        let rect = Rect::new(Vector2(10.0, 10.0), Vector2(40.0, 20.0));
+        d.draw_rect_pro(rect, Vector2::zero(), FRAC_PI_2, Color::WHITE);
+        // The long side now points down, and the rect hangs to the left of its position
+        let (min, max) = bounds(&positions(&d));
+        assert_near(min, Vector2(-10.0, 10.0));
+        assert_near(max, Vector2(10.0, 50.0));
+    }
+
+    #[test]
+    fn anti_aliased_rect_pro_is_centered_the_same() {
+        let rect = Rect::new(Vector2(100.0, 50.0), Vector2(40.0, 20.0));
+        let origin = Vector2(40.0, 0.0);
+        let (mut plain, mut smooth) = (handle(false), handle(true));
+        plain.draw_rect_pro(rect, origin, 0.6, Color::WHITE);
+        smooth.draw_rect_pro(rect, origin, 0.6, Color::WHITE);
+        let center = |d: &RendiumDrawHandle| {
+            let points = positions(d);
+            points.iter().fold(Vector2::zero(), |sum, &p| sum + p) / points.len() as f32
+        };
+        assert_near(center(&plain), center(&smooth));
+    }
+
+    #[test]
+    fn circle_segments_grow_with_the_radius() {
+        assert_eq!(circle_segments(0.1), 8);
+        assert_eq!(circle_segments(1e6), 512);
+        let counts: Vec<_> = [1.0, 10.0, 100.0, 1000.0]
+            .into_iter()
+            .map(circle_segments)
+            .collect();
+        assert!(counts.windows(2).all(|w| w[0] <= w[1]), "{counts:?}");
+        // A quarter of a pixel off at most
+        for radius in [5.0, 50.0, 500.0] {
+            let step = std::f32::consts::TAU / circle_segments(radius) as f32;
+            assert!(radius * (1.0 - (step / 2.0).cos()) <= 0.25);
+        }
+    }
+
+    #[test]
+    fn circle_uses_the_given_segments() {
+        let mut d = handle(false);
+        d.draw_circle(Vector2(100.0, 100.0), 20, Color::WHITE, 12);
+        assert_eq!(d.vertices.len(), 13);
+        assert_eq!(d.indices.len(), 12 * 3);
+
+        let mut d = handle(false);
+        d.draw_circle(Vector2(100.0, 100.0), 20, Color::WHITE, 2);
+        assert!(d.vertices.is_empty());
+    }
+
+    #[test]
+    fn capsule_segments_follow_the_zoom() {
+        let capsule = |zoom: f32| {
+            let mut d = handle(false);
+            let camera = Camera2D::new(Vector2::zero(), Vector2::zero()).with_zoom(zoom);
+            d.with_camera(&camera, |d| {
+                d.draw_capsule(Vector2(0.0, 0.0), Vector2(30.0, 0.0), 10.0, Color::WHITE);
+            });
+            d.vertices.len()
+        };
+        // Two half circles, each with half the segments of the whole circle
+        assert_eq!(capsule(1.0), (circle_segments(10.0) / 2 + 1) * 2);
+        assert_eq!(capsule(8.0), (circle_segments(80.0) / 2 + 1) * 2);
+        assert!(capsule(8.0) > capsule(1.0));
+    }
+
+    #[test]
+    fn negative_capsule_radius_is_the_same_capsule() {
+        for anti_aliasing in [false, true] {
+            let (mut a, mut b) = (handle(anti_aliasing), handle(anti_aliasing));
+            a.draw_capsule(Vector2(10.0, 20.0), Vector2(60.0, 40.0), 8.0, Color::WHITE);
+            b.draw_capsule(Vector2(10.0, 20.0), Vector2(60.0, 40.0), -8.0, Color::WHITE);
+            assert_eq!(positions(&a), positions(&b));
+        }
+    }
+
+    #[test]
+    fn rect_outlines_move_with_the_alignment() {
+        let pos = Vector2(100.0, 100.0);
+        let size = Vector2(80.0, 60.0);
+        for (align, grow) in [
+            (StrokeAlign::Inner, 0.0),
+            (StrokeAlign::Center, 2.0),
+            (StrokeAlign::Outer, 4.0),
+        ] {
+            for rounded in [false, true] {
+                let mut d = handle(false);
+                if rounded {
+                    d.draw_rect_rounded_lines(pos, size, [0.0; 4], 4.0, align, Color::WHITE);
+                } else {
+                    d.draw_rect_lines_v(pos, size, 4.0, align, Color::WHITE);
+                }
+                let (min, max) = bounds(&positions(&d));
+                assert_near(min, pos - Vector2(grow, grow));
+                assert_near(max, pos + size + Vector2(grow, grow));
+            }
+        }
+    }
+
+    #[test]
+    fn circle_outlines_move_with_the_alignment() {
+        let center = Vector2(200.0, 200.0);
+        for (align, inner, outer) in [
+            (StrokeAlign::Inner, 46.0, 50.0),
+            (StrokeAlign::Center, 48.0, 52.0),
+            (StrokeAlign::Outer, 50.0, 54.0),
+        ] {
+            let mut d = handle(false);
+            d.draw_circle_lines(center, 50.0, 4.0, align, Color::WHITE);
+            let distances: Vec<_> = positions(&d).iter().map(|p| p.distance(center)).collect();
+            let nearest = distances.iter().copied().fold(f32::MAX, f32::min);
+            let farthest = distances.iter().copied().fold(0.0, f32::max);
+            assert!((nearest - inner).abs() < 1e-3, "{align:?}: {nearest}");
+            assert!((farthest - outer).abs() < 1e-3, "{align:?}: {farthest}");
+        }
+    }
+
+    #[test]
+    fn polygon_outlines_move_with_the_alignment() {
+        let square = [
+            Vector2(100.0, 100.0),
+            Vector2(200.0, 100.0),
+            Vector2(200.0, 200.0),
+            Vector2(100.0, 200.0),
+        ];
+        for (align, grow) in [
+            (StrokeAlign::Inner, 0.0),
+            (StrokeAlign::Center, 3.0),
+            (StrokeAlign::Outer, 6.0),
+        ] {
+            // Either way round, outside is outside
+            for points in [square.to_vec(), square.iter().rev().copied().collect()] {
+                let mut d = handle(false);
+                d.draw_polygon_lines(&points, 6.0, align, Color::WHITE);
+                let (min, max) = bounds(&positions(&d));
+                assert_near(min, Vector2(100.0 - grow, 100.0 - grow));
+                assert_near(max, Vector2(200.0 + grow, 200.0 + grow));
+            }
+        }
     }
 }
@@ -0,0 +1,136 @@
+// This module has methods for drawing lots of sprites with GPU instancing
+use crate::RendiumDrawHandle;
+use crate::texture::get_texture_index;
+use crate::types::{Color, Matrix3, Vector2};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sprite {
+    pub pos: Vector2,
+    pub size: Vector2,
+    // In radians, around the center of the sprite
+    pub rotation: f32,
+    // The part of the texture to show, in 0..1 texture coordinates
+    pub uv_offset: Vector2,
+    pub uv_size: Vector2,
+    pub col: Color,
+}
+
+impl Sprite {
+    pub fn new(pos: Vector2, size: Vector2) -> Self {
+        Self {
+            pos,
+            size,
+            rotation: 0.0,
+            uv_offset: Vector2(0.0, 0.0),
+            uv_size: Vector2(1.0, 1.0),
+            col: Color::WHITE,
+        }
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_uv(mut self, offset: Vector2, size: Vector2) -> Self {
+        self.uv_offset = offset;
+        self.uv_size = size;
+        self
+    }
+
+    pub fn with_color(mut self, col: Color) -> Self {
+        self.col = col;
+        self
+    }
+}
+
+// What actually goes into the instance buffer, the vertex shader turns it into a quad
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub(crate) struct SpriteInstance {
+    position: [f32; 2],
+    size: [f32; 2],
+    rotation: f32,
+    uv_rect: [f32; 4],
+    color: [u8; 4],
+}
+
+impl SpriteInstance {
+    pub(crate) fn desc() -> wgpu::VertexBufferLayout<'static> {
+        const ATTRIBUTES: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![
+            0 => Float32x2,
+            1 => Float32x2,
+            2 => Float32,
+            3 => Float32x4,
+            4 => Unorm8x4,
+        ];
+
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<SpriteInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &ATTRIBUTES,
+        }
+    }
+}
+
+impl From<Sprite> for SpriteInstance {
+    fn from(s: Sprite) -> Self {
+        Self {
+            position: s.pos.into(),
+            size: s.size.into(),
+            rotation: s.rotation,
+            uv_rect: [s.uv_offset.0, s.uv_offset.1, s.uv_size.0, s.uv_size.1],
+            color: [s.col.0, s.col.1, s.col.2, s.col.3],
+        }
+    }
+}
+
+pub trait DrawSprite {
+    fn draw_sprite(&mut self, name: &str, sprite: Sprite);
+    fn draw_sprites(&mut self, name: &str, sprites: &[Sprite]);
+}
+
+impl DrawSprite for RendiumDrawHandle {
+    fn draw_sprite(&mut self, name: &str, sprite: Sprite) {
+        self.draw_sprites(name, &[sprite]);
+    }
+
+    fn draw_sprites(&mut self, name: &str, sprites: &[Sprite]) {
+        let tex_index = match get_texture_index(self, name) {
+            Some(i) => i,
+            None => {
+                return;
+            }
+        };
+
+        let transform = self.get_transform();
+        for sprite in sprites {
+            let sprite = if transform == Matrix3::IDENTITY {
+                *sprite
+            } else {
+                apply_transform(*sprite, &transform)
+            };
+            self.add_sprite(sprite.into(), tex_index);
+        }
+    }
+}
+
+// Sprites are rotated rects, so shear can't be kept. The center is moved exactly,
+// the size is scaled by the length of each axis and the rotation by the angle of the x axis.
+fn apply_transform(mut sprite: Sprite, m: &Matrix3) -> Sprite {
+    let x_axis = m.transform_vector(Vector2(1.0, 0.0));
+    let y_axis = m.transform_vector(Vector2(0.0, 1.0));
+
+    // A mirrored transform flips the sprite
+    let flip = if x_axis.0 * y_axis.1 - x_axis.1 * y_axis.0 < 0.0 {
+        -1.0
+    } else {
+        1.0
+    };
+
+    let center = m.transform_point(sprite.pos + sprite.size * 0.5);
+    sprite.size *= Vector2(x_axis.length(), y_axis.length() * flip);
+    sprite.pos = center - sprite.size * 0.5;
+    sprite.rotation += x_axis.1.atan2(x_axis.0);
+    sprite
+}
@@ -0,0 +1,423 @@
+// This module turns lines through points into outlines, which are filled with the
+// non-zero rule. Overlapping parts of the outline merge, so a translucent line
+// is never drawn twice in the same place.
+use crate::RendiumDrawHandle;
+use crate::shapes::circle_segments;
+use crate::tessellate::{FillRule, Shade, add_fill_shaded, signed_area, solid};
+use crate::types::{Color, Vector2};
+
+// How the segments of a line are connected
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineJoin {
+    // Sharp corners, the number is the miter limit. Corners where the point would stick out
+    // further than that many times half the thickness are beveled instead, 4 like SVG is a good start.
+    Miter(f32),
+    // The corner is cut off straight
+    Bevel,
+    Round,
+}
+
+impl Default for LineJoin {
+    fn default() -> Self {
+        LineJoin::Miter(4.0)
+    }
+}
+
+// How the ends of a line look
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineCap {
+    // Ends right at the point
+    #[default]
+    Butt,
+    // Goes on past the point by half the thickness
+    Square,
+    Round,
+}
+
+// Where the outline of a shape goes, compared to the shape's edge
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StrokeAlign {
+    // All inside, so the outline doesn't make the shape any bigger
+    Inner,
+    // Half inside and half outside
+    #[default]
+    Center,
+    Outer,
+}
+
+impl StrokeAlign {
+    // How far outside the edge the middle of an outline this thick is
+    pub(crate) fn offset(self, thickness: f32) -> f32 {
+        match self {
+            StrokeAlign::Inner => -thickness / 2.0,
+            StrokeAlign::Center => 0.0,
+            StrokeAlign::Outer => thickness / 2.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StrokeStyle {
+    pub width: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    // Lengths of dashes and the gaps between them, taking turns and starting with a dash.
+    // A pattern with an odd number of lengths is used twice, like in SVG. Empty means no dashes.
+    pub dash: Vec<f32>,
+    // How far into the pattern the line starts, moving it makes the dashes march along
+    pub dash_offset: f32,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl StrokeStyle {
+    pub fn new(width: f32) -> Self {
+        Self {
+            width,
+            join: LineJoin::default(),
+            cap: LineCap::default(),
+            dash: Vec::new(),
+            dash_offset: 0.0,
+        }
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn with_dash(mut self, pattern: &[f32], offset: f32) -> Self {
+        self.dash = pattern.to_vec();
+        self.dash_offset = offset;
+        self
+    }
+}
+
+// The polygon with every edge moved `distance` outwards, negative moves them in. The corners stay
+// sharp, so an outline with miter joins centered on the result has the polygon as one edge.
+pub(crate) fn offset_polygon(points: &[Vector2], distance: f32) -> Vec<Vector2> {
+    let mut points = points.to_vec();
+    points.dedup();
+    while points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    if points.len() < 3 || distance == 0.0 {
+        return points;
+    }
+
+    // Outwards is on the left going clockwise on screen, and on the right the other way round
+    let side = if signed_area(&points) > 0.0 {
+        -1.0
+    } else {
+        1.0
+    };
+    let n = points.len();
+    let normal = |i: usize| {
+        (points[(i + 1) % n] - points[i])
+            .normalized()
+            .perpendicular()
+            * side
+    };
+    (0..n)
+        .map(|i| {
+            let (before, after) = (normal((i + n - 1) % n), normal(i));
+            // Where the two moved edges meet. Edges that double back just move along their normal.
+            let cos = before.dot(after);
+            if cos < -0.999 {
+                points[i] + after * distance
+            } else {
+                points[i] + (before + after) * (distance / (1.0 + cos))
+            }
+        })
+        .collect()
+}
+
+// Strokes all the lines in one go, so where they cross each other it's only drawn once.
+// Each line is its points and whether it's closed.
+pub(crate) fn add_stroke(
+    d: &mut RendiumDrawHandle,
+    lines: &[(Vec<Vector2>, bool)],
+    style: &StrokeStyle,
+    col: Color,
+) {
+    add_stroke_shaded(d, lines, style, &solid(col));
+}
+
+pub(crate) fn add_stroke_shaded(
+    d: &mut RendiumDrawHandle,
+    lines: &[(Vec<Vector2>, bool)],
+    style: &StrokeStyle,
+    shade: Shade,
+) {
+    let pixels_per_unit = d.pixels_per_unit();
+    let mut contours = Vec::new();
+    for (points, closed) in lines {
+        let mut outline = |points: &[Vector2], closed: bool| {
+            contours.extend(stroke_outline(
+                points,
+                style.width,
+                style.join,
+                style.cap,
+                closed,
+                pixels_per_unit,
+            ));
+        };
+        match dash(points, *closed, &style.dash, style.dash_offset) {
+            Some(dashes) => dashes.iter().for_each(|dash| outline(dash, false)),
+            None => outline(points, *closed),
+        }
+    }
+    add_fill_shaded(d, &contours, FillRule::NonZero, shade);
+}
+
+// Cuts the line into its dashes. A dash going round a corner keeps its join, and on a closed
+// line the last and first dash are one dash when they meet. None when the pattern makes no gaps.
+pub(crate) fn dash(
+    points: &[Vector2],
+    closed: bool,
+    pattern: &[f32],
+    offset: f32,
+) -> Option<Vec<Vec<Vector2>>> {
+    let pattern = if pattern.len() % 2 == 1 {
+        [pattern, pattern].concat()
+    } else {
+        pattern.to_vec()
+    };
+    let total: f32 = pattern.iter().sum();
+    if pattern.is_empty()
+        || pattern.iter().any(|&l| l < 0.0 || !l.is_finite())
+        || total <= 0.0
+        || pattern.iter().skip(1).step_by(2).all(|&gap| gap == 0.0)
+    {
+        return None;
+    }
+
+    // Where in the pattern the line starts
+    let mut i = 0;
+    let mut phase = offset.rem_euclid(total);
+    // A zero length dash right at the start is still a dot
+    while phase > pattern[i] || (phase == pattern[i] && pattern[i] > 0.0) {
+        phase -= pattern[i];
+        i = (i + 1) % pattern.len();
+    }
+    let mut left = pattern[i] - phase;
+    let starts_on = i % 2 == 0;
+
+    let (&first, _) = points.split_first()?;
+    let mut dashes = Vec::new();
+    let mut current = if starts_on { vec![first] } else { Vec::new() };
+    let mut switched = false;
+
+    let next = closed.then_some(first);
+    for (a, b) in points.iter().zip(points.iter().skip(1).chain(next.iter())) {
+        let (a, b) = (*a, *b);
+        let length = a.distance(b);
+        let mut pos = 0.0;
+        while length - pos > left {
+            pos += left;
+            let p = a.lerp(b, pos / length);
+            if i % 2 == 0 {
+                current.push(p);
+                dashes.push(std::mem::take(&mut current));
+            } else {
+                current = vec![p];
+            }
+            i = (i + 1) % pattern.len();
+            left = pattern[i];
+            switched = true;
+        }
+        left -= length - pos;
+        if i % 2 == 0 {
+            current.push(b);
+        }
+    }
+
+    if !switched {
+        // The whole line fits in one dash
+        return None;
+    }
+    if i % 2 == 0 && !current.is_empty() {
+        if closed && starts_on && !dashes.is_empty() {
+            // The dash going over the start of the line
+            let first_dash = dashes.remove(0);
+            current.extend_from_slice(&first_dash[1..]);
+        }
+        dashes.push(current);
+    }
+    Some(dashes)
+}
+
+// The outlines of a line with the given thickness, with the line on their left on a y-down screen.
+// `pixels_per_unit` decides how smooth round joins and caps are.
+pub(crate) fn stroke_outline(
+    points: &[Vector2],
+    thickness: f32,
+    join: LineJoin,
+    cap: LineCap,
+    closed: bool,
+    pixels_per_unit: f32,
+) -> Vec<Vec<Vector2>> {
+    let mut points = points.to_vec();
+    points.dedup();
+    if closed && points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+
+    let stroker = Stroker {
+        half: thickness.abs() / 2.0,
+        join,
+        round_segments: circle_segments(thickness.abs() / 2.0 * pixels_per_unit),
+    };
+    if stroker.half == 0.0 || points.is_empty() {
+        return Vec::new();
+    }
+
+    if points.len() == 1 {
+        // A dot, only caps that stick out make one
+        let (p, h) = (points[0], stroker.half);
+        let mut outline = Vec::new();
+        match cap {
+            LineCap::Butt => return Vec::new(),
+            LineCap::Square => outline.extend([
+                p + Vector2(-h, -h),
+                p + Vector2(-h, h),
+                p + Vector2(h, h),
+                p + Vector2(h, -h),
+            ]),
+            LineCap::Round => {
+                stroker.arc(&mut outline, p, Vector2(h, 0.0), -std::f32::consts::TAU);
+                outline.pop();
+            }
+        }
+        return vec![outline];
+    }
+
+    if closed && points.len() > 2 {
+        // Two loops going opposite ways, the inner one cuts a hole in the outer one
+        let mut reversed = points.clone();
+        reversed.reverse();
+        return vec![stroker.side(&points, true), stroker.side(&reversed, true)];
+    }
+
+    let mut reversed = points.clone();
+    reversed.reverse();
+    let n = points.len();
+
+    let mut outline = stroker.side(&points, false);
+    stroker.cap(&mut outline, points[n - 2], points[n - 1], cap);
+    outline.extend(stroker.side(&reversed, false));
+    stroker.cap(&mut outline, points[1], points[0], cap);
+    vec![outline]
+}
+
+struct Stroker {
+    half: f32,
+    join: LineJoin,
+    // For a whole circle with this radius
+    round_segments: usize,
+}
+
+impl Stroker {
+    // The side of the line to the right of its direction on a y-down screen, with the joins
+    fn side(&self, points: &[Vector2], closed: bool) -> Vec<Vector2> {
+        let n = points.len();
+        let segment_count = if closed { n } else { n - 1 };
+        let normal = |i: usize| {
+            let d = (points[(i + 1) % n] - points[i]).normalized();
+            d.perpendicular() * self.half
+        };
+
+        let mut out = Vec::new();
+        if !closed {
+            out.push(points[0] + normal(0));
+        }
+
+        let joins = if closed { 0..n } else { 1..n - 1 };
+        for i in joins {
+            let prev = (i + segment_count - 1) % segment_count;
+            self.join(&mut out, points[i], normal(prev), normal(i));
+        }
+
+        if !closed {
+            out.push(points[n - 1] + normal(n - 2));
+        }
+        out
+    }
+
+    // The corner at `p` between a segment with normal `n1` and the next one with `n2`
+    fn join(&self, out: &mut Vec<Vector2>, p: Vector2, n1: Vector2, n2: Vector2) {
+        let turn = n1.cross(n2);
+        let dot = n1.dot(n2) / (self.half * self.half);
+
+        if turn.abs() < 1e-6 * self.half * self.half && dot > 0.0 {
+            // Straight on
+            out.push(p + n1);
+            return;
+        }
+
+        if turn > 0.0 {
+            // The inside of the corner. Going through the point itself keeps the outline
+            // winding the same way, so the overlap is filled once.
+            out.extend([p + n1, p, p + n2]);
+            return;
+        }
+
+        match self.join {
+            LineJoin::Miter(limit) => {
+                let mid = (n1 + n2).normalized();
+                let cos_half = mid.dot(n1) / self.half;
+                if cos_half > 1e-6 && 1.0 / cos_half <= limit {
+                    out.push(p + mid * (self.half / cos_half));
+                } else {
+                    out.extend([p + n1, p + n2]);
+                }
+            }
+            LineJoin::Bevel => out.extend([p + n1, p + n2]),
+            LineJoin::Round => {
+                // A u-turn has no inside, so it goes round the far side
+                let sweep = if turn.abs() < 1e-6 * self.half * self.half {
+                    -std::f32::consts::PI
+                } else {
+                    n1.angle_to(n2)
+                };
+                self.arc(out, p, n1, sweep);
+            }
+        }
+    }
+
+    // From `p + from`, `sweep` radians around `p`
+    fn arc(&self, out: &mut Vec<Vector2>, p: Vector2, from: Vector2, sweep: f32) {
+        let from = from.normalized() * self.half;
+        let segments = ((self.round_segments as f32 * sweep.abs() / std::f32::consts::TAU).ceil()
+            as usize)
+            .max(1);
+        for i in 0..=segments {
+            out.push(p + from.rotate(sweep * i as f32 / segments as f32));
+        }
+    }
+
+    // The end of a line coming from `prev` and ending at `end`, going round from the side
+    // `side` finished on to the other one
+    fn cap(&self, out: &mut Vec<Vector2>, prev: Vector2, end: Vector2, cap: LineCap) {
+        let d = (end - prev).normalized() * self.half;
+        let n = d.perpendicular();
+        match cap {
+            LineCap::Butt => out.extend([end + n, end - n]),
+            LineCap::Square => out.extend([end + n + d, end - n + d]),
+            LineCap::Round => self.arc(out, end, n, -std::f32::consts::PI),
+        }
+    }
+}
@@ -0,0 +1,1294 @@
+// This module loads SVG files into paths, which are drawn like any other path, so they stay sharp
+// at any scale. It covers what icons use: paths and the basic shapes, groups and `use`, fills
+// and strokes, transforms, and linear and radial gradients. Text, images, filters, masks, clipping
+// and style sheets are skipped. Group opacity is passed on to each shape in the group, so
+// overlapping shapes in a translucent group show through each other.
+use anyhow::{Context, bail};
+use roxmltree::{Document, Node, ParsingOptions};
+
+use crate::curves::flatten_tolerance;
+use crate::gradient::{ColorStop, GradientShape, gradient_shade};
+use crate::path::Path;
+use crate::stroke::{LineCap, LineJoin, StrokeStyle, add_stroke_shaded};
+use crate::tessellate::{FillRule, Shade, add_fill_shaded, solid};
+use crate::types::{Color, Matrix3, Rect, Vector2, Vector3, css};
+use crate::{RendiumDrawHandle, RendiumInstance};
+
+const XLINK: &str = "http://www.w3.org/1999/xlink";
+// Deep enough for any real file, and stops gradients that refer to themselves
+const MAX_DEPTH: u32 = 32;
+// Far more than any real file has. `use` can copy a group many times over, and groups of
+// copies can be copied again, so a small file could otherwise ask for billions.
+const MAX_ELEMENTS: usize = 1_000_000;
+const MAX_SHAPES: usize = 100_000;
+
+#[derive(Clone, Debug)]
+pub struct Svg {
+    size: Vector2,
+    view_box: Rect,
+    shapes: Vec<SvgShape>,
+}
+
+#[derive(Clone, Debug)]
+struct SvgShape {
+    path: Path,
+    // From the shape's own coordinates to the view box
+    transform: Matrix3,
+    fill: Option<(Paint, FillRule)>,
+    stroke: Option<(Paint, StrokeStyle)>,
+}
+
+#[derive(Clone, Debug)]
+enum Paint {
+    Solid(Color),
+    Gradient {
+        shape: GradientShape,
+        stops: Vec<ColorStop>,
+        // From the shape's own coordinates to the gradient's
+        to_gradient: Matrix3,
+        opacity: f32,
+    },
+}
+
+impl Svg {
+    pub fn load(filename: &str) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(filename)
+            .with_context(|| format!("Couldn't read SVG file {filename:?}"))?;
+        Self::parse(&text).with_context(|| format!("Couldn't load SVG file {filename:?}"))
+    }
+
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let options = ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        };
+        let doc = Document::parse_with_options(text, options)?;
+        let root = doc.root_element();
+        if root.tag_name().name() != "svg" {
+            bail!(
+                "Not an SVG file, the root element is <{}>",
+                root.tag_name().name()
+            );
+        }
+
+        let view_box = root.attribute("viewBox").and_then(|v| {
+            let n = numbers(v);
+            (n.len() == 4 && n[2] > 0.0 && n[3] > 0.0)
+                .then(|| Rect::new(Vector2(n[0], n[1]), Vector2(n[2], n[3])))
+        });
+        // Percentages are of a window the file doesn't know about, so they count as missing
+        let length_attr = |name| {
+            root.attribute(name)
+                .filter(|v| !v.trim_end().ends_with('%'))
+                .and_then(|v| length(v, 0.0))
+                .filter(|&l| l > 0.0)
+        };
+        // With only one of them, the other keeps the view box's aspect ratio
+        let fallback = view_box.map_or(Vector2(100.0, 100.0), |v| v.size);
+        let size = match (length_attr("width"), length_attr("height")) {
+            (Some(w), Some(h)) => Vector2(w, h),
+            (Some(w), None) => Vector2(w, w * fallback.1 / fallback.0),
+            (None, Some(h)) => Vector2(h * fallback.0 / fallback.1, h),
+            (None, None) => fallback,
+        };
+        let view_box = view_box.unwrap_or(Rect::new(Vector2::zero(), size));
+
+        let mut parser = Parser {
+            doc: &doc,
+            view_box,
+            shapes: Vec::new(),
+            using: Vec::new(),
+            elements: 0,
+        };
+        let style = Style::default();
+        for child in root.children() {
+            parser.element(child, &style, Matrix3::IDENTITY, 0);
+        }
+
+        Ok(Self {
+            size,
+            view_box,
+            shapes: parser.shapes,
+        })
+    }
+
+    // The size the file asks for, from its width and height or its view box
+    pub fn size(&self) -> Vector2 {
+        self.size
+    }
+
+    // The part of the drawing's coordinates that gets shown
+    pub fn view_box(&self) -> Rect {
+        self.view_box
+    }
+}
+
+pub trait DrawSvg {
+    // The view box is stretched to fill the rect
+    fn draw_svg(&mut self, svg: &Svg, pos: Vector2, size: Vector2);
+}
+
+impl DrawSvg for RendiumDrawHandle {
+    fn draw_svg(&mut self, svg: &Svg, pos: Vector2, size: Vector2) {
+        let view_box = svg.view_box;
+        self.push_transform();
+        self.translate(pos);
+        self.scale(Vector2(size.0 / view_box.size.0, size.1 / view_box.size.1));
+        self.translate(-view_box.pos);
+
+        for shape in &svg.shapes {
+            self.push_transform();
+            self.apply_transform(shape.transform);
+            // Flattened here, so curves are as smooth as this size needs
+            let lines = shape.path.flatten(flatten_tolerance(self));
+
+            if let Some((paint, rule)) = &shape.fill {
+                let contours: Vec<Vec<Vector2>> =
+                    lines.iter().map(|(points, _)| points.clone()).collect();
+                with_shade(self, paint, |d, shade| {
+                    add_fill_shaded(d, &contours, *rule, shade)
+                });
+            }
+            if let Some((paint, style)) = &shape.stroke {
+                with_shade(self, paint, |d, shade| {
+                    add_stroke_shaded(d, &lines, style, shade)
+                });
+            }
+            self.pop_transform();
+        }
+
+        self.pop_transform();
+    }
+}
+
+fn with_shade(
+    d: &mut RendiumDrawHandle,
+    paint: &Paint,
+    f: impl FnOnce(&mut RendiumDrawHandle, Shade),
+) {
+    match paint {
+        Paint::Solid(col) => f(d, &solid(*col)),
+        Paint::Gradient {
+            shape,
+            stops,
+            to_gradient,
+            opacity,
+        } => {
+            let tint = Color::WHITE.fade(*opacity);
+            let shade = gradient_shade(d, *shape, stops, *to_gradient, tint);
+            f(d, &shade);
+        }
+    }
+}
+
+pub trait SvgLoad {
+    // Draws the SVG into a texture of the given size, for `draw_texture` and sprites
+    fn load_svg_texture(
+        &mut self,
+        filename: &str,
+        tex_name: &str,
+        width: u32,
+        height: u32,
+    ) -> anyhow::Result<()>;
+}
+
+impl SvgLoad for RendiumInstance {
+    fn load_svg_texture(
+        &mut self,
+        filename: &str,
+        tex_name: &str,
+        width: u32,
+        height: u32,
+    ) -> anyhow::Result<()> {
+        let svg = Svg::load(filename)?;
+        let size = Vector2(width as f32, height as f32);
+        self.draw_to_texture(tex_name, width, height, |d| {
+            d.draw_svg(&svg, Vector2::zero(), size)
+        })
+    }
+}
+
+// The inherited properties, as they stand at an element
+#[derive(Clone, Debug)]
+struct Style {
+    fill: Option<PaintValue>,
+    fill_opacity: f32,
+    fill_rule: FillRule,
+    stroke: Option<PaintValue>,
+    stroke_opacity: f32,
+    stroke_width: f32,
+    join: LineJoin,
+    miter_limit: f32,
+    cap: LineCap,
+    dash: Vec<f32>,
+    dash_offset: f32,
+    color: Color,
+    visible: bool,
+    // Not inherited in SVG, but groups pass theirs on to their shapes
+    opacity: f32,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            fill: Some(PaintValue::Color(Color::BLACK)),
+            fill_opacity: 1.0,
+            fill_rule: FillRule::NonZero,
+            stroke: None,
+            stroke_opacity: 1.0,
+            stroke_width: 1.0,
+            join: LineJoin::Miter(4.0),
+            miter_limit: 4.0,
+            cap: LineCap::Butt,
+            dash: Vec::new(),
+            dash_offset: 0.0,
+            color: Color::BLACK,
+            visible: true,
+            opacity: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum PaintValue {
+    Color(Color),
+    CurrentColor,
+    // A gradient by id, and what to use if there's no such gradient
+    Url(String, Option<Box<PaintValue>>),
+}
+
+struct Parser<'a, 'input> {
+    doc: &'a Document<'input>,
+    view_box: Rect,
+    shapes: Vec<SvgShape>,
+    // The `use` elements being drawn, outermost first
+    using: Vec<Node<'a, 'input>>,
+    // How many elements have been drawn, counting every copy made by `use`
+    elements: usize,
+}
+
+impl<'a, 'input> Parser<'a, 'input> {
+    fn element(&mut self, node: Node<'a, 'input>, parent: &Style, transform: Matrix3, depth: u32) {
+        if !node.is_element() || depth > MAX_DEPTH || property(node, "display") == Some("none") {
+            return;
+        }
+        if self.elements >= MAX_ELEMENTS || self.shapes.len() >= MAX_SHAPES {
+            return;
+        }
+        self.elements += 1;
+        let style = self.style(node, parent);
+        let transform = transform
+            * node
+                .attribute("transform")
+                .map_or(Matrix3::IDENTITY, parse_transform);
+
+        match node.tag_name().name() {
+            "g" | "a" | "switch" | "svg" => {
+                for child in node.children() {
+                    self.element(child, &style, transform, depth + 1);
+                }
+            }
+            "use" => {
+                // A target holding this `use`, or one of the `use`s it's being copied by, would
+                // copy itself forever. SVG calls that an error, so it draws nothing.
+                let target = href(node).and_then(|id| self.find(id)).filter(|target| {
+                    !std::iter::once(&node)
+                        .chain(&self.using)
+                        .any(|u| u.ancestors().any(|a| a == *target))
+                });
+                if let Some(target) = target {
+                    self.using.push(node);
+                    let offset = Vector2(self.number(node, "x", 0.0), self.number(node, "y", 0.0));
+                    let transform = transform * Matrix3::translation(offset);
+                    // Symbols are only drawn through `use`, so they aren't groups below
+                    if target.has_tag_name("symbol") {
+                        let style = self.style(target, &style);
+                        for child in target.children() {
+                            self.element(child, &style, transform, depth + 1);
+                        }
+                    } else {
+                        self.element(target, &style, transform, depth + 1);
+                    }
+                    self.using.pop();
+                }
+            }
+            _ => {
+                if let Some(path) = self.shape(node)
+                    && style.visible
+                {
+                    self.add_shape(path, &style, transform);
+                }
+            }
+        }
+    }
+
+    fn style(&self, node: Node, parent: &Style) -> Style {
+        let mut style = parent.clone();
+
+        if let Some(col) = property(node, "color").and_then(parse_color) {
+            style.color = col;
+        }
+        if let Some(v) = property(node, "fill") {
+            style.fill = parse_paint(v).unwrap_or(parent.fill.clone());
+        }
+        if let Some(v) = property(node, "stroke") {
+            style.stroke = parse_paint(v).unwrap_or(parent.stroke.clone());
+        }
+        let unit = |name, fallback: f32| {
+            property(node, name)
+                .and_then(|v| v.trim().parse::<f32>().ok())
+                .map_or(fallback, |v| v.clamp(0.0, 1.0))
+        };
+        style.fill_opacity = unit("fill-opacity", parent.fill_opacity);
+        style.stroke_opacity = unit("stroke-opacity", parent.stroke_opacity);
+        style.opacity = parent.opacity * unit("opacity", 1.0);
+
+        match property(node, "fill-rule") {
+            Some("evenodd") => style.fill_rule = FillRule::EvenOdd,
+            Some("nonzero") => style.fill_rule = FillRule::NonZero,
+            _ => (),
+        }
+        if let Some(width) = property(node, "stroke-width").and_then(|v| length(v, self.diagonal()))
+        {
+            style.stroke_width = width;
+        }
+        if let Some(limit) = property(node, "stroke-miterlimit").and_then(|v| v.trim().parse().ok())
+        {
+            style.miter_limit = limit;
+        }
+        match property(node, "stroke-linejoin") {
+            Some("miter") | Some("miter-clip") | Some("arcs") => {
+                style.join = LineJoin::Miter(style.miter_limit)
+            }
+            Some("round") => style.join = LineJoin::Round,
+            Some("bevel") => style.join = LineJoin::Bevel,
+            _ => {
+                if let LineJoin::Miter(_) = style.join {
+                    style.join = LineJoin::Miter(style.miter_limit);
+                }
+            }
+        }
+        match property(node, "stroke-linecap") {
+            Some("butt") => style.cap = LineCap::Butt,
+            Some("round") => style.cap = LineCap::Round,
+            Some("square") => style.cap = LineCap::Square,
+            _ => (),
+        }
+        match property(node, "stroke-dasharray") {
+            Some("none") => style.dash.clear(),
+            Some(v) if v != "inherit" => style.dash = numbers(v),
+            _ => (),
+        }
+        if let Some(offset) = property(node, "stroke-dashoffset").and_then(|v| length(v, 0.0)) {
+            style.dash_offset = offset;
+        }
+        match property(node, "visibility") {
+            Some("hidden") | Some("collapse") => style.visible = false,
+            Some("visible") => style.visible = true,
+            _ => (),
+        }
+        style
+    }
+
+    // The outline of a shape element, in its own coordinates
+    fn shape(&self, node: Node) -> Option<Path> {
+        let mut path = Path::new();
+        match node.tag_name().name() {
+            "path" => parse_path_data(node.attribute("d")?, &mut path),
+            "rect" => {
+                let pos = Vector2(self.number(node, "x", 0.0), self.number(node, "y", 0.0));
+                let size = Vector2(
+                    self.number(node, "width", 0.0),
+                    self.number(node, "height", 0.0),
+                );
+                if size.0 <= 0.0 || size.1 <= 0.0 {
+                    return None;
+                }
+                // A missing radius is the same as the other one
+                let rx = node
+                    .attribute("rx")
+                    .and_then(|v| length(v, self.view_box.size.0));
+                let ry = node
+                    .attribute("ry")
+                    .and_then(|v| length(v, self.view_box.size.1));
+                let (rx, ry) = match (rx, ry) {
+                    (Some(rx), Some(ry)) => (rx, ry),
+                    (Some(r), None) | (None, Some(r)) => (r, r),
+                    (None, None) => (0.0, 0.0),
+                };
+                let r = Vector2(rx.clamp(0.0, size.0 / 2.0), ry.clamp(0.0, size.1 / 2.0));
+                rounded_rect(&mut path, pos, size, r);
+            }
+            "circle" => {
+                let r = self.number(node, "r", 0.0);
+                ellipse(&mut path, self.center(node), Vector2(r, r));
+            }
+            "ellipse" => {
+                let r = Vector2(self.number(node, "rx", 0.0), self.number(node, "ry", 0.0));
+                ellipse(&mut path, self.center(node), r);
+            }
+            "line" => {
+                path.move_to(Vector2(
+                    self.number(node, "x1", 0.0),
+                    self.number(node, "y1", 0.0),
+                ))
+                .line_to(Vector2(
+                    self.number(node, "x2", 0.0),
+                    self.number(node, "y2", 0.0),
+                ));
+            }
+            name @ ("polyline" | "polygon") => {
+                let n = numbers(node.attribute("points")?);
+                for p in n.chunks_exact(2) {
+                    path.line_to(Vector2(p[0], p[1]));
+                }
+                if name == "polygon" {
+                    path.close();
+                }
+            }
+            _ => return None,
+        }
+        (!path.is_empty()).then_some(path)
+    }
+
+    fn add_shape(&mut self, path: Path, style: &Style, transform: Matrix3) {
+        let bounds = path.bounds();
+        let fill = style.fill.as_ref().and_then(|paint| {
+            let opacity = style.fill_opacity * style.opacity;
+            Some((
+                self.paint(paint, style, opacity, bounds, 0)?,
+                style.fill_rule,
+            ))
+        });
+        let stroke = style.stroke.as_ref().and_then(|paint| {
+            if style.stroke_width <= 0.0 {
+                return None;
+            }
+            let opacity = style.stroke_opacity * style.opacity;
+            let stroke = StrokeStyle::new(style.stroke_width)
+                .with_join(style.join)
+                .with_cap(style.cap)
+                .with_dash(&style.dash, style.dash_offset);
+            Some((self.paint(paint, style, opacity, bounds, 0)?, stroke))
+        });
+
+        if fill.is_some() || stroke.is_some() {
+            self.shapes.push(SvgShape {
+                path,
+                transform,
+                fill,
+                stroke,
+            });
+        }
+    }
+
+    fn paint(
+        &self,
+        value: &PaintValue,
+        style: &Style,
+        opacity: f32,
+        bounds: Option<Rect>,
+        depth: u32,
+    ) -> Option<Paint> {
+        match value {
+            PaintValue::Color(col) => Some(Paint::Solid(col.fade(opacity))),
+            PaintValue::CurrentColor => Some(Paint::Solid(style.color.fade(opacity))),
+            PaintValue::Url(id, fallback) => match self.find(id) {
+                Some(node) if depth < MAX_DEPTH => self.gradient(node, opacity, bounds),
+                _ => self.paint(fallback.as_deref()?, style, opacity, bounds, depth + 1),
+            },
+        }
+    }
+
+    fn gradient(&self, node: Node, opacity: f32, bounds: Option<Rect>) -> Option<Paint> {
+        let linear = match node.tag_name().name() {
+            "linearGradient" => true,
+            "radialGradient" => false,
+            _ => return None,
+        };
+
+        // Gradients can take their attributes and stops from the one they link to
+        let chain: Vec<Node> =
+            std::iter::successors(Some(node), |n| href(*n).and_then(|id| self.find(id)))
+                .take(MAX_DEPTH as usize)
+                .collect();
+        let attr = |name: &str| chain.iter().find_map(|n| n.attribute(name));
+
+        let stop_nodes = chain
+            .iter()
+            .map(|n| {
+                n.children()
+                    .filter(|c| c.has_tag_name("stop"))
+                    .collect::<Vec<_>>()
+            })
+            .find(|stops| !stops.is_empty())?;
+        let mut stops = Vec::with_capacity(stop_nodes.len());
+        let mut last_offset: f32 = 0.0;
+        for stop in stop_nodes {
+            // Offsets can't go back, a stop before the last one is moved up to it
+            let offset = stop
+                .attribute("offset")
+                .and_then(|v| length(v, 1.0))
+                .unwrap_or(0.0);
+            last_offset = last_offset.max(offset.clamp(0.0, 1.0));
+            let col = property(stop, "stop-color")
+                .and_then(|v| match v {
+                    "currentColor" => property(stop, "color").and_then(parse_color),
+                    v => parse_color(v),
+                })
+                .unwrap_or(Color::BLACK);
+            let stop_opacity = property(stop, "stop-opacity")
+                .and_then(|v| v.trim().parse::<f32>().ok())
+                .unwrap_or(1.0)
+                .clamp(0.0, 1.0);
+            stops.push(ColorStop::new(last_offset, col.fade(stop_opacity)));
+        }
+
+        // In the box around the shape by default, where 0 to 1 goes across it
+        let user_space = attr("gradientUnits") == Some("userSpaceOnUse");
+        let (units, w, h) = if user_space {
+            let size = self.view_box.size;
+            (Matrix3::IDENTITY, size.0, size.1)
+        } else {
+            let bounds = bounds?;
+            let units = Matrix3::translation(bounds.pos) * Matrix3::scale(bounds.size);
+            (units, 1.0, 1.0)
+        };
+        let coord = |name: &str, fallback: &str, of: f32| {
+            length(attr(name).unwrap_or(fallback), of).unwrap_or(0.0)
+        };
+
+        let shape = if linear {
+            GradientShape::Linear {
+                from: Vector2(coord("x1", "0%", w), coord("y1", "0%", h)),
+                to: Vector2(coord("x2", "100%", w), coord("y2", "0%", h)),
+            }
+        } else {
+            // The focal point isn't supported, the gradient is always centered
+            let diagonal = (w * w + h * h).sqrt() / std::f32::consts::SQRT_2;
+            GradientShape::Radial {
+                center: Vector2(coord("cx", "50%", w), coord("cy", "50%", h)),
+                radius: coord("r", "50%", diagonal),
+            }
+        };
+        let gradient_transform =
+            attr("gradientTransform").map_or(Matrix3::IDENTITY, parse_transform);
+
+        Some(Paint::Gradient {
+            shape,
+            stops,
+            to_gradient: (units * gradient_transform).inverse()?,
+            opacity,
+        })
+    }
+
+    fn find(&self, id: &str) -> Option<Node<'a, 'input>> {
+        self.doc
+            .descendants()
+            .find(|n| n.attribute("id") == Some(id))
+    }
+
+    fn center(&self, node: Node) -> Vector2 {
+        Vector2(self.number(node, "cx", 0.0), self.number(node, "cy", 0.0))
+    }
+
+    // A length attribute, percentages are of the view box
+    fn number(&self, node: Node, name: &str, fallback: f32) -> f32 {
+        let of = match name {
+            "x" | "cx" | "x1" | "x2" | "width" | "rx" => self.view_box.size.0,
+            "y" | "cy" | "y1" | "y2" | "height" | "ry" => self.view_box.size.1,
+            _ => self.diagonal(),
+        };
+        node.attribute(name)
+            .and_then(|v| length(v, of))
+            .unwrap_or(fallback)
+    }
+
+    // What percentages of lengths that aren't across or down are of
+    fn diagonal(&self) -> f32 {
+        self.view_box.size.length() / std::f32::consts::SQRT_2
+    }
+}
+
+// A property from the style attribute, or the attribute with its name
+fn property<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    let from_style = node.attribute("style").and_then(|style| {
+        style.split(';').rev().find_map(|declaration| {
+            let (key, value) = declaration.split_once(':')?;
+            (key.trim() == name).then(|| value.trim().trim_end_matches("!important").trim())
+        })
+    });
+    from_style
+        .or_else(|| node.attribute(name).map(str::trim))
+        .filter(|v| *v != "inherit")
+}
+
+fn href<'a>(node: Node<'a, '_>) -> Option<&'a str> {
+    node.attribute((XLINK, "href"))
+        .or_else(|| node.attribute("href"))?
+        .strip_prefix('#')
+}
+
+// None means the value can't be read, so the inherited one stays
+fn parse_paint(value: &str) -> Option<Option<PaintValue>> {
+    let value = value.trim();
+    match value {
+        "none" => Some(None),
+        "currentColor" => Some(Some(PaintValue::CurrentColor)),
+        _ => {
+            if let Some(rest) = value.strip_prefix("url(") {
+                let (url, fallback) = rest.split_once(')')?;
+                let id = url.trim().trim_matches(['"', '\'']).strip_prefix('#')?;
+                let fallback = parse_paint(fallback).flatten().map(Box::new);
+                return Some(Some(PaintValue::Url(id.to_string(), fallback)));
+            }
+            parse_color(value).map(|col| Some(PaintValue::Color(col)))
+        }
+    }
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+    let value = value.trim();
+    if value.starts_with('#') {
+        return Color::from_hex(value).ok();
+    }
+    if value.eq_ignore_ascii_case("transparent") {
+        return Some(Color::TRANSPARENT);
+    }
+
+    if let Some(args) = value
+        .strip_prefix("rgba(")
+        .or_else(|| value.strip_prefix("rgb("))
+        .and_then(|v| v.strip_suffix(')'))
+    {
+        let parts: Vec<&str> = args
+            .split([',', ' ', '/'])
+            .filter(|p| !p.is_empty())
+            .collect();
+        if parts.len() < 3 {
+            return None;
+        }
+        let channel = |p: &str| -> Option<u8> {
+            let v = match p.strip_suffix('%') {
+                Some(percent) => percent.parse::<f32>().ok()? * 2.55,
+                None => p.parse::<f32>().ok()?,
+            };
+            Some(v.round().clamp(0.0, 255.0) as u8)
+        };
+        let alpha = match parts.get(3) {
+            Some(a) => match a.strip_suffix('%') {
+                Some(percent) => percent.parse::<f32>().ok()? / 100.0,
+                None => a.parse::<f32>().ok()?,
+            },
+            None => 1.0,
+        };
+        return Some(
+            Color::rgb(channel(parts[0])?, channel(parts[1])?, channel(parts[2])?).fade(alpha),
+        );
+    }
+
+    css::from_name(value)
+}
+
+// A number with an optional unit, in px. Percentages are of `percent_of`.
+fn length(value: &str, percent_of: f32) -> Option<f32> {
+    let mut data = PathData::new(value);
+    let number = data.number()?;
+    let unit = &value[data.pos..];
+    let scale = match unit.trim() {
+        "" | "px" => 1.0,
+        "%" => percent_of / 100.0,
+        "pt" => 4.0 / 3.0,
+        "pc" => 16.0,
+        "in" => 96.0,
+        "cm" => 96.0 / 2.54,
+        "mm" => 96.0 / 25.4,
+        // Without fonts, an em is the default font size
+        "em" | "rem" => 16.0,
+        "ex" => 8.0,
+        _ => return None,
+    };
+    Some(number * scale)
+}
+
+// All the numbers in a list, separated by commas or spaces
+fn numbers(value: &str) -> Vec<f32> {
+    let mut data = PathData::new(value);
+    std::iter::from_fn(|| data.number()).collect()
+}
+
+fn parse_transform(value: &str) -> Matrix3 {
+    let mut matrix = Matrix3::IDENTITY;
+    for part in value.split_inclusive(')') {
+        let Some((name, args)) = part.split_once('(') else {
+            continue;
+        };
+        let a = numbers(args.trim_end_matches(')'));
+        let arg = |i: usize, fallback: f32| a.get(i).copied().unwrap_or(fallback);
+        let m = match name.trim().trim_start_matches(',').trim() {
+            "matrix" if a.len() == 6 => Matrix3::from_cols(
+                Vector3(a[0], a[1], 0.0),
+                Vector3(a[2], a[3], 0.0),
+                Vector3(a[4], a[5], 1.0),
+            ),
+            "translate" => Matrix3::translation(Vector2(arg(0, 0.0), arg(1, 0.0))),
+            "scale" => Matrix3::scale(Vector2(arg(0, 1.0), arg(1, arg(0, 1.0)))),
+            "rotate" => {
+                let center = Vector2(arg(1, 0.0), arg(2, 0.0));
+                Matrix3::translation(center)
+                    * Matrix3::rotation(arg(0, 0.0).to_radians())
+                    * Matrix3::translation(-center)
+            }
+            "skewX" => Matrix3::from_cols(
+                Vector3(1.0, 0.0, 0.0),
+                Vector3(arg(0, 0.0).to_radians().tan(), 1.0, 0.0),
+                Vector3(0.0, 0.0, 1.0),
+            ),
+            "skewY" => Matrix3::from_cols(
+                Vector3(1.0, arg(0, 0.0).to_radians().tan(), 0.0),
+                Vector3(0.0, 1.0, 0.0),
+                Vector3(0.0, 0.0, 1.0),
+            ),
+            // A broken transform turns off the whole attribute
+            _ => return Matrix3::IDENTITY,
+        };
+        matrix = matrix * m;
+    }
+    matrix
+}
+
+fn rounded_rect(path: &mut Path, pos: Vector2, size: Vector2, r: Vector2) {
+    let (x, y, w, h) = (pos.0, pos.1, size.0, size.1);
+    path.move_to(Vector2(x + r.0, y))
+        .line_to(Vector2(x + w - r.0, y));
+    path.arc_to(r, 0.0, false, true, Vector2(x + w, y + r.1))
+        .line_to(Vector2(x + w, y + h - r.1));
+    path.arc_to(r, 0.0, false, true, Vector2(x + w - r.0, y + h))
+        .line_to(Vector2(x + r.0, y + h));
+    path.arc_to(r, 0.0, false, true, Vector2(x, y + h - r.1))
+        .line_to(Vector2(x, y + r.1));
+    path.arc_to(r, 0.0, false, true, Vector2(x + r.0, y))
+        .close();
+}
+
+fn ellipse(path: &mut Path, center: Vector2, r: Vector2) {
+    if r.0 <= 0.0 || r.1 <= 0.0 {
+        return;
+    }
+    path.move_to(center + Vector2(r.0, 0.0))
+        .arc_to(r, 0.0, false, true, center + Vector2(0.0, r.1))
+        .arc_to(r, 0.0, false, true, center - Vector2(r.0, 0.0))
+        .arc_to(r, 0.0, false, true, center - Vector2(0.0, r.1))
+        .arc_to(r, 0.0, false, true, center + Vector2(r.0, 0.0))
+        .close();
+}
+
+// Reads the `d` attribute of a path. Like browsers, it keeps everything up to the first error.
+fn parse_path_data(d: &str, path: &mut Path) {
+    let mut data = PathData::new(d);
+    let mut command = None;
+    let mut current = Vector2::zero();
+    let mut start = Vector2::zero();
+    // The control point of the last curve, for the smooth ones that mirror it
+    let mut last_control: Option<(char, Vector2)> = None;
+
+    loop {
+        data.skip_separators();
+        let Some(next) = data.peek() else { break };
+        if next.is_ascii_alphabetic() {
+            data.advance();
+            command = Some(next as char);
+        } else if command.is_none() {
+            break;
+        }
+        let Some(c) = command else { break };
+        let relative = c.is_ascii_lowercase();
+        let offset = if relative { current } else { Vector2::zero() };
+
+        let control = match c.to_ascii_uppercase() {
+            'M' => {
+                let Some(p) = data.point(offset) else { break };
+                path.move_to(p);
+                current = p;
+                start = p;
+                // More points after a move are lines
+                command = Some(if relative { 'l' } else { 'L' });
+                None
+            }
+            'L' => {
+                let Some(p) = data.point(offset) else { break };
+                path.line_to(p);
+                current = p;
+                None
+            }
+            'H' => {
+                let Some(x) = data.number() else { break };
+                current = Vector2(if relative { current.0 + x } else { x }, current.1);
+                path.line_to(current);
+                None
+            }
+            'V' => {
+                let Some(y) = data.number() else { break };
+                current = Vector2(current.0, if relative { current.1 + y } else { y });
+                path.line_to(current);
+                None
+            }
+            'C' => {
+                let (Some(c1), Some(c2), Some(p)) =
+                    (data.point(offset), data.point(offset), data.point(offset))
+                else {
+                    break;
+                };
+                path.cubic_to(c1, c2, p);
+                current = p;
+                Some(('C', c2))
+            }
+            'S' => {
+                let (Some(c2), Some(p)) = (data.point(offset), data.point(offset)) else {
+                    break;
+                };
+                let c1 = match last_control {
+                    Some(('C', control)) => current * 2.0 - control,
+                    _ => current,
+                };
+                path.cubic_to(c1, c2, p);
+                current = p;
+                Some(('C', c2))
+            }
+            'Q' => {
+                let (Some(control), Some(p)) = (data.point(offset), data.point(offset)) else {
+                    break;
+                };
+                path.quad_to(control, p);
+                current = p;
+                Some(('Q', control))
+            }
+            'T' => {
+                let Some(p) = data.point(offset) else { break };
+                let control = match last_control {
+                    Some(('Q', control)) => current * 2.0 - control,
+                    _ => current,
+                };
+                path.quad_to(control, p);
+                current = p;
+                Some(('Q', control))
+            }
+            'A' => {
+                let (Some(rx), Some(ry), Some(rotation)) =
+                    (data.number(), data.number(), data.number())
+                else {
+                    break;
+                };
+                let (Some(large_arc), Some(sweep), Some(p)) =
+                    (data.flag(), data.flag(), data.point(offset))
+                else {
+                    break;
+                };
+                path.arc_to(Vector2(rx, ry), rotation.to_radians(), large_arc, sweep, p);
+                current = p;
+                None
+            }
+            'Z' => {
+                path.close();
+                current = start;
+                // Z takes no numbers, so it can't repeat
+                command = None;
+                None
+            }
+            _ => break,
+        };
+        last_control = control;
+    }
+}
+
+// Reads numbers and flags out of path data and other number lists
+struct PathData<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> PathData<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            bytes: text.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn advance(&mut self) {
+        self.pos += 1;
+    }
+
+    fn skip_separators(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r' | b',') = self.peek() {
+            self.advance();
+        }
+    }
+
+    fn point(&mut self, offset: Vector2) -> Option<Vector2> {
+        Some(Vector2(self.number()?, self.number()?) + offset)
+    }
+
+    // Numbers can run into each other, like "1.5.5" or "1-2"
+    fn number(&mut self) -> Option<f32> {
+        self.skip_separators();
+        let start = self.pos;
+        if let Some(b'+' | b'-') = self.peek() {
+            self.advance();
+        }
+        let mut digits = self.digits();
+        if self.peek() == Some(b'.') {
+            self.advance();
+            digits += self.digits();
+        }
+        if digits == 0 {
+            self.pos = start;
+            return None;
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            let mantissa_end = self.pos;
+            self.advance();
+            if let Some(b'+' | b'-') = self.peek() {
+                self.advance();
+            }
+            if self.digits() == 0 {
+                self.pos = mantissa_end;
+            }
+        }
+        std::str::from_utf8(&self.bytes[start..self.pos])
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while self.peek().is_some_and(|b| b.is_ascii_digit()) {
+            self.advance();
+        }
+        self.pos - start
+    }
+
+    // Arc flags are a single 0 or 1, and don't need anything after them
+    fn flag(&mut self) -> Option<bool> {
+        self.skip_separators();
+        let flag = match self.peek()? {
+            b'0' => false,
+            b'1' => true,
+            _ => return None,
+        };
+        self.advance();
+        Some(flag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::PathCommand::{self, *};
+
+    fn svg(body: &str) -> Svg {
+        let text = format!(r#"<svg xmlns="http://www.w3.org/2000/svg">{body}</svg>"#);
+        Svg::parse(&text).unwrap()
+    }
+
+    fn path_data(d: &str) -> Vec<PathCommand> {
+        let mut path = Path::new();
+        parse_path_data(d, &mut path);
+        path.commands().to_vec()
+    }
+
+    fn assert_maps(m: Matrix3, from: Vector2, to: Vector2) {
+        let p = m.transform_point(from);
+        assert!(p.approx_eq(to, 1e-4), "{from:?} went to {p:?}, not {to:?}");
+    }
+
+    #[test]
+    fn path_data_repeats_the_last_command() {
+        assert_eq!(
+            path_data("M0 0 10 0 10 10"),
+            [
+                MoveTo(Vector2(0.0, 0.0)),
+                LineTo(Vector2(10.0, 0.0)),
+                LineTo(Vector2(10.0, 10.0)),
+            ]
+        );
+        // Relative moves repeat as relative lines
+        assert_eq!(
+            path_data("m1 1 2 2l1 0 1 0h1 1v-1"),
+            [
+                MoveTo(Vector2(1.0, 1.0)),
+                LineTo(Vector2(3.0, 3.0)),
+                LineTo(Vector2(4.0, 3.0)),
+                LineTo(Vector2(5.0, 3.0)),
+                LineTo(Vector2(6.0, 3.0)),
+                LineTo(Vector2(7.0, 3.0)),
+                LineTo(Vector2(7.0, 2.0)),
+            ]
+        );
+        assert_eq!(
+            path_data("M0,0C1,1,2,2,3,3,4,4,5,5,6,6"),
+            [
+                MoveTo(Vector2(0.0, 0.0)),
+                CubicTo(Vector2(1.0, 1.0), Vector2(2.0, 2.0), Vector2(3.0, 3.0)),
+                CubicTo(Vector2(4.0, 4.0), Vector2(5.0, 5.0), Vector2(6.0, 6.0)),
+            ]
+        );
+        // After a close, relative commands go from where the sub-path started
+        assert_eq!(
+            path_data("M5 5L6 5Zl1 1"),
+            [
+                MoveTo(Vector2(5.0, 5.0)),
+                LineTo(Vector2(6.0, 5.0)),
+                Close,
+                LineTo(Vector2(6.0, 6.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn path_data_numbers_run_together() {
+        assert_eq!(path_data("M1.5.5"), [MoveTo(Vector2(1.5, 0.5))]);
+        assert_eq!(path_data("M1-2"), [MoveTo(Vector2(1.0, -2.0))]);
+        assert_eq!(path_data("M.5-.5"), [MoveTo(Vector2(0.5, -0.5))]);
+        assert_eq!(path_data("M1e2-3E-1"), [MoveTo(Vector2(100.0, -0.3))]);
+        assert_eq!(path_data("M+1 -0"), [MoveTo(Vector2(1.0, 0.0))]);
+        assert_eq!(
+            path_data("M0 0L.1.2.3.4"),
+            [
+                MoveTo(Vector2(0.0, 0.0)),
+                LineTo(Vector2(0.1, 0.2)),
+                LineTo(Vector2(0.3, 0.4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn path_data_keeps_everything_before_an_error() {
+        assert_eq!(
+            path_data("M0 0L10 0L5"),
+            [MoveTo(Vector2(0.0, 0.0)), LineTo(Vector2(10.0, 0.0))]
+        );
+        assert_eq!(
+            path_data("M0 0L10 0X20 20"),
+            [MoveTo(Vector2(0.0, 0.0)), LineTo(Vector2(10.0, 0.0))]
+        );
+        // Numbers before any command
+        assert!(path_data("10 10L5 5").is_empty());
+    }
+
+    #[test]
+    fn path_data_arc_flags_need_no_separators() {
+        let spaced = path_data("M0 0a5,5 0 1,0 20,0");
+        assert_eq!(path_data("M0 0a5 5 0 1020 0"), spaced);
+        assert_eq!(path_data("M0 0A5 5 0 10 20 0"), spaced);
+
+        // Too small, so a half circle of radius 10 below the line
+        let mut path = Path::new();
+        parse_path_data("M0 0a5 5 0 1020 0", &mut path);
+        assert_eq!(path.current_point(), Some(Vector2(20.0, 0.0)));
+        let bounds = path.bounds().unwrap();
+        assert!((bounds.max().1 - 10.0).abs() < 0.01, "{bounds:?}");
+
+        // A flag is only ever one digit
+        assert_eq!(path_data("M0 0a5 5 0 2 0 20 0").len(), 1);
+    }
+
+    #[test]
+    fn path_data_smooth_curves_reflect_the_last_control_point() {
+        assert_eq!(
+            path_data("M0 0C0 10 10 10 10 0S20 -10 20 0"),
+            [
+                MoveTo(Vector2(0.0, 0.0)),
+                CubicTo(Vector2(0.0, 10.0), Vector2(10.0, 10.0), Vector2(10.0, 0.0)),
+                CubicTo(
+                    Vector2(10.0, -10.0),
+                    Vector2(20.0, -10.0),
+                    Vector2(20.0, 0.0)
+                ),
+            ]
+        );
+        assert_eq!(
+            path_data("m0 0c0 10 10 10 10 0s10 -10 10 0"),
+            path_data("M0 0C0 10 10 10 10 0S20 -10 20 0")
+        );
+        assert_eq!(
+            path_data("M0 0Q5 10 10 0T20 0T30 0"),
+            [
+                MoveTo(Vector2(0.0, 0.0)),
+                QuadTo(Vector2(5.0, 10.0), Vector2(10.0, 0.0)),
+                QuadTo(Vector2(15.0, -10.0), Vector2(20.0, 0.0)),
+                QuadTo(Vector2(25.0, 10.0), Vector2(30.0, 0.0)),
+            ]
+        );
+
+        // Only a curve of the same kind is reflected, anything else uses the current point
+        assert_eq!(
+            path_data("M0 0L5 0S10 10 10 0")[2],
+            CubicTo(Vector2(5.0, 0.0), Vector2(10.0, 10.0), Vector2(10.0, 0.0))
+        );
+        assert_eq!(
+            path_data("M0 0Q5 10 10 0S20 10 20 0")[2],
+            CubicTo(Vector2(10.0, 0.0), Vector2(20.0, 10.0), Vector2(20.0, 0.0))
+        );
+        assert_eq!(
+            path_data("M0 0C0 10 10 10 10 0T20 0")[2],
+            QuadTo(Vector2(10.0, 0.0), Vector2(20.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn transforms() {
+        let p = Vector2(1.0, 1.0);
+        assert_maps(parse_transform("translate(10)"), p, Vector2(11.0, 1.0));
+        assert_maps(parse_transform("translate(10, -5)"), p, Vector2(11.0, -4.0));
+        assert_maps(parse_transform("scale(2)"), p, Vector2(2.0, 2.0));
+        assert_maps(parse_transform("scale(2 3)"), p, Vector2(2.0, 3.0));
+        assert_maps(
+            parse_transform("rotate(90)"),
+            Vector2(1.0, 0.0),
+            Vector2(0.0, 1.0),
+        );
+        assert_maps(
+            parse_transform("rotate(90 10 10)"),
+            Vector2(20.0, 10.0),
+            Vector2(10.0, 20.0),
+        );
+        assert_maps(
+            parse_transform("skewX(45)"),
+            Vector2(0.0, 1.0),
+            Vector2(1.0, 1.0),
+        );
+        assert_maps(
+            parse_transform("skewY(45)"),
+            Vector2(1.0, 0.0),
+            Vector2(1.0, 1.0),
+        );
+        assert_maps(parse_transform("matrix(2 0 0 3 5 6)"), p, Vector2(7.0, 9.0));
+
+        // The list applies right to left, like nested groups
+        assert_maps(
+            parse_transform("translate(10,20) scale(2)"),
+            p,
+            Vector2(12.0, 22.0),
+        );
+        assert_maps(
+            parse_transform("translate(10,20),scale(2)"),
+            p,
+            Vector2(12.0, 22.0),
+        );
+        assert_maps(
+            parse_transform("scale(2) translate(10,20)"),
+            p,
+            Vector2(22.0, 42.0),
+        );
+
+        // Anything broken turns off the whole list
+        assert_eq!(
+            parse_transform("translate(10) wobble(3)"),
+            Matrix3::IDENTITY
+        );
+        assert_eq!(parse_transform("matrix(1 2 3)"), Matrix3::IDENTITY);
+        assert_eq!(parse_transform(""), Matrix3::IDENTITY);
+    }
+
+    #[test]
+    fn colors() {
+        assert_eq!(parse_color("#f00"), Some(Color::RED));
+        assert_eq!(parse_color(" #00ff0080 "), Some(Color(0, 255, 0, 128)));
+        assert_eq!(parse_color("red"), Some(Color::RED));
+        assert_eq!(parse_color("CornflowerBlue"), Some(css::CORNFLOWERBLUE));
+        assert_eq!(parse_color("transparent"), Some(Color::TRANSPARENT));
+        assert_eq!(parse_color("rgb(255, 0, 0)"), Some(Color::RED));
+        assert_eq!(
+            parse_color("rgb(100%, 0%, 50%)"),
+            Some(Color(255, 0, 128, 255))
+        );
+        assert_eq!(
+            parse_color("rgba(0,0,255,0.5)"),
+            Some(Color(0, 0, 255, 128))
+        );
+        assert_eq!(
+            parse_color("rgb(0 0 255 / 50%)"),
+            Some(Color(0, 0, 255, 128))
+        );
+        // Out of range channels are clamped
+        assert_eq!(parse_color("rgb(300, -5, 0)"), Some(Color::RED));
+
+        for bad in [
+            "",
+            "#ff",
+            "#+f+f+f",
+            "rgb(1, 2)",
+            "rgb(a, b, c)",
+            "nocolor",
+            "none",
+        ] {
+            assert_eq!(parse_color(bad), None, "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn lengths() {
+        let cases = [
+            ("10", 10.0),
+            ("10px", 10.0),
+            (" 3 px", 3.0),
+            ("50%", 100.0),
+            ("3pt", 4.0),
+            ("1pc", 16.0),
+            ("1in", 96.0),
+            ("2.54cm", 96.0),
+            ("25.4mm", 96.0),
+            ("2em", 32.0),
+            ("1rem", 16.0),
+            ("1ex", 8.0),
+            ("-1.5e1px", -15.0),
+        ];
+        for (value, expected) in cases {
+            let l = length(value, 200.0).unwrap();
+            assert!((l - expected).abs() < 1e-4, "{value:?} is {l}");
+        }
+        for bad in ["", "px", "10furlongs", "10 20"] {
+            assert_eq!(length(bad, 200.0), None, "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn use_inside_its_own_target_draws_nothing() {
+        // Used to copy the group 4^32 times
+        let svg = svg(&format!(
+            r##"<g id="a"><rect width="1" height="1"/>{}</g>"##,
+            r##"<use href="#a"/>"##.repeat(4)
+        ));
+        assert_eq!(svg.shapes.len(), 1);
+    }
+
+    #[test]
+    fn use_cycles_through_other_elements_stop() {
+        let svg = svg(
+            r##"<g id="a"><rect width="1" height="1"/><use href="#b"/><use href="#b"/></g>
+                <g id="b"><circle r="1"/><use href="#a"/><use href="#a"/></g>
+                <use id="c" href="#c"/>"##,
+        );
+        // Each group by itself, and each copy of the other group without its `use`s
+        assert_eq!(svg.shapes.len(), 6);
+    }
+
+    #[test]
+    fn use_copies_are_capped() {
+        // Ten levels of ten copies each, 10^10 rects if nothing stopped it
+        let mut body = String::from(r#"<defs><rect id="l0" width="1" height="1"/>"#);
+        for level in 1..=10 {
+            body += &format!(r#"<g id="l{level}">"#);
+            body += &format!(r##"<use href="#l{}"/>"##, level - 1).repeat(10);
+            body += "</g>";
+        }
+        body += r##"</defs><use href="#l10"/>"##;
+        assert_eq!(svg(&body).shapes.len(), MAX_SHAPES);
+    }
+}
@@ -0,0 +1,1131 @@
+// This module turns outlines into triangles, for filling any polygon: concave ones,
+// ones with holes, and ones that touch or cross themselves.
+//
+// It sweeps down the outlines, keeping the edges the sweep line crosses in order from left to
+// right. Every point and crossing only changes the order around itself, and between them the
+// fill is trapezoids between edges next to each other. The fill rule only has to count the
+// edges to the left of each one.
+//
+// Paths, polygons and strokes are filled here. The shapes drawn with signed distance
+// functions aren't, see the top of `shapes`.
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::types::{Color, Vector2};
+use crate::{RendiumDrawHandle, shapes::aa_margin};
+
+// Which parts of overlapping outlines are inside
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FillRule {
+    // Inside when the outlines wind around the point any number of times. Holes have to go
+    // the other way round from the outline around them.
+    #[default]
+    NonZero,
+    // Inside when a line from the point crosses an odd number of edges,
+    // so holes work whichever way they go
+    EvenOdd,
+}
+
+impl FillRule {
+    fn is_inside(self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+struct Edge {
+    top: Vector2,
+    bottom: Vector2,
+    // +1 going down, -1 going up
+    winding: i32,
+}
+
+impl Edge {
+    fn x_at(&self, y: f32) -> f32 {
+        if y <= self.top.1 {
+            return self.top.0;
+        }
+        if y >= self.bottom.1 {
+            return self.bottom.0;
+        }
+        self.top.0 + (y - self.top.1) / (self.bottom.1 - self.top.1) * (self.bottom.0 - self.top.0)
+    }
+
+    // How far it goes right for every step down
+    fn slope(&self) -> f32 {
+        (self.bottom.0 - self.top.0) / (self.bottom.1 - self.top.1)
+    }
+}
+
+// The fill between two edges, from where it starts being between those two to where it stops
+#[derive(Clone, Copy)]
+struct Trapezoid {
+    left: usize,
+    right: usize,
+    top: f32,
+    bottom: f32,
+    // Whether each side is on the outline of the fill, rather than having more of it beyond
+    outer: (bool, bool),
+}
+
+// Where two edges cross, the first one to come out of the heap is the highest
+struct Crossing {
+    y: f32,
+    edges: (usize, usize),
+}
+
+impl Ord for Crossing {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.y.total_cmp(&self.y)
+    }
+}
+
+impl PartialOrd for Crossing {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Crossing {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Crossing {}
+
+// Triangles covering what's inside the closed outlines, as points and indices into them
+pub fn tessellate(contours: &[Vec<Vector2>], rule: FillRule) -> (Vec<Vector2>, Vec<u32>) {
+    let (edges, trapezoids) = sweep(contours, rule);
+    let mut points = Vec::with_capacity(trapezoids.len() * 4);
+    let mut indices = Vec::with_capacity(trapezoids.len() * 6);
+    for t in &trapezoids {
+        let (left, right) = (&edges[t.left], &edges[t.right]);
+        let base = points.len() as u32;
+        points.extend([
+            Vector2(left.x_at(t.top), t.top),
+            Vector2(right.x_at(t.top), t.top),
+            Vector2(right.x_at(t.bottom), t.bottom),
+            Vector2(left.x_at(t.bottom), t.bottom),
+        ]);
+        indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+    (points, indices)
+}
+
+// Cuts the fill into trapezoids, going down from the top
+fn sweep(contours: &[Vec<Vector2>], rule: FillRule) -> (Vec<Edge>, Vec<Trapezoid>) {
+    let mut edges = Vec::new();
+    for contour in contours {
+        for (i, &a) in contour.iter().enumerate() {
+            let b = contour[(i + 1) % contour.len()];
+            // Horizontal edges never cross the sweep line, the ones next to them close the shape
+            let finite = [a.0, a.1, b.0, b.1].iter().all(|v| v.is_finite());
+            if a.1 == b.1 || !finite {
+                continue;
+            }
+            edges.push(if a.1 < b.1 {
+                Edge {
+                    top: a,
+                    bottom: b,
+                    winding: 1,
+                }
+            } else {
+                Edge {
+                    top: b,
+                    bottom: a,
+                    winding: -1,
+                }
+            });
+        }
+    }
+    edges.sort_by(|a, b| a.top.1.total_cmp(&b.top.1));
+
+    let mut ends: Vec<usize> = (0..edges.len()).collect();
+    ends.sort_by(|&a, &b| edges[a].bottom.1.total_cmp(&edges[b].bottom.1));
+    let trapezoids = Sweep {
+        edges: &edges,
+        rule,
+        ends,
+        next_top: 0,
+        next_end: 0,
+        active: Vec::new(),
+        crossings: BinaryHeap::new(),
+        crossing_with: vec![None; edges.len()],
+        open: vec![None; edges.len()],
+        trapezoids: Vec::new(),
+    }
+    .run();
+    (edges, trapezoids)
+}
+
+struct Sweep<'a> {
+    // In order of where they start
+    edges: &'a [Edge],
+    rule: FillRule,
+    // The edges in order of where they end
+    ends: Vec<usize>,
+    next_top: usize,
+    next_end: usize,
+    // The edges crossing the sweep line from left to right, each with the winding number just
+    // right of it
+    active: Vec<(usize, i32)>,
+    // Where edges next to each other cross further down, and the edge each one is in the heap
+    // with, so finding the same crossing again doesn't add it twice
+    crossings: BinaryHeap<Crossing>,
+    crossing_with: Vec<Option<usize>>,
+    // The trapezoid going down right of each edge, which has no bottom yet
+    open: Vec<Option<Trapezoid>>,
+    trapezoids: Vec<Trapezoid>,
+}
+
+impl Sweep<'_> {
+    fn run(mut self) -> Vec<Trapezoid> {
+        while let Some(y) = self.next_y() {
+            // Where the order may have changed
+            let mut changed = Vec::new();
+            while let Some(&edge) = self.ends.get(self.next_end)
+                && self.edges[edge].bottom.1 <= y
+            {
+                self.next_end += 1;
+                self.remove(edge, y, &mut changed);
+            }
+            while self.crossings.peek().is_some_and(|c| c.y <= y) {
+                let (a, b) = self.crossings.pop().unwrap().edges;
+                if self.crossing_with[a] == Some(b) {
+                    self.crossing_with[a] = None;
+                }
+                // Edges that aren't next to each other any more don't cross here after all
+                if self.is_active(a, y)
+                    && self.is_active(b, y)
+                    && let (Some(i), Some(j)) = (self.position(a, y), self.position(b, y))
+                    && i.abs_diff(j) == 1
+                {
+                    changed.extend([i, j]);
+                }
+            }
+            while self
+                .edges
+                .get(self.next_top)
+                .is_some_and(|edge| edge.top.1 <= y)
+            {
+                self.insert(self.next_top, y, &mut changed);
+                self.next_top += 1;
+            }
+
+            // Changes far apart from each other don't affect each other
+            let last = self.active.len().saturating_sub(1);
+            changed.sort_unstable();
+            changed.dedup();
+            for cluster in changed.chunk_by(|a, b| b - a <= 1) {
+                if self.active.is_empty() {
+                    break;
+                }
+                self.reorder(
+                    cluster[0].min(last),
+                    cluster[cluster.len() - 1].min(last),
+                    y,
+                );
+            }
+        }
+        self.trapezoids
+    }
+
+    // Where the next edge starts or ends or the next crossing is
+    fn next_y(&self) -> Option<f32> {
+        let top = self.edges.get(self.next_top).map(|e| e.top.1);
+        let end = self
+            .ends
+            .get(self.next_end)
+            .map(|&e| self.edges[e].bottom.1);
+        let crossing = self.crossings.peek().map(|c| c.y);
+        [top, end, crossing]
+            .into_iter()
+            .flatten()
+            .min_by(f32::total_cmp)
+    }
+
+    fn is_active(&self, edge: usize, y: f32) -> bool {
+        edge < self.next_top && self.edges[edge].bottom.1 > y
+    }
+
+    // Where the edge is among the active ones. They're in order along `y` give or take rounding,
+    // so it's a search and a short walk. Nearly horizontal edges can be a bit further off.
+    fn position(&self, edge: usize, y: f32) -> Option<usize> {
+        let x = self.edges[edge].x_at(y);
+        let tolerance = 1e-4 * x.abs().max(1.0);
+        let start = self
+            .active
+            .partition_point(|&(e, _)| self.edges[e].x_at(y) < x - tolerance);
+        (0..self.active.len()).find_map(|d| {
+            let after = start + d;
+            let before = start.checked_sub(d + 1);
+            [Some(after), before]
+                .into_iter()
+                .flatten()
+                .find(|&i| self.active.get(i).is_some_and(|&(e, _)| e == edge))
+        })
+    }
+
+    fn remove(&mut self, edge: usize, y: f32, changed: &mut Vec<usize>) {
+        let Some(i) = self.position(edge, y) else {
+            return;
+        };
+        self.active.remove(i);
+        self.finish(edge, y);
+        for c in changed.iter_mut() {
+            if *c > i {
+                *c -= 1;
+            }
+        }
+        // The edges on either side are next to each other now
+        changed.extend([i.saturating_sub(1), i]);
+    }
+
+    fn insert(&mut self, edge: usize, y: f32, changed: &mut Vec<usize>) {
+        let new = &self.edges[edge];
+        let (x, slope) = (new.x_at(y), new.slope());
+        // Edges starting at the same point go in order of where they go next
+        let i = self.active.partition_point(|&(e, _)| {
+            let e = &self.edges[e];
+            e.x_at(y)
+                .total_cmp(&x)
+                .then(e.slope().total_cmp(&slope))
+                .is_lt()
+        });
+        self.active.insert(i, (edge, 0));
+        for c in changed.iter_mut() {
+            if *c >= i {
+                *c += 1;
+            }
+        }
+        changed.push(i);
+    }
+
+    // Puts the edges from `start` to `end` and any they got mixed up with in order for just
+    // below `y`, and finds the crossings and trapezoids that changed around them
+    fn reorder(&mut self, mut start: usize, mut end: usize, y: f32) {
+        let edges = self.edges;
+        let swapped = |active: &[(usize, i32)], i: usize| {
+            let (a, b) = (active[i].0, active[i + 1].0);
+            crossing(&edges[a], &edges[b], y).is_some_and(|c| c <= y)
+        };
+        loop {
+            for i in start + 1..=end {
+                let mut j = i;
+                while j > start && swapped(&self.active, j - 1) {
+                    self.active.swap(j - 1, j);
+                    j -= 1;
+                }
+            }
+            if start > 0 && swapped(&self.active, start - 1) {
+                start -= 1;
+            } else if end + 1 < self.active.len() && swapped(&self.active, end) {
+                end += 1;
+            } else {
+                break;
+            }
+        }
+
+        // Including the edges just outside, which have new neighbours
+        for i in start.saturating_sub(1)..(end + 1).min(self.active.len() - 1) {
+            let (a, b) = (self.active[i].0, self.active[i + 1].0);
+            if self.crossing_with[a] != Some(b)
+                && let Some(c) = crossing(&edges[a], &edges[b], y)
+                && c > y
+            {
+                self.crossings.push(Crossing {
+                    y: c,
+                    edges: (a, b),
+                });
+                self.crossing_with[a] = Some(b);
+            }
+        }
+
+        // Past the edges that moved, the winding numbers are back to what they were
+        let mut winding = if start == 0 {
+            0
+        } else {
+            self.active[start - 1].1
+        };
+        let mut last = end;
+        for i in start..self.active.len() {
+            winding += edges[self.active[i].0].winding;
+            if i > end && self.active[i].1 == winding {
+                break;
+            }
+            self.active[i].1 = winding;
+            last = i;
+        }
+        for i in start.saturating_sub(1)..=(last + 1).min(self.active.len() - 1) {
+            self.update_trapezoid(i, y);
+        }
+    }
+
+    // Ends the trapezoid right of the edge at `i` and starts another one at `y`, if the fill
+    // there isn't the same as before
+    fn update_trapezoid(&mut self, i: usize, y: f32) {
+        let span = {
+            let inside = |i: usize| self.rule.is_inside(self.active[i].1);
+            match self.active.get(i + 1) {
+                Some(&(right, _)) if inside(i) => {
+                    Some((right, (i == 0 || !inside(i - 1), !inside(i + 1))))
+                }
+                _ => None,
+            }
+        };
+        let left = self.active[i].0;
+        if self.open[left].map(|t| (t.right, t.outer)) != span {
+            self.finish(left, y);
+            if let Some((right, outer)) = span {
+                self.open[left] = Some(Trapezoid {
+                    left,
+                    right,
+                    top: y,
+                    bottom: y,
+                    outer,
+                });
+            }
+        }
+    }
+
+    fn finish(&mut self, left: usize, y: f32) {
+        if let Some(mut t) = self.open[left].take()
+            && y > t.top
+        {
+            t.bottom = y;
+            self.trapezoids.push(t);
+        }
+    }
+}
+
+// Where `right` crosses over to the left of `left`, before either of them ends. At or above `y`
+// means they're the wrong way round for below `y`. Being closer than rounding errors at `y`
+// counts as touching there, and then where they go next decides.
+fn crossing(left: &Edge, right: &Edge, y: f32) -> Option<f32> {
+    let end = left.bottom.1.min(right.bottom.1);
+    let (top_left, top_right) = (left.x_at(y), right.x_at(y));
+    let top = top_right - top_left;
+    let bottom = right.x_at(end) - left.x_at(end);
+    let tolerance = 1e-6 * (top_left.abs() + top_right.abs() + 1.0);
+    if top < -tolerance {
+        return Some(y);
+    }
+    if bottom >= -tolerance {
+        return None;
+    }
+    if top <= tolerance {
+        return Some(y);
+    }
+    Some(y + (end - y) * top / (top - bottom))
+}
+
+// The outlines around what's inside the closed outlines, without any of the edges that have
+// the fill on both sides. They go with the fill on the right on a y-down screen, so the outside
+// ones go clockwise and the ones around holes counterclockwise.
+pub(crate) fn boundary(contours: &[Vec<Vector2>], rule: FillRule) -> Vec<Vec<Vector2>> {
+    let (edges, trapezoids) = sweep(contours, rule);
+    let mut segments = outline_segments(&edges, &trapezoids);
+    snap_crossings(&mut segments);
+    chain_segments(&segments)
+}
+
+// Where a trapezoid meets a line, with the fill above it for a bottom or below it for a top
+struct Side {
+    y: f32,
+    is_top: bool,
+    left: f32,
+    right: f32,
+}
+
+// The outline of the fill in segments, each going with the fill on its right on a y-down screen
+fn outline_segments(edges: &[Edge], trapezoids: &[Trapezoid]) -> Vec<(Vector2, Vector2)> {
+    let mut segments = Vec::new();
+    let mut sides = Vec::with_capacity(trapezoids.len() * 2);
+    // The sides of trapezoids on the outline, as the edge, whether the fill is right of it, and
+    // where the trapezoid starts and ends
+    let mut outer = Vec::new();
+    for t in trapezoids {
+        let (left, right) = (&edges[t.left], &edges[t.right]);
+        for (y, is_top) in [(t.top, true), (t.bottom, false)] {
+            sides.push(Side {
+                y,
+                is_top,
+                left: left.x_at(y),
+                right: right.x_at(y),
+            });
+        }
+        if t.outer.0 {
+            outer.push((t.left, true, t.top, t.bottom));
+        }
+        if t.outer.1 {
+            outer.push((t.right, false, t.top, t.bottom));
+        }
+    }
+
+    // Trapezoids one after another down the same edge make one segment. The left side of the
+    // fill goes up and the right side down.
+    outer.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)).then(a.2.total_cmp(&b.2)));
+    for run in outer.chunk_by(|a, b| (a.0, a.1, a.3) == (b.0, b.1, b.2)) {
+        let (edge, fill_right) = (&edges[run[0].0], run[0].1);
+        let (top, bottom) = (run[0].2, run[run.len() - 1].3);
+        let top = Vector2(edge.x_at(top), top);
+        let bottom = Vector2(edge.x_at(bottom), bottom);
+        segments.push(if fill_right {
+            (bottom, top)
+        } else {
+            (top, bottom)
+        });
+    }
+
+    sides.sort_by(|a, b| {
+        (a.y.total_cmp(&b.y))
+            .then(a.is_top.cmp(&b.is_top))
+            .then(a.left.total_cmp(&b.left))
+    });
+    for line in sides.chunk_by(|a, b| a.y == b.y) {
+        let y = line[0].y;
+        let (above, below) = line.split_at(line.partition_point(|s| !s.is_top));
+        let mut xs: Vec<f32> = line.iter().flat_map(|s| [s.left, s.right]).collect();
+        xs.sort_by(f32::total_cmp);
+        xs.dedup();
+
+        // Trapezoids going on past the line have the same fill on both sides of it, so the fill
+        // only differs where the ones ending and starting here do. There the line goes right
+        // with the fill below it and left with the fill above it.
+        let (mut a, mut b) = (0, 0);
+        let fill: Vec<(bool, bool)> = xs
+            .windows(2)
+            .map(|pair| {
+                let mid = (pair[0] + pair[1]) / 2.0;
+                (covers(above, &mut a, mid), covers(below, &mut b, mid))
+            })
+            .collect();
+        let mut i = 0;
+        for run in fill.chunk_by(|a, b| a == b) {
+            let (left, right) = (Vector2(xs[i], y), Vector2(xs[i + run.len()], y));
+            match run[0] {
+                (true, false) => segments.push((right, left)),
+                (false, true) => segments.push((left, right)),
+                _ => (),
+            }
+            i += run.len();
+        }
+    }
+    segments
+}
+
+// Whether `x` is inside one of the sides, which are in order and don't overlap. `i` moves along
+// them as `x` goes up.
+fn covers(sides: &[Side], i: &mut usize, x: f32) -> bool {
+    while sides.get(*i).is_some_and(|s| s.right <= x) {
+        *i += 1;
+    }
+    sides.get(*i).is_some_and(|s| s.left < x)
+}
+
+// Where two edges cross, each of them finds a slightly different x for the crossing.
+// Moves the ends along each line that are that close together onto the same point.
+fn snap_crossings(segments: &mut Vec<(Vector2, Vector2)>) {
+    let mut ends: Vec<Vector2> = segments.iter().flat_map(|&(a, b)| [a, b]).collect();
+    ends.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.total_cmp(&b.0)));
+
+    let mut snapped: HashMap<(u32, u32), f32> = HashMap::new();
+    let mut cluster = Vector2(f32::NAN, f32::NAN);
+    for p in ends {
+        if p.1 != cluster.1 || p.0 - cluster.0 > 1e-5 * p.0.abs().max(1.0) {
+            cluster = p;
+        }
+        snapped.insert(key(p), cluster.0);
+    }
+
+    for (a, b) in segments.iter_mut() {
+        a.0 = snapped[&key(*a)];
+        b.0 = snapped[&key(*b)];
+    }
+    segments.retain(|(a, b)| a != b);
+}
+
+// Adding 0 turns -0 into 0, so both find the same points
+fn key(p: Vector2) -> (u32, u32) {
+    ((p.0 + 0.0).to_bits(), (p.1 + 0.0).to_bits())
+}
+
+// Joins segments end to start into closed outlines, leaving out the points in the middle
+// of straight runs
+fn chain_segments(segments: &[(Vector2, Vector2)]) -> Vec<Vec<Vector2>> {
+    let mut starting_at: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+    for (i, (start, _)) in segments.iter().enumerate() {
+        starting_at.entry(key(*start)).or_default().push(i);
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut outlines = Vec::new();
+    for first in 0..segments.len() {
+        if used[first] {
+            continue;
+        }
+
+        let mut outline = Vec::new();
+        let mut current = first;
+        loop {
+            used[current] = true;
+            let (start, end) = segments[current];
+            // Crossings can leave slivers much shorter than a pixel, whose direction is noise
+            if outline
+                .last()
+                .is_none_or(|&last: &Vector2| (start - last).length() > 1e-3)
+            {
+                outline.push(start);
+            }
+            let next = starting_at
+                .get(&key(end))
+                .and_then(|next| next.iter().copied().find(|&i| !used[i]));
+            match next {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+        while outline.len() > 1 && (outline[0] - outline[outline.len() - 1]).length() <= 1e-3 {
+            outline.pop();
+        }
+        outlines.push(without_straight_points(&outline));
+    }
+
+    outlines.retain(|outline| outline.len() >= 3);
+    outlines
+}
+
+fn without_straight_points(points: &[Vector2]) -> Vec<Vector2> {
+    let points = without_duplicates(points);
+    let n = points.len();
+    if n < 3 {
+        return points;
+    }
+    (0..n)
+        .filter(|&i| {
+            let before = points[i] - points[(i + n - 1) % n];
+            let after = points[(i + 1) % n] - points[i];
+            before.cross(after).abs() > 1e-6 * before.length() * after.length()
+                || before.dot(after) < 0.0
+        })
+        .map(|i| points[i])
+        .collect()
+}
+
+// Twice the signed area, positive when the outline goes clockwise on a y-down screen
+pub(crate) fn signed_area(points: &[Vector2]) -> f32 {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a.cross(*b))
+        .sum()
+}
+
+// Adds the vertex of a fill at a point. The outer edge of the anti-aliasing fringe is `faded`,
+// and has to come out transparent.
+pub(crate) type Shade<'a> = &'a dyn Fn(&mut RendiumDrawHandle, Vector2, bool);
+
+pub(crate) fn solid(col: Color) -> impl Fn(&mut RendiumDrawHandle, Vector2, bool) {
+    move |d, p, faded| {
+        let col = if faded { col.with_alpha(0) } else { col };
+        d.add_vertex([p.0, p.1, 0.0], col, [0.0, 0.0], 0);
+    }
+}
+
+// Fills the outlines. With anti-aliasing the fill is pulled in by half a pixel and a one pixel
+// fringe fading out to transparent goes around it, so the edges look smooth.
+pub(crate) fn add_fill(
+    d: &mut RendiumDrawHandle,
+    contours: &[Vec<Vector2>],
+    rule: FillRule,
+    col: Color,
+) {
+    add_fill_shaded(d, contours, rule, &solid(col));
+}
+
+pub(crate) fn add_fill_shaded(
+    d: &mut RendiumDrawHandle,
+    contours: &[Vec<Vector2>],
+    rule: FillRule,
+    shade: Shade,
+) {
+    let contours: Vec<Vec<Vector2>> = contours
+        .iter()
+        .map(|c| without_duplicates(c))
+        .filter(|c| c.len() >= 3)
+        .collect();
+    if contours.is_empty() {
+        return;
+    }
+
+    if !d.anti_aliasing {
+        add_triangles(d, &tessellate(&contours, rule), shade);
+        return;
+    }
+
+    // Only the edges of the whole fill get a fringe. One on an edge inside the fill, like where
+    // outlines overlap, would show as a darker seam with translucent colors.
+    let outlines = boundary(&contours, rule);
+    let margin = aa_margin(d);
+    let mut inset = Vec::with_capacity(outlines.len());
+    let mut fringes = Vec::with_capacity(outlines.len());
+    for outline in &outlines {
+        // The fill is on the right of the outlines, so their left is outwards
+        let offsets = vertex_offsets(outline, margin / 2.0);
+        inset.push(
+            outline
+                .iter()
+                .zip(&offsets)
+                .map(|(&p, &o)| p - o)
+                .collect::<Vec<_>>(),
+        );
+        fringes.push(offsets);
+    }
+
+    add_triangles(d, &tessellate(&inset, FillRule::NonZero), shade);
+
+    for (inner, offsets) in inset.iter().zip(&fringes) {
+        add_fringe(d, inner, offsets, shade);
+    }
+}
+
+// Fills between two lines of points across from each other, a quad for each pair of points,
+// like a line that doesn't fold over itself. With anti-aliasing it's pulled in and gets a fringe
+// the same as `add_fill_shaded`, without having to find the outline of the fill.
+pub(crate) fn add_strip(
+    d: &mut RendiumDrawHandle,
+    left: &[Vector2],
+    right: &[Vector2],
+    shade: Shade,
+) {
+    let n = left.len().min(right.len());
+    if n < 2 {
+        return;
+    }
+    // Going round the strip with the fill on the right. The quads are the same either way round.
+    let mut outline: Vec<Vector2> = left[..n]
+        .iter()
+        .chain(right[..n].iter().rev())
+        .copied()
+        .collect();
+    if signed_area(&outline) < 0.0 {
+        outline.reverse();
+    }
+    let n = n as u32;
+    let indices = (0..n - 1)
+        .flat_map(|i| {
+            let (a, b, c, e) = (i, i + 1, 2 * n - 2 - i, 2 * n - 1 - i);
+            [a, b, c, a, c, e]
+        })
+        .collect();
+
+    if !d.anti_aliasing {
+        add_triangles(d, &(outline, indices), shade);
+        return;
+    }
+    let offsets = vertex_offsets(&outline, aa_margin(d) / 2.0);
+    let inner: Vec<Vector2> = outline.iter().zip(&offsets).map(|(&p, &o)| p - o).collect();
+    add_triangles(d, &(inner.clone(), indices), shade);
+    add_fringe(d, &inner, &offsets, shade);
+}
+
+// A band around an outline pulled in by `offsets`, fading out from there to as far outside it
+fn add_fringe(d: &mut RendiumDrawHandle, inner: &[Vector2], offsets: &[Vector2], shade: Shade) {
+    let base = d.vertices.len() as u32;
+    for (&p, &o) in inner.iter().zip(offsets) {
+        shade(d, p, false);
+        shade(d, p + o * 2.0, true);
+    }
+    let n = inner.len() as u32;
+    for i in 0..n {
+        let (a, b) = (base + i * 2, base + (i + 1) % n * 2);
+        for index in [a, a + 1, b, b, a + 1, b + 1] {
+            d.add_index(index);
+        }
+    }
+}
+
+pub(crate) fn add_triangles(
+    d: &mut RendiumDrawHandle,
+    (points, indices): &(Vec<Vector2>, Vec<u32>),
+    shade: Shade,
+) {
+    let base = d.vertices.len() as u32;
+    for &p in points {
+        shade(d, p, false);
+    }
+    for i in indices {
+        d.add_index(base + i);
+    }
+}
+
+// How far to move each point so every edge moves `distance` to its left on a y-down screen,
+// which is outwards for a clockwise outline. Sharp corners are limited to a few times the distance.
+fn vertex_offsets(points: &[Vector2], distance: f32) -> Vec<Vector2> {
+    let n = points.len();
+    let normals: Vec<Vector2> = (0..n)
+        .map(|i| {
+            let edge = points[(i + 1) % n] - points[i];
+            -edge.perpendicular().normalized()
+        })
+        .collect();
+
+    (0..n)
+        .map(|i| {
+            let (n1, n2) = (normals[(i + n - 1) % n], normals[i]);
+            (n1 + n2) * (distance / (1.0 + n1.dot(n2)).max(0.25))
+        })
+        .collect()
+}
+
+fn without_duplicates(points: &[Vector2]) -> Vec<Vector2> {
+    let mut out: Vec<Vector2> = Vec::with_capacity(points.len());
+    for &p in points {
+        if out.last() != Some(&p) {
+            out.push(p);
+        }
+    }
+    while out.len() > 1 && out.first() == out.last() {
+        out.pop();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stroke::{LineCap, LineJoin, dash, stroke_outline};
+    use winit::dpi::PhysicalSize;
+
+    fn winding(contours: &[Vec<Vector2>], p: Vector2) -> i32 {
+        let mut winding = 0;
+        for contour in contours {
+            for (i, &a) in contour.iter().enumerate() {
+                let b = contour[(i + 1) % contour.len()];
+                let side = (b - a).cross(p - a);
+                if a.1 <= p.1 && b.1 > p.1 && side > 0.0 {
+                    winding += 1;
+                } else if b.1 <= p.1 && a.1 > p.1 && side < 0.0 {
+                    winding -= 1;
+                }
+            }
+        }
+        winding
+    }
+
+    fn area(outlines: &[Vec<Vector2>]) -> f32 {
+        outlines.iter().map(|o| signed_area(o) / 2.0).sum()
+    }
+
+    fn tessellated_area(contours: &[Vec<Vector2>], rule: FillRule) -> f32 {
+        let (points, indices) = tessellate(contours, rule);
+        indices
+            .chunks(3)
+            .map(|t| {
+                let (a, b, c) = (
+                    points[t[0] as usize],
+                    points[t[1] as usize],
+                    points[t[2] as usize],
+                );
+                (b - a).cross(c - a).abs() / 2.0
+            })
+            .sum()
+    }
+
+    // Every edge of the boundary has the fill just to its right and nothing just to its left,
+    // so none of them are inside the fill
+    fn assert_outlines_fill(contours: &[Vec<Vector2>], rule: FillRule) -> Vec<Vec<Vector2>> {
+        let outlines = boundary(contours, rule);
+        assert!(!outlines.is_empty());
+        for outline in &outlines {
+            for (i, &a) in outline.iter().enumerate() {
+                let b = outline[(i + 1) % outline.len()];
+                let mid = a.lerp(b, 0.5);
+                let right = (b - a).perpendicular() * (1e-2 / (b - a).length()).min(0.05);
+                assert!(
+                    rule.is_inside(winding(contours, mid + right)),
+                    "{a:?} -> {b:?} has no fill on its right"
+                );
+                assert!(
+                    !rule.is_inside(winding(contours, mid - right)),
+                    "{a:?} -> {b:?} has fill on both sides"
+                );
+            }
+        }
+
+        let expected = tessellated_area(contours, rule);
+        assert!((area(&outlines) - expected).abs() <= expected * 1e-4);
+        outlines
+    }
+
+    // How many of the triangles `p` is in
+    fn coverage((points, indices): &(Vec<Vector2>, Vec<u32>), p: Vector2) -> usize {
+        indices
+            .chunks(3)
+            .filter(|t| {
+                let [a, b, c] = [0, 1, 2].map(|i| points[t[i] as usize]);
+                let sides = [
+                    (b - a).cross(p - a),
+                    (c - b).cross(p - b),
+                    (a - c).cross(p - c),
+                ];
+                sides.iter().all(|&s| s > 0.0) || sides.iter().all(|&s| s < 0.0)
+            })
+            .count()
+    }
+
+    fn distance_to_edges(contours: &[Vec<Vector2>], p: Vector2) -> f32 {
+        let mut closest = f32::INFINITY;
+        for contour in contours {
+            for (i, &a) in contour.iter().enumerate() {
+                let b = contour[(i + 1) % contour.len()];
+                let t = ((p - a).dot(b - a) / (b - a).dot(b - a)).clamp(0.0, 1.0);
+                closest = closest.min((a.lerp(b, t) - p).length());
+            }
+        }
+        closest
+    }
+
+    // Points scattered over the outlines, where none of them line up with an edge
+    fn samples(contours: &[Vec<Vector2>]) -> impl Iterator<Item = Vector2> {
+        let points = contours.iter().flatten();
+        let min = points.clone().fold(Vector2(f32::MAX, f32::MAX), |m, p| {
+            Vector2(m.0.min(p.0), m.1.min(p.1))
+        });
+        let max = points.fold(Vector2(f32::MIN, f32::MIN), |m, p| {
+            Vector2(m.0.max(p.0), m.1.max(p.1))
+        });
+        (0..2000).map(move |i| {
+            let (u, v) = ((i as f32 * 0.618_034) % 1.0, (i as f32 * 0.754_877_7) % 1.0);
+            Vector2(min.0 + (max.0 - min.0) * u, min.1 + (max.1 - min.1) * v)
+        })
+    }
+
+    // A polygon crossing itself all over, with some points on the same lines
+    fn scribble() -> Vec<Vector2> {
+        let mut seed = 7u32;
+        (0..40)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                let x = (seed >> 16) % 64;
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                let y = (seed >> 16) % 64;
+                Vector2(x as f32, y as f32)
+            })
+            .collect()
+    }
+
+    fn zigzag(points: usize) -> Vec<Vector2> {
+        (0..points)
+            .map(|i| {
+                let x = 10.0 + 780.0 * i as f32 / points as f32;
+                let y = if i % 2 == 0 { 100.0 } else { 500.0 } + (i * 37 % 50) as f32;
+                Vector2(x, y)
+            })
+            .collect()
+    }
+
+    fn square(pos: Vector2, size: f32) -> Vec<Vector2> {
+        vec![
+            pos,
+            pos + Vector2(size, 0.0),
+            pos + Vector2(size, size),
+            pos + Vector2(0.0, size),
+        ]
+    }
+
+    #[test]
+    fn boundary_of_a_square() {
+        let outlines = assert_outlines_fill(&[square(Vector2(0.0, 0.0), 10.0)], FillRule::NonZero);
+        assert_eq!(outlines.len(), 1);
+        assert_eq!(outlines[0].len(), 4);
+        // Clockwise on screen
+        assert_eq!(area(&outlines), 100.0);
+
+        // Which way the square goes doesn't matter
+        let mut reversed = square(Vector2(0.0, 0.0), 10.0);
+        reversed.reverse();
+        let outlines = assert_outlines_fill(&[reversed], FillRule::NonZero);
+        assert_eq!(area(&outlines), 100.0);
+    }
+
+    #[test]
+    fn boundary_merges_overlaps() {
+        let contours = [
+            square(Vector2(0.0, 0.0), 10.0),
+            square(Vector2(5.0, 5.0), 10.0),
+        ];
+        let outlines = assert_outlines_fill(&contours, FillRule::NonZero);
+        assert_eq!(outlines.len(), 1);
+        assert_eq!(outlines[0].len(), 8);
+        assert_eq!(area(&outlines), 175.0);
+
+        // Even-odd leaves the overlap out
+        let outlines = assert_outlines_fill(&contours, FillRule::EvenOdd);
+        assert_eq!(area(&outlines), 150.0);
+    }
+
+    #[test]
+    fn boundary_with_holes() {
+        let mut hole = square(Vector2(2.0, 2.0), 6.0);
+        hole.reverse();
+        let contours = [square(Vector2(0.0, 0.0), 10.0), hole];
+        for rule in [FillRule::NonZero, FillRule::EvenOdd] {
+            let outlines = assert_outlines_fill(&contours, rule);
+            assert_eq!(outlines.len(), 2);
+            assert_eq!(area(&outlines), 64.0);
+            // The hole goes the other way round
+            assert!(outlines.iter().any(|o| signed_area(o) < 0.0));
+        }
+    }
+
+    #[test]
+    fn boundary_of_a_crossing_outline() {
+        // A bow tie, crossing itself in the middle
+        let contours = [vec![
+            Vector2(0.0, 0.0),
+            Vector2(10.0, 10.0),
+            Vector2(10.0, 0.0),
+            Vector2(0.0, 10.0),
+        ]];
+        let outlines = assert_outlines_fill(&contours, FillRule::NonZero);
+        assert_eq!(area(&outlines), 50.0);
+    }
+
+    #[test]
+    fn boundary_of_strokes_skips_inner_joins() {
+        let zigzag = [
+            Vector2(0.0, 0.0),
+            Vector2(40.0, 10.0),
+            Vector2(5.0, 30.0),
+            Vector2(50.0, 45.0),
+            Vector2(45.0, 0.5),
+        ];
+        for join in [
+            LineJoin::Miter(4.0),
+            LineJoin::Miter(f32::INFINITY),
+            LineJoin::Bevel,
+            LineJoin::Round,
+        ] {
+            for closed in [false, true] {
+                let contours = stroke_outline(&zigzag, 8.0, join, LineCap::Round, closed, 1.0);
+                assert_outlines_fill(&contours, FillRule::NonZero);
+            }
+        }
+    }
+
+    #[test]
+    fn boundary_of_overlapping_dashes() {
+        let rect = square(Vector2(0.0, 0.0), 40.0);
+        let dashes = dash(&rect, true, &[25.0, 5.0], 10.0).unwrap();
+        let contours: Vec<Vec<Vector2>> = dashes
+            .iter()
+            .flat_map(|d| stroke_outline(d, 6.0, LineJoin::Miter(4.0), LineCap::Square, false, 1.0))
+            .collect();
+        assert_outlines_fill(&contours, FillRule::NonZero);
+    }
+
+    #[test]
+    fn boundary_of_a_star() {
+        let star: Vec<Vector2> = (0..10)
+            .map(|i| {
+                let angle = i as f32 * std::f32::consts::PI / 5.0;
+                let radius = if i % 2 == 0 { 28.0 } else { 16.0 };
+                Vector2(96.0 + radius * angle.cos(), 96.0 + radius * angle.sin())
+            })
+            .collect();
+        let contours = stroke_outline(&star, 6.0, LineJoin::Bevel, LineCap::Butt, true, 1.0);
+        assert_outlines_fill(&contours, FillRule::NonZero);
+    }
+
+    #[test]
+    fn tessellation_covers_the_inside_once() {
+        let mut hole = square(Vector2(20.0, 20.0), 20.0);
+        hole.reverse();
+        let shapes = [
+            vec![scribble()],
+            vec![square(Vector2(0.0, 0.0), 60.0), hole],
+            stroke_outline(
+                &zigzag(40),
+                6.0,
+                LineJoin::Miter(4.0),
+                LineCap::Round,
+                false,
+                1.0,
+            ),
+        ];
+        for contours in &shapes {
+            for rule in [FillRule::NonZero, FillRule::EvenOdd] {
+                let triangles = tessellate(contours, rule);
+                for p in samples(contours) {
+                    if distance_to_edges(contours, p) < 1e-3 {
+                        continue;
+                    }
+                    let expected = rule.is_inside(winding(contours, p)) as usize;
+                    assert_eq!(coverage(&triangles, p), expected, "at {p:?} with {rule:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn inset_fill_stays_inside() {
+        let distance = 0.5;
+        let shapes = [
+            vec![scribble()],
+            stroke_outline(
+                &zigzag(40),
+                6.0,
+                LineJoin::Miter(4.0),
+                LineCap::Butt,
+                false,
+                1.0,
+            ),
+        ];
+        for contours in &shapes {
+            let inset: Vec<Vec<Vector2>> = boundary(contours, FillRule::NonZero)
+                .iter()
+                .map(|outline| {
+                    let offsets = vertex_offsets(outline, distance);
+                    outline.iter().zip(offsets).map(|(&p, o)| p - o).collect()
+                })
+                .collect();
+            let fill = tessellate(&inset, FillRule::NonZero);
+            for p in samples(contours) {
+                // Corners move in less than three times the distance. Closer to the edges than
+                // that, parts thinner than the fringe can fold over.
+                if distance_to_edges(contours, p) > distance * 3.0 {
+                    let inside = winding(contours, p) != 0;
+                    assert_eq!(coverage(&fill, p), inside as usize, "at {p:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn long_polylines_are_quick() {
+        let contours = stroke_outline(
+            &zigzag(1000),
+            2.0,
+            LineJoin::Miter(4.0),
+            LineCap::Butt,
+            false,
+            1.0,
+        );
+        let mut d = RendiumDrawHandle::new(PhysicalSize::new(800, 600), HashMap::new());
+        let start = std::time::Instant::now();
+        add_fill(&mut d, &contours, FillRule::NonZero, Color::WHITE);
+        d.set_anti_aliasing(false);
+        add_fill(&mut d, &contours, FillRule::NonZero, Color::WHITE);
+        // Well under this even in a debug build
+        assert!(
+            start.elapsed().as_secs_f32() < 2.0,
+            "took {:?}",
+            start.elapsed()
+        );
+    }
+}
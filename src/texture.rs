@@ -1,32 +1,21 @@
+use anyhow::{Context, bail};
+
 use crate::{
-    RendiumDrawHandle, RendiumInstance,
+    RendiumDrawHandle, RendiumInstance, decode,
+    image::Image,
     types::{Color, Vector2},
 };
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Texture {
-    data: Vec<u8>,
-    width: u32,
-    height: u32,
+    pub(crate) data: Vec<u8>,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
 }
 
-fn load_texture(path: &str) -> anyhow::Result<Texture> {
-    use std::fs::File;
-    use std::io::BufReader;
-
-    let file = File::open(path)?;
-    let decoder = png::Decoder::new(BufReader::new(file));
-    let mut reader = decoder.read_info()?;
-
-    let mut data = vec![0; reader.output_buffer_size()];
-    let info = reader.next_frame(&mut data)?;
-    data.truncate(info.buffer_size());
-
-    Ok(Texture {
-        data,
-        width: info.width,
-        height: info.height,
-    })
+pub(crate) fn load_texture(path: &str) -> anyhow::Result<Texture> {
+    let bytes = std::fs::read(path).with_context(|| format!("Couldn't read image {path:?}"))?;
+    decode::decode(&bytes).with_context(|| format!("Couldn't load image {path:?}"))
 }
 
 #[derive(Clone)]
@@ -34,9 +23,34 @@ pub struct GPUTexture {
     pub bind_group: wgpu::BindGroup,
     sampler: wgpu::Sampler,
     view: wgpu::TextureView,
+    width: u32,
+    height: u32,
+}
+
+impl GPUTexture {
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    pub fn sampler(&self) -> &wgpu::Sampler {
+        &self.sampler
+    }
+
+    // Size of the texture on the GPU, in bytes
+    pub fn memory_size(&self) -> u64 {
+        self.width as u64 * self.height as u64 * 4
+    }
 }
 
-fn create_gpu_texture(
+pub(crate) fn create_gpu_texture(
     device: &wgpu::Device,
     queue: &wgpu::Queue,
     bind_group_layout: &wgpu::BindGroupLayout,
@@ -105,26 +119,76 @@ fn create_gpu_texture(
         bind_group,
         sampler,
         view,
+        width: texture.width,
+        height: texture.height,
     }
 }
 
+// A 1x1 white texture, bound for anything drawn without a texture
+pub(crate) fn create_white_texture(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    bind_group_layout: &wgpu::BindGroupLayout,
+) -> GPUTexture {
+    let texture = Texture {
+        data: vec![255; 4],
+        width: 1,
+        height: 1,
+    };
+    create_gpu_texture(device, queue, bind_group_layout, &texture)
+}
+
 pub trait TextureLoad {
     fn load_texture(&mut self, filename: &str, tex_name: &str) -> anyhow::Result<()>;
+    // For files built into the program with `include_bytes!`
+    fn load_texture_from_memory(&mut self, bytes: &[u8], tex_name: &str) -> anyhow::Result<()>;
+    // Uploads an image made or changed in code, replacing any texture with the same name
+    fn load_texture_from_image(&mut self, image: &Image, tex_name: &str) -> anyhow::Result<()>;
 }
 
 impl TextureLoad for RendiumInstance {
     fn load_texture(&mut self, filename: &str, tex_name: &str) -> anyhow::Result<()> {
-        if let Some(state) = &self.state {
+        if self.state.is_some() {
             let texture = load_texture(filename)?;
-            let bind_group_layout = state.render_pipeline.get_bind_group_layout(0);
-            let gpu_texture =
-                create_gpu_texture(&state.device, &state.queue, &bind_group_layout, &texture);
-            self.texture_storage
-                .insert(tex_name.to_string(), gpu_texture);
+            self.upload_texture(&texture, tex_name);
+        }
+
+        Ok(())
+    }
+
+    fn load_texture_from_memory(&mut self, bytes: &[u8], tex_name: &str) -> anyhow::Result<()> {
+        if self.state.is_some() {
+            let texture = decode::decode(bytes)?;
+            self.upload_texture(&texture, tex_name);
         }
 
         Ok(())
     }
+
+    fn load_texture_from_image(&mut self, image: &Image, tex_name: &str) -> anyhow::Result<()> {
+        if image.width() == 0 || image.height() == 0 {
+            bail!("Can't make texture {tex_name:?} from an empty image");
+        }
+        self.upload_texture(&image.clone().into(), tex_name);
+        Ok(())
+    }
+}
+
+impl RendiumInstance {
+    fn upload_texture(&mut self, texture: &Texture, tex_name: &str) {
+        if let Some(state) = &self.state {
+            let renderer = &state.renderer;
+            let bind_group_layout = renderer.pipelines.main.get_bind_group_layout(0);
+            let gpu_texture = create_gpu_texture(
+                &renderer.device,
+                &renderer.queue,
+                &bind_group_layout,
+                texture,
+            );
+            self.texture_storage
+                .insert(tex_name.to_string(), gpu_texture);
+        }
+    }
 }
 
 pub trait DrawTexture {
@@ -160,10 +224,11 @@ impl DrawTexture for RendiumDrawHandle {
     }
 }
 
-fn get_texture_index(d: &RendiumDrawHandle, name: &str) -> Option<u32> {
+pub(crate) fn get_texture_index(d: &RendiumDrawHandle, name: &str) -> Option<u32> {
     d.textures
         .keys()
         .enumerate()
         .find(|(_, key)| key == &name)
-        .map(|(i, _)| i as u32)
+        // Index 0 is the white texture, so user textures start at 1
+        .map(|(i, _)| i as u32 + 1)
 }
@@ -1,58 +1,215 @@
 // Some useful types
 
-use std::ops::{Add, Mul, Neg, Sub};
+pub use crate::color::{Color, LinearColor, css};
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub struct Color(pub u8, pub u8, pub u8, pub u8);
+use std::iter::Sum;
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
+};
 
-impl Color {
-    pub const WHITE: Self = Self(255, 255, 255, 255);
-    pub const BLACK: Self = Self(0, 0, 0, 255);
-    pub const RED: Self = Self(255, 0, 0, 255);
-    pub const GREEN: Self = Self(0, 255, 0, 255);
-    pub const BLUE: Self = Self(0, 0, 255, 255);
+// Component-wise operators and helpers shared by all the vector types
+macro_rules! impl_vector {
+    ($t:ident, $($i:tt),+) => {
+        impl $t {
+            pub fn length_squared(&self) -> f32 {
+                self.dot(*self)
+            }
 
-    pub fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
-        Self(r, g, b, a)
-    }
+            pub fn min(self, other: Self) -> Self {
+                Self($(self.$i.min(other.$i)),+)
+            }
 
-    pub fn rgb(r: u8, g: u8, b: u8) -> Self {
-        Self(r, g, b, 255)
-    }
+            pub fn max(self, other: Self) -> Self {
+                Self($(self.$i.max(other.$i)),+)
+            }
 
-    pub fn to_linear(self) -> [f32; 4] {
-        [
-            (self.0 as f32 / 255.0).powf(2.2),
-            (self.1 as f32 / 255.0).powf(2.2),
-            (self.2 as f32 / 255.0).powf(2.2),
-            self.3 as f32 / 255.0,
-        ]
-    }
-}
+            pub fn clamp(self, min: Self, max: Self) -> Self {
+                Self($(self.$i.clamp(min.$i, max.$i)),+)
+            }
+
+            pub fn abs(self) -> Self {
+                Self($(self.$i.abs()),+)
+            }
+
+            pub fn floor(self) -> Self {
+                Self($(self.$i.floor()),+)
+            }
 
-impl From<Color> for wgpu::Color {
-    fn from(c: Color) -> Self {
-        wgpu::Color {
-            r: c.0 as f64 / 255.0,
-            g: c.1 as f64 / 255.0,
-            b: c.2 as f64 / 255.0,
-            a: c.3 as f64 / 255.0,
+            pub fn ceil(self) -> Self {
+                Self($(self.$i.ceil()),+)
+            }
+
+            pub fn round(self) -> Self {
+                Self($(self.$i.round()),+)
+            }
+
+            // Keeps the direction, a zero vector stays zero
+            pub fn clamp_length(self, min: f32, max: f32) -> Self {
+                let len = self.length();
+                if len == 0.0 {
+                    self
+                } else {
+                    self * (len.clamp(min, max) / len)
+                }
+            }
+
+            // Bounces off a surface, `normal` should be normalized
+            pub fn reflect(self, normal: Self) -> Self {
+                self - normal * (2.0 * self.dot(normal))
+            }
+
+            // The part of this vector that points along `onto`
+            pub fn project(self, onto: Self) -> Self {
+                let len_sq = onto.length_squared();
+                if len_sq == 0.0 {
+                    Self::zero()
+                } else {
+                    onto * (self.dot(onto) / len_sq)
+                }
+            }
+
+            pub fn approx_eq(self, other: Self, epsilon: f32) -> bool {
+                $((self.$i - other.$i).abs() <= epsilon)&&+
+            }
         }
-    }
-}
 
-impl From<Color> for [f32; 4] {
-    fn from(c: Color) -> Self {
-        [
-            c.0 as f32 / 255.0,
-            c.1 as f32 / 255.0,
-            c.2 as f32 / 255.0,
-            c.3 as f32 / 255.0,
-        ]
-    }
+        impl Add for $t {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                Self($(self.$i + rhs.$i),+)
+            }
+        }
+
+        impl Sub for $t {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self($(self.$i - rhs.$i),+)
+            }
+        }
+
+        impl Mul for $t {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self::Output {
+                Self($(self.$i * rhs.$i),+)
+            }
+        }
+
+        impl Mul<f32> for $t {
+            type Output = Self;
+
+            fn mul(self, rhs: f32) -> Self::Output {
+                Self($(self.$i * rhs),+)
+            }
+        }
+
+        impl Mul<$t> for f32 {
+            type Output = $t;
+
+            fn mul(self, rhs: $t) -> Self::Output {
+                rhs * self
+            }
+        }
+
+        impl Div for $t {
+            type Output = Self;
+
+            fn div(self, rhs: Self) -> Self::Output {
+                Self($(self.$i / rhs.$i),+)
+            }
+        }
+
+        impl Div<f32> for $t {
+            type Output = Self;
+
+            fn div(self, rhs: f32) -> Self::Output {
+                Self($(self.$i / rhs),+)
+            }
+        }
+
+        impl Neg for $t {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                Self($(-self.$i),+)
+            }
+        }
+
+        impl AddAssign for $t {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl SubAssign for $t {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl MulAssign for $t {
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl MulAssign<f32> for $t {
+            fn mul_assign(&mut self, rhs: f32) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl DivAssign for $t {
+            fn div_assign(&mut self, rhs: Self) {
+                *self = *self / rhs;
+            }
+        }
+
+        impl DivAssign<f32> for $t {
+            fn div_assign(&mut self, rhs: f32) {
+                *self = *self / rhs;
+            }
+        }
+
+        impl Sum for $t {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::zero(), |a, b| a + b)
+            }
+        }
+
+        impl<'a> Sum<&'a $t> for $t {
+            fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+                iter.fold(Self::zero(), |a, b| a + *b)
+            }
+        }
+
+        impl Index<usize> for $t {
+            type Output = f32;
+
+            fn index(&self, i: usize) -> &f32 {
+                match i {
+                    $($i => &self.$i,)+
+                    _ => panic!("index {} out of range for {}", i, stringify!($t)),
+                }
+            }
+        }
+
+        impl IndexMut<usize> for $t {
+            fn index_mut(&mut self, i: usize) -> &mut f32 {
+                match i {
+                    $($i => &mut self.$i,)+
+                    _ => panic!("index {} out of range for {}", i, stringify!($t)),
+                }
+            }
+        }
+    };
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector2(pub f32, pub f32);
 
 impl Vector2 {
@@ -91,61 +248,60 @@ impl Vector2 {
             self.1 + (other.1 - self.1) * t,
         )
     }
-}
 
-impl Sub for Vector2 {
-    type Output = Self;
-
-    fn sub(self, other: Self) -> Self::Output {
-        Self(self.0 - other.0, self.1 - other.1)
+    // In radians, from the positive x axis
+    pub fn angle(self) -> f32 {
+        self.1.atan2(self.0)
     }
-}
-
-impl Add for Vector2 {
-    type Output = Self;
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Self(self.0 + rhs.0, self.1 + rhs.1)
+    // The signed angle to turn this vector onto `other`
+    pub fn angle_to(self, other: Self) -> f32 {
+        (self.0 * other.1 - self.1 * other.0).atan2(self.dot(other))
     }
-}
-
-impl Mul for Vector2 {
-    type Output = Self;
 
-    fn mul(self, rhs: Self) -> Self::Output {
-        Self(self.0 * rhs.0, self.1 * rhs.1)
+    pub fn rotate(self, angle: f32) -> Self {
+        let (s, c) = angle.sin_cos();
+        Self(self.0 * c - self.1 * s, self.0 * s + self.1 * c)
     }
-}
-
-impl Mul<f32> for Vector2 {
-    type Output = Self;
 
-    fn mul(self, rhs: f32) -> Self::Output {
-        Self(self.0 * rhs, self.1 * rhs)
+    // Rotated by 90 degrees, clockwise on screen since y goes down
+    pub fn perpendicular(self) -> Self {
+        Self(-self.1, self.0)
     }
-}
 
-impl Neg for Vector2 {
-    type Output = Self;
+    // The z component of the 3D cross product
+    pub fn cross(self, other: Self) -> f32 {
+        self.0 * other.1 - self.1 * other.0
+    }
 
-    fn neg(self) -> Self::Output {
-        Self(-self.0, -self.1)
+    pub fn extend(self, z: f32) -> Vector3 {
+        Vector3(self.0, self.1, z)
     }
 }
 
+impl_vector!(Vector2, 0, 1);
+
 impl From<Vector2> for [f32; 2] {
     fn from(v: Vector2) -> Self {
         [v.0, v.1]
     }
 }
 
+impl From<[f32; 2]> for Vector2 {
+    fn from(v: [f32; 2]) -> Self {
+        Self(v[0], v[1])
+    }
+}
+
 impl From<(f32, f32)> for Vector2 {
     fn from(v: (f32, f32)) -> Self {
         Self(v.0, v.1)
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector3(pub f32, pub f32, pub f32);
 
 impl Vector3 {
@@ -193,94 +349,1655 @@ impl Vector3 {
             self.2 + (other.2 - self.2) * t,
         )
     }
+
+    // The unsigned angle between the two, in radians
+    pub fn angle_to(self, other: Self) -> f32 {
+        self.cross(other).length().atan2(self.dot(other))
+    }
+
+    pub fn xy(self) -> Vector2 {
+        Vector2(self.0, self.1)
+    }
+
+    pub fn extend(self, w: f32) -> Vector4 {
+        Vector4(self.0, self.1, self.2, w)
+    }
 }
 
-impl Sub for Vector3 {
-    type Output = Self;
+impl_vector!(Vector3, 0, 1, 2);
 
-    fn sub(self, other: Self) -> Self::Output {
-        Self(self.0 - other.0, self.1 - other.1, self.2 - other.2)
+impl From<Vector3> for [f32; 3] {
+    fn from(v: Vector3) -> Self {
+        [v.0, v.1, v.2]
     }
 }
 
-impl Add for Vector3 {
-    type Output = Self;
+impl From<[f32; 3]> for Vector3 {
+    fn from(v: [f32; 3]) -> Self {
+        Self(v[0], v[1], v[2])
+    }
+}
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Self(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
+impl From<(f32, f32, f32)> for Vector3 {
+    fn from(v: (f32, f32, f32)) -> Self {
+        Self(v.0, v.1, v.2)
     }
 }
 
-impl Mul for Vector3 {
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vector4(pub f32, pub f32, pub f32, pub f32);
+
+impl Vector4 {
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self(x, y, z, w)
+    }
+
+    pub fn zero() -> Self {
+        Self(0.0, 0.0, 0.0, 0.0)
+    }
+
+    pub fn length(&self) -> f32 {
+        self.dot(*self).sqrt()
+    }
+
+    pub fn normalized(self) -> Self {
+        let len = self.length();
+        if len != 0.0 { self / len } else { self }
+    }
+
+    pub fn dot(self, other: Self) -> f32 {
+        self.0 * other.0 + self.1 * other.1 + self.2 * other.2 + self.3 * other.3
+    }
+
+    pub fn distance(self, other: Self) -> f32 {
+        (self - other).length()
+    }
+
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+
+    pub fn xy(self) -> Vector2 {
+        Vector2(self.0, self.1)
+    }
+
+    pub fn xyz(self) -> Vector3 {
+        Vector3(self.0, self.1, self.2)
+    }
+}
+
+impl_vector!(Vector4, 0, 1, 2, 3);
+
+impl From<Vector4> for [f32; 4] {
+    fn from(v: Vector4) -> Self {
+        [v.0, v.1, v.2, v.3]
+    }
+}
+
+impl From<[f32; 4]> for Vector4 {
+    fn from(v: [f32; 4]) -> Self {
+        Self(v[0], v[1], v[2], v[3])
+    }
+}
+
+impl From<(f32, f32, f32, f32)> for Vector4 {
+    fn from(v: (f32, f32, f32, f32)) -> Self {
+        Self(v.0, v.1, v.2, v.3)
+    }
+}
+
+// An axis-aligned rectangle. `pos` is the corner with the smallest coordinates,
+// the top left one with the default y-down screen space.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect {
+    pub pos: Vector2,
+    pub size: Vector2,
+}
+
+impl Rect {
+    pub fn new(pos: Vector2, size: Vector2) -> Self {
+        Self { pos, size }
+    }
+
+    // Works with the corners in any order
+    pub fn from_corners(a: Vector2, b: Vector2) -> Self {
+        let min = a.min(b);
+        Self::new(min, a.max(b) - min)
+    }
+
+    pub fn from_center(center: Vector2, size: Vector2) -> Self {
+        Self::new(center - size * 0.5, size)
+    }
+
+    pub fn min(&self) -> Vector2 {
+        self.pos
+    }
+
+    pub fn max(&self) -> Vector2 {
+        self.pos + self.size
+    }
+
+    pub fn center(&self) -> Vector2 {
+        self.pos + self.size * 0.5
+    }
+
+    pub fn area(&self) -> f32 {
+        self.size.0 * self.size.1
+    }
+
+    // In drawing order: min, (max x, min y), max, (min x, max y)
+    pub fn corners(&self) -> [Vector2; 4] {
+        let Vector2(x0, y0) = self.min();
+        let Vector2(x1, y1) = self.max();
+        [
+            Vector2(x0, y0),
+            Vector2(x1, y0),
+            Vector2(x1, y1),
+            Vector2(x0, y1),
+        ]
+    }
+
+    // The min edges are inside and the max edges outside, so rects that tile
+    // a grid never both contain the same point
+    pub fn contains_point(&self, p: Vector2) -> bool {
+        let max = self.max();
+        p.0 >= self.pos.0 && p.1 >= self.pos.1 && p.0 < max.0 && p.1 < max.1
+    }
+
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        let (max, other_max) = (self.max(), other.max());
+        other.pos.0 >= self.pos.0
+            && other.pos.1 >= self.pos.1
+            && other_max.0 <= max.0
+            && other_max.1 <= max.1
+    }
+
+    // Rects that only touch don't intersect
+    pub fn intersects(&self, other: &Rect) -> bool {
+        let (max, other_max) = (self.max(), other.max());
+        self.pos.0 < other_max.0
+            && other.pos.0 < max.0
+            && self.pos.1 < other_max.1
+            && other.pos.1 < max.1
+    }
+
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        self.intersects(other)
+            .then(|| Rect::from_corners(self.pos.max(other.pos), self.max().min(other.max())))
+    }
+
+    // The smallest rect containing both
+    pub fn union(&self, other: &Rect) -> Rect {
+        Rect::from_corners(self.pos.min(other.pos), self.max().max(other.max()))
+    }
+
+    // Grows every side by `amount`, a negative amount shrinks the rect down to its center
+    pub fn expand(&self, amount: f32) -> Rect {
+        let size = (self.size + Vector2(amount, amount) * 2.0).max(Vector2::zero());
+        Rect::from_center(self.center(), size)
+    }
+
+    // Cuts the rect at `amount` along x, the left part is at most the whole rect
+    pub fn split_x(&self, amount: f32) -> (Rect, Rect) {
+        let amount = amount.clamp(0.0, self.size.0);
+        (
+            Rect::new(self.pos, Vector2(amount, self.size.1)),
+            Rect::new(
+                self.pos + Vector2(amount, 0.0),
+                Vector2(self.size.0 - amount, self.size.1),
+            ),
+        )
+    }
+
+    // Cuts the rect at `amount` along y
+    pub fn split_y(&self, amount: f32) -> (Rect, Rect) {
+        let amount = amount.clamp(0.0, self.size.1);
+        (
+            Rect::new(self.pos, Vector2(self.size.0, amount)),
+            Rect::new(
+                self.pos + Vector2(0.0, amount),
+                Vector2(self.size.0, self.size.1 - amount),
+            ),
+        )
+    }
+
+    // Splits the rect into a grid of equal cells, row by row
+    pub fn split_grid(&self, columns: usize, rows: usize) -> Vec<Rect> {
+        if columns == 0 || rows == 0 {
+            return Vec::new();
+        }
+        let cell = Vector2(self.size.0 / columns as f32, self.size.1 / rows as f32);
+        (0..rows)
+            .flat_map(|y| {
+                (0..columns)
+                    .map(move |x| Rect::new(self.pos + cell * Vector2(x as f32, y as f32), cell))
+            })
+            .collect()
+    }
+
+    // The point inside the rect closest to `p`
+    pub fn clamp_point(&self, p: Vector2) -> Vector2 {
+        p.clamp(self.min(), self.max())
+    }
+}
+
+// A 2D affine transform, stored as three columns.
+// Anything made with the constructors keeps (0, 0, 1) as the last row.
+// A WGSL mat3x3 pads every column to 16 bytes, so upload `to_gpu()` rather than this.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Matrix3(pub [[f32; 3]; 3]);
+
+impl Default for Matrix3 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Matrix3 {
+    pub const IDENTITY: Self = Self([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+
+    pub fn identity() -> Self {
+        Self::IDENTITY
+    }
+
+    pub fn from_cols(x: Vector3, y: Vector3, z: Vector3) -> Self {
+        Self([x.into(), y.into(), z.into()])
+    }
+
+    pub fn translation(offset: Vector2) -> Self {
+        Self([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [offset.0, offset.1, 1.0]])
+    }
+
+    // In radians
+    pub fn rotation(angle: f32) -> Self {
+        let (s, c) = angle.sin_cos();
+        Self([[c, s, 0.0], [-s, c, 0.0], [0.0, 0.0, 1.0]])
+    }
+
+    pub fn scale(factor: Vector2) -> Self {
+        Self([[factor.0, 0.0, 0.0], [0.0, factor.1, 0.0], [0.0, 0.0, 1.0]])
+    }
+
+    // Scale, then rotate, then translate
+    pub fn from_scale_rotation_translation(scale: Vector2, angle: f32, offset: Vector2) -> Self {
+        Self::translation(offset) * Self::rotation(angle) * Self::scale(scale)
+    }
+
+    // Maps the rect to -1..1 on both axes, `bottom` can be larger than `top` for y-down
+    pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32) -> Self {
+        let rcp_w = 1.0 / (right - left);
+        let rcp_h = 1.0 / (top - bottom);
+        Self([
+            [2.0 * rcp_w, 0.0, 0.0],
+            [0.0, 2.0 * rcp_h, 0.0],
+            [-(left + right) * rcp_w, -(top + bottom) * rcp_h, 1.0],
+        ])
+    }
+
+    pub fn col(&self, i: usize) -> Vector3 {
+        self.0[i].into()
+    }
+
+    pub fn row(&self, i: usize) -> Vector3 {
+        Vector3(self.0[0][i], self.0[1][i], self.0[2][i])
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self::from_cols(self.row(0), self.row(1), self.row(2))
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.col(2).dot(self.col(0).cross(self.col(1)))
+    }
+
+    // None if the matrix can't be inverted, like a scale of zero
+    pub fn inverse(&self) -> Option<Self> {
+        let (x, y, z) = (self.col(0), self.col(1), self.col(2));
+        let det = z.dot(x.cross(y));
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        // The rows of the inverse are the cross products of the columns
+        let inv_det = 1.0 / det;
+        Some(
+            Self::from_cols(
+                y.cross(z) * inv_det,
+                z.cross(x) * inv_det,
+                x.cross(y) * inv_det,
+            )
+            .transpose(),
+        )
+    }
+
+    pub fn transform_point(&self, p: Vector2) -> Vector2 {
+        let m = &self.0;
+        Vector2(
+            m[0][0] * p.0 + m[1][0] * p.1 + m[2][0],
+            m[0][1] * p.0 + m[1][1] * p.1 + m[2][1],
+        )
+    }
+
+    // Like `transform_point`, but ignores the translation
+    pub fn transform_vector(&self, v: Vector2) -> Vector2 {
+        let m = &self.0;
+        Vector2(m[0][0] * v.0 + m[1][0] * v.1, m[0][1] * v.0 + m[1][1] * v.1)
+    }
+
+    // The 48 byte layout of a WGSL mat3x3<f32>, ready for a uniform buffer
+    pub fn to_gpu(&self) -> GpuMatrix3 {
+        GpuMatrix3(self.0.map(|[x, y, z]| [x, y, z, 0.0]))
+    }
+}
+
+// A Matrix3 with every column padded to 16 bytes, matching WGSL's mat3x3<f32>
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct GpuMatrix3(pub [[f32; 4]; 3]);
+
+impl From<Matrix3> for GpuMatrix3 {
+    fn from(m: Matrix3) -> Self {
+        m.to_gpu()
+    }
+}
+
+impl From<GpuMatrix3> for Matrix3 {
+    fn from(m: GpuMatrix3) -> Self {
+        Self(m.0.map(|[x, y, z, _]| [x, y, z]))
+    }
+}
+
+impl Mul for Matrix3 {
     type Output = Self;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        Self(self.0 * rhs.0, self.1 * rhs.1, self.2 * rhs.2)
+        Self::from_cols(self * rhs.col(0), self * rhs.col(1), self * rhs.col(2))
     }
 }
 
-impl Mul<f32> for Vector3 {
-    type Output = Self;
+impl Mul<Vector3> for Matrix3 {
+    type Output = Vector3;
+
+    fn mul(self, v: Vector3) -> Self::Output {
+        self.col(0) * v.0 + self.col(1) * v.1 + self.col(2) * v.2
+    }
+}
+
+impl From<Matrix3> for [[f32; 3]; 3] {
+    fn from(m: Matrix3) -> Self {
+        m.0
+    }
+}
+
+// A 4x4 matrix, stored as four columns like WGSL's mat4x4<f32>.
+// Projections use wgpu's clip space, with depth going from 0 to 1.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Matrix4(pub [[f32; 4]; 4]);
 
-    fn mul(self, rhs: f32) -> Self::Output {
-        Self(self.0 * rhs, self.1 * rhs, self.2 * rhs)
+impl Default for Matrix4 {
+    fn default() -> Self {
+        Self::IDENTITY
     }
 }
 
-impl Neg for Vector3 {
+impl Matrix4 {
+    pub const IDENTITY: Self = Self([
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]);
+
+    pub fn identity() -> Self {
+        Self::IDENTITY
+    }
+
+    pub fn from_cols(x: Vector4, y: Vector4, z: Vector4, w: Vector4) -> Self {
+        Self([x.into(), y.into(), z.into(), w.into()])
+    }
+
+    pub fn translation(offset: Vector3) -> Self {
+        let mut m = Self::IDENTITY;
+        m.0[3] = [offset.0, offset.1, offset.2, 1.0];
+        m
+    }
+
+    pub fn scale(factor: Vector3) -> Self {
+        let mut m = Self::IDENTITY;
+        m.0[0][0] = factor.0;
+        m.0[1][1] = factor.1;
+        m.0[2][2] = factor.2;
+        m
+    }
+
+    // In radians
+    pub fn rotation_x(angle: f32) -> Self {
+        let (s, c) = angle.sin_cos();
+        let mut m = Self::IDENTITY;
+        m.0[1] = [0.0, c, s, 0.0];
+        m.0[2] = [0.0, -s, c, 0.0];
+        m
+    }
+
+    pub fn rotation_y(angle: f32) -> Self {
+        let (s, c) = angle.sin_cos();
+        let mut m = Self::IDENTITY;
+        m.0[0] = [c, 0.0, -s, 0.0];
+        m.0[2] = [s, 0.0, c, 0.0];
+        m
+    }
+
+    pub fn rotation_z(angle: f32) -> Self {
+        let (s, c) = angle.sin_cos();
+        let mut m = Self::IDENTITY;
+        m.0[0] = [c, s, 0.0, 0.0];
+        m.0[1] = [-s, c, 0.0, 0.0];
+        m
+    }
+
+    pub fn rotation(axis: Vector3, angle: f32) -> Self {
+        Quaternion::from_axis_angle(axis, angle).into()
+    }
+
+    // Scale, then rotate, then translate
+    pub fn from_scale_rotation_translation(
+        scale: Vector3,
+        rotation: Quaternion,
+        offset: Vector3,
+    ) -> Self {
+        Self::translation(offset) * Self::from(rotation) * Self::scale(scale)
+    }
+
+    // Right-handed, the camera looks down -z
+    pub fn orthographic_rh(
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        near: f32,
+        far: f32,
+    ) -> Self {
+        let rcp_w = 1.0 / (right - left);
+        let rcp_h = 1.0 / (top - bottom);
+        let rcp_d = 1.0 / (near - far);
+        Self([
+            [2.0 * rcp_w, 0.0, 0.0, 0.0],
+            [0.0, 2.0 * rcp_h, 0.0, 0.0],
+            [0.0, 0.0, rcp_d, 0.0],
+            [
+                -(left + right) * rcp_w,
+                -(top + bottom) * rcp_h,
+                rcp_d * near,
+                1.0,
+            ],
+        ])
+    }
+
+    // Left-handed, the camera looks down +z
+    pub fn orthographic_lh(
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        near: f32,
+        far: f32,
+    ) -> Self {
+        let mut m = Self::orthographic_rh(left, right, bottom, top, near, far);
+        let rcp_d = 1.0 / (far - near);
+        m.0[2][2] = rcp_d;
+        m.0[3][2] = -rcp_d * near;
+        m
+    }
+
+    // `fov_y` is in radians
+    pub fn perspective_rh(fov_y: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let h = 1.0 / (0.5 * fov_y).tan();
+        let w = h / aspect;
+        let r = far / (near - far);
+        Self([
+            [w, 0.0, 0.0, 0.0],
+            [0.0, h, 0.0, 0.0],
+            [0.0, 0.0, r, -1.0],
+            [0.0, 0.0, r * near, 0.0],
+        ])
+    }
+
+    pub fn perspective_lh(fov_y: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let h = 1.0 / (0.5 * fov_y).tan();
+        let w = h / aspect;
+        let r = far / (far - near);
+        Self([
+            [w, 0.0, 0.0, 0.0],
+            [0.0, h, 0.0, 0.0],
+            [0.0, 0.0, r, 1.0],
+            [0.0, 0.0, -r * near, 0.0],
+        ])
+    }
+
+    // A view matrix for a camera at `eye` looking at `target`
+    pub fn look_at_rh(eye: Vector3, target: Vector3, up: Vector3) -> Self {
+        let f = (target - eye).normalized();
+        let s = f.cross(up).normalized();
+        let u = s.cross(f);
+        Self([
+            [s.0, u.0, -f.0, 0.0],
+            [s.1, u.1, -f.1, 0.0],
+            [s.2, u.2, -f.2, 0.0],
+            [-s.dot(eye), -u.dot(eye), f.dot(eye), 1.0],
+        ])
+    }
+
+    pub fn look_at_lh(eye: Vector3, target: Vector3, up: Vector3) -> Self {
+        Self::look_at_rh(eye, eye * 2.0 - target, up)
+    }
+
+    pub fn col(&self, i: usize) -> Vector4 {
+        self.0[i].into()
+    }
+
+    pub fn row(&self, i: usize) -> Vector4 {
+        Vector4(self.0[0][i], self.0[1][i], self.0[2][i], self.0[3][i])
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self::from_cols(self.row(0), self.row(1), self.row(2), self.row(3))
+    }
+
+    pub fn determinant(&self) -> f32 {
+        // Expand along the first column
+        (0..4)
+            .map(|row| {
+                let sign = if row % 2 == 0 { 1.0 } else { -1.0 };
+                sign * self.0[0][row] * self.minor(0, row)
+            })
+            .sum()
+    }
+
+    // The determinant of the 3x3 matrix left after removing a column and a row
+    fn minor(&self, col: usize, row: usize) -> f32 {
+        let mut m = [[0.0; 3]; 3];
+        for (c, src) in (0..4).filter(|&c| c != col).enumerate() {
+            for (r, src_row) in (0..4).filter(|&r| r != row).enumerate() {
+                m[c][r] = self.0[src][src_row];
+            }
+        }
+        Matrix3(m).determinant()
+    }
+
+    // None if the matrix can't be inverted
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        // The adjugate divided by the determinant
+        let inv_det = 1.0 / det;
+        let mut out = [[0.0; 4]; 4];
+        for (col, out_col) in out.iter_mut().enumerate() {
+            for (row, value) in out_col.iter_mut().enumerate() {
+                let sign = if (col + row) % 2 == 0 { 1.0 } else { -1.0 };
+                *value = sign * self.minor(row, col) * inv_det;
+            }
+        }
+        Some(Self(out))
+    }
+
+    // Assumes the matrix is affine, so there's no divide by w
+    pub fn transform_point(&self, p: Vector3) -> Vector3 {
+        let v = *self * Vector4(p.0, p.1, p.2, 1.0);
+        Vector3(v.0, v.1, v.2)
+    }
+
+    // Like `transform_point`, but ignores the translation
+    pub fn transform_vector(&self, v: Vector3) -> Vector3 {
+        let v = *self * Vector4(v.0, v.1, v.2, 0.0);
+        Vector3(v.0, v.1, v.2)
+    }
+
+    // Divides by w, for going through a perspective projection
+    pub fn project_point(&self, p: Vector3) -> Vector3 {
+        let v = *self * Vector4(p.0, p.1, p.2, 1.0);
+        Vector3(v.0 / v.3, v.1 / v.3, v.2 / v.3)
+    }
+}
+
+impl Mul for Matrix4 {
     type Output = Self;
 
-    fn neg(self) -> Self::Output {
-        Self(-self.0, -self.1, -self.2)
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::from_cols(
+            self * rhs.col(0),
+            self * rhs.col(1),
+            self * rhs.col(2),
+            self * rhs.col(3),
+        )
     }
 }
 
-impl From<Vector3> for [f32; 3] {
-    fn from(v: Vector3) -> Self {
-        [v.0, v.1, v.2]
+impl Mul<Vector4> for Matrix4 {
+    type Output = Vector4;
+
+    fn mul(self, v: Vector4) -> Self::Output {
+        let [x, y, z, w] = self.0;
+        Vector4(
+            x[0] * v.0 + y[0] * v.1 + z[0] * v.2 + w[0] * v.3,
+            x[1] * v.0 + y[1] * v.1 + z[1] * v.2 + w[1] * v.3,
+            x[2] * v.0 + y[2] * v.1 + z[2] * v.2 + w[2] * v.3,
+            x[3] * v.0 + y[3] * v.1 + z[3] * v.2 + w[3] * v.3,
+        )
     }
 }
 
-impl From<(f32, f32, f32)> for Vector3 {
-    fn from(v: (f32, f32, f32)) -> Self {
-        Self(v.0, v.1, v.2)
+// Puts a 2D transform on the xy plane, leaving z alone
+impl From<Matrix3> for Matrix4 {
+    fn from(m: Matrix3) -> Self {
+        let [x, y, t] = m.0;
+        Self([
+            [x[0], x[1], 0.0, x[2]],
+            [y[0], y[1], 0.0, y[2]],
+            [0.0, 0.0, 1.0, 0.0],
+            [t[0], t[1], 0.0, t[2]],
+        ])
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub struct Vector4(pub f32, pub f32, pub f32, pub f32);
+impl From<Quaternion> for Matrix4 {
+    fn from(q: Quaternion) -> Self {
+        let Quaternion(x, y, z, w) = q;
+        let (x2, y2, z2) = (x + x, y + y, z + z);
+        let (xx, xy, xz) = (x * x2, x * y2, x * z2);
+        let (yy, yz, zz) = (y * y2, y * z2, z * z2);
+        let (wx, wy, wz) = (w * x2, w * y2, w * z2);
 
-impl Vector4 {
-    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
-        Self(x, y, z, w)
+        Self([
+            [1.0 - (yy + zz), xy + wz, xz - wy, 0.0],
+            [xy - wz, 1.0 - (xx + zz), yz + wx, 0.0],
+            [xz + wy, yz - wx, 1.0 - (xx + yy), 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
     }
+}
 
-    pub fn zero() -> Self {
-        Self(0.0, 0.0, 0.0, 0.0)
+impl From<Matrix4> for [[f32; 4]; 4] {
+    fn from(m: Matrix4) -> Self {
+        m.0
+    }
+}
+
+// A rotation in 3D, stored as (x, y, z, w) with w being the scalar part
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quaternion(pub f32, pub f32, pub f32, pub f32);
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self::IDENTITY
     }
 }
 
-impl Sub for Vector4 {
+impl Quaternion {
+    pub const IDENTITY: Self = Self(0.0, 0.0, 0.0, 1.0);
+
+    pub fn identity() -> Self {
+        Self::IDENTITY
+    }
+
+    // In radians, `axis` doesn't need to be normalized
+    pub fn from_axis_angle(axis: Vector3, angle: f32) -> Self {
+        let (s, c) = (angle * 0.5).sin_cos();
+        let a = axis.normalized() * s;
+        Self(a.0, a.1, a.2, c)
+    }
+
+    pub fn from_rotation_x(angle: f32) -> Self {
+        Self::from_axis_angle(Vector3(1.0, 0.0, 0.0), angle)
+    }
+
+    pub fn from_rotation_y(angle: f32) -> Self {
+        Self::from_axis_angle(Vector3(0.0, 1.0, 0.0), angle)
+    }
+
+    pub fn from_rotation_z(angle: f32) -> Self {
+        Self::from_axis_angle(Vector3(0.0, 0.0, 1.0), angle)
+    }
+
+    // The axis is zero for the identity rotation
+    pub fn to_axis_angle(self) -> (Vector3, f32) {
+        let q = self.normalized();
+        let s = (1.0 - q.3 * q.3).max(0.0).sqrt();
+        let angle = 2.0 * q.3.clamp(-1.0, 1.0).acos();
+        if s < 1e-6 {
+            (Vector3::zero(), angle)
+        } else {
+            (Vector3(q.0 / s, q.1 / s, q.2 / s), angle)
+        }
+    }
+
+    // The rotation part of `m`, which shouldn't have any scale or skew
+    pub fn from_rotation_matrix(m: Matrix4) -> Self {
+        // `r(row, col)`, the matrix is stored as columns
+        let r = |row: usize, col: usize| m.0[col][row];
+        let trace = r(0, 0) + r(1, 1) + r(2, 2);
+
+        // Divides by the biggest component to stay accurate
+        if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Self(
+                (r(2, 1) - r(1, 2)) / s,
+                (r(0, 2) - r(2, 0)) / s,
+                (r(1, 0) - r(0, 1)) / s,
+                0.25 * s,
+            )
+        } else if r(0, 0) > r(1, 1) && r(0, 0) > r(2, 2) {
+            let s = (1.0 + r(0, 0) - r(1, 1) - r(2, 2)).sqrt() * 2.0;
+            Self(
+                0.25 * s,
+                (r(0, 1) + r(1, 0)) / s,
+                (r(0, 2) + r(2, 0)) / s,
+                (r(2, 1) - r(1, 2)) / s,
+            )
+        } else if r(1, 1) > r(2, 2) {
+            let s = (1.0 + r(1, 1) - r(0, 0) - r(2, 2)).sqrt() * 2.0;
+            Self(
+                (r(0, 1) + r(1, 0)) / s,
+                0.25 * s,
+                (r(1, 2) + r(2, 1)) / s,
+                (r(0, 2) - r(2, 0)) / s,
+            )
+        } else {
+            let s = (1.0 + r(2, 2) - r(0, 0) - r(1, 1)).sqrt() * 2.0;
+            Self(
+                (r(0, 2) + r(2, 0)) / s,
+                (r(1, 2) + r(2, 1)) / s,
+                0.25 * s,
+                (r(1, 0) - r(0, 1)) / s,
+            )
+        }
+    }
+
+    pub fn dot(self, other: Self) -> f32 {
+        self.0 * other.0 + self.1 * other.1 + self.2 * other.2 + self.3 * other.3
+    }
+
+    pub fn length(&self) -> f32 {
+        self.dot(*self).sqrt()
+    }
+
+    pub fn normalized(self) -> Self {
+        let len = self.length();
+        if len != 0.0 {
+            Self(self.0 / len, self.1 / len, self.2 / len, self.3 / len)
+        } else {
+            self
+        }
+    }
+
+    pub fn conjugate(self) -> Self {
+        Self(-self.0, -self.1, -self.2, self.3)
+    }
+
+    pub fn inverse(self) -> Self {
+        let len_sq = self.dot(self);
+        if len_sq == 0.0 {
+            return self;
+        }
+        let c = self.conjugate();
+        Self(c.0 / len_sq, c.1 / len_sq, c.2 / len_sq, c.3 / len_sq)
+    }
+
+    pub fn rotate_vector(self, v: Vector3) -> Vector3 {
+        let q = Vector3(self.0, self.1, self.2);
+        let t = q.cross(v) * 2.0;
+        v + t * self.3 + q.cross(t)
+    }
+
+    // Spherical interpolation, always along the shortest path
+    pub fn slerp(self, other: Self, t: f32) -> Self {
+        let mut other = other;
+        let mut cos = self.dot(other);
+        if cos < 0.0 {
+            other = Self(-other.0, -other.1, -other.2, -other.3);
+            cos = -cos;
+        }
+
+        // Too close for slerp to be stable, so lerp instead
+        if cos > 0.9995 {
+            return Self(
+                self.0 + (other.0 - self.0) * t,
+                self.1 + (other.1 - self.1) * t,
+                self.2 + (other.2 - self.2) * t,
+                self.3 + (other.3 - self.3) * t,
+            )
+            .normalized();
+        }
+
+        let angle = cos.acos();
+        let sin = angle.sin();
+        let a = ((1.0 - t) * angle).sin() / sin;
+        let b = (t * angle).sin() / sin;
+        Self(
+            self.0 * a + other.0 * b,
+            self.1 * a + other.1 * b,
+            self.2 * a + other.2 * b,
+            self.3 * a + other.3 * b,
+        )
+    }
+}
+
+// Applies `rhs` first, then `self`
+impl Mul for Quaternion {
     type Output = Self;
 
-    fn sub(self, other: Self) -> Self::Output {
+    fn mul(self, rhs: Self) -> Self::Output {
+        let Self(x1, y1, z1, w1) = self;
+        let Self(x2, y2, z2, w2) = rhs;
         Self(
-            self.0 - other.0,
-            self.1 - other.1,
-            self.2 - other.2,
-            self.3 - other.3,
+            w1 * x2 + x1 * w2 + y1 * z2 - z1 * y2,
+            w1 * y2 - x1 * z2 + y1 * w2 + z1 * x2,
+            w1 * z2 + x1 * y2 - y1 * x2 + z1 * w2,
+            w1 * w2 - x1 * x2 - y1 * y2 - z1 * z2,
         )
     }
 }
 
-impl From<Vector4> for [f32; 4] {
-    fn from(v: Vector4) -> Self {
-        [v.0, v.1, v.2, v.3]
+impl Mul<Vector3> for Quaternion {
+    type Output = Vector3;
+
+    fn mul(self, v: Vector3) -> Self::Output {
+        self.rotate_vector(v)
     }
 }
 
-impl From<(f32, f32, f32, f32)> for Vector4 {
-    fn from(v: (f32, f32, f32, f32)) -> Self {
-        Self(v.0, v.1, v.2, v.3)
+impl From<Quaternion> for [f32; 4] {
+    fn from(q: Quaternion) -> Self {
+        [q.0, q.1, q.2, q.3]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    const EPSILON: f32 = 1e-5;
+
+    fn assert_mat3_eq(a: Matrix3, b: Matrix3) {
+        for (col_a, col_b) in a.0.iter().zip(&b.0) {
+            for (x, y) in col_a.iter().zip(col_b) {
+                assert!((x - y).abs() <= EPSILON, "{a:?} != {b:?}");
+            }
+        }
+    }
+
+    fn assert_mat4_eq(a: Matrix4, b: Matrix4) {
+        for (col_a, col_b) in a.0.iter().zip(&b.0) {
+            for (x, y) in col_a.iter().zip(col_b) {
+                assert!((x - y).abs() <= EPSILON, "{a:?} != {b:?}");
+            }
+        }
+    }
+
+    fn assert_vec2_eq(a: Vector2, b: Vector2) {
+        assert!(a.approx_eq(b, EPSILON), "{a:?} != {b:?}");
+    }
+
+    fn assert_vec3_eq(a: Vector3, b: Vector3) {
+        assert!(a.approx_eq(b, EPSILON), "{a:?} != {b:?}");
+    }
+
+    // The same rotation either way round
+    fn assert_quat_eq(a: Quaternion, b: Quaternion) {
+        assert!((a.dot(b).abs() - 1.0).abs() <= EPSILON, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn rect_contains_point_edges() {
+        let r = Rect::new(Vector2(0.0, 0.0), Vector2(10.0, 5.0));
+        assert!(r.contains_point(Vector2(0.0, 0.0)));
+        assert!(r.contains_point(Vector2(9.99, 4.99)));
+        // The max edges are outside
+        assert!(!r.contains_point(Vector2(10.0, 2.0)));
+        assert!(!r.contains_point(Vector2(2.0, 5.0)));
+        assert!(!r.contains_point(Vector2(-0.01, 2.0)));
+
+        // Neighbours in a grid never share a point
+        let right = Rect::new(Vector2(10.0, 0.0), Vector2(10.0, 5.0));
+        let p = Vector2(10.0, 2.0);
+        assert!(r.contains_point(p) != right.contains_point(p));
+
+        // A zero-size rect contains nothing, not even its own position
+        let empty = Rect::new(Vector2(3.0, 3.0), Vector2::zero());
+        assert!(!empty.contains_point(Vector2(3.0, 3.0)));
+    }
+
+    #[test]
+    fn rect_contains_rect_edges() {
+        let r = Rect::new(Vector2(0.0, 0.0), Vector2(10.0, 10.0));
+        assert!(r.contains_rect(&r));
+        assert!(r.contains_rect(&Rect::new(Vector2(5.0, 5.0), Vector2(5.0, 5.0))));
+        assert!(!r.contains_rect(&Rect::new(Vector2(5.0, 5.0), Vector2(5.1, 5.0))));
+        assert!(!r.contains_rect(&Rect::new(Vector2(-1.0, 0.0), Vector2(2.0, 2.0))));
+        // Zero-size rects on the border are still contained
+        assert!(r.contains_rect(&Rect::new(Vector2(10.0, 10.0), Vector2::zero())));
+        assert!(!r.contains_rect(&Rect::new(Vector2(10.1, 10.0), Vector2::zero())));
+    }
+
+    #[test]
+    fn rect_intersects_edges() {
+        let a = Rect::new(Vector2(0.0, 0.0), Vector2(10.0, 10.0));
+        let overlapping = Rect::new(Vector2(9.0, 9.0), Vector2(5.0, 5.0));
+        assert!(a.intersects(&overlapping) && overlapping.intersects(&a));
+        assert_eq!(
+            a.intersection(&overlapping),
+            Some(Rect::new(Vector2(9.0, 9.0), Vector2(1.0, 1.0)))
+        );
+
+        // Touching along an edge or at a corner isn't an intersection
+        for touching in [
+            Rect::new(Vector2(10.0, 0.0), Vector2(5.0, 10.0)),
+            Rect::new(Vector2(0.0, 10.0), Vector2(10.0, 5.0)),
+            Rect::new(Vector2(10.0, 10.0), Vector2(5.0, 5.0)),
+            Rect::new(Vector2(-5.0, -5.0), Vector2(5.0, 5.0)),
+        ] {
+            assert!(!a.intersects(&touching), "{touching:?}");
+            assert!(!touching.intersects(&a), "{touching:?}");
+            assert_eq!(a.intersection(&touching), None);
+        }
+
+        // A zero-size rect strictly inside still intersects, but not on the border or itself
+        let point = Rect::new(Vector2(5.0, 5.0), Vector2::zero());
+        assert!(a.intersects(&point));
+        assert!(!a.intersects(&Rect::new(Vector2(10.0, 5.0), Vector2::zero())));
+        assert!(!point.intersects(&point));
+    }
+
+    #[test]
+    fn rect_helpers() {
+        let r = Rect::from_corners(Vector2(4.0, 1.0), Vector2(0.0, 3.0));
+        assert_eq!(r, Rect::new(Vector2(0.0, 1.0), Vector2(4.0, 2.0)));
+        assert_eq!(Rect::from_center(r.center(), r.size), r);
+        assert_eq!(r.area(), 8.0);
+        assert_eq!(
+            r.union(&Rect::new(Vector2(-1.0, 2.0), Vector2(1.0, 5.0))),
+            Rect::new(Vector2(-1.0, 1.0), Vector2(5.0, 6.0))
+        );
+        assert_eq!(
+            r.expand(1.0),
+            Rect::new(Vector2(-1.0, 0.0), Vector2(6.0, 4.0))
+        );
+        // Shrinking stops at the center
+        assert_eq!(
+            r.expand(-5.0),
+            Rect::new(Vector2(2.0, 2.0), Vector2::zero())
+        );
+
+        let (left, right) = r.split_x(10.0);
+        assert_eq!(left, r);
+        assert_eq!(right.size.0, 0.0);
+        let (top, bottom) = r.split_y(0.5);
+        assert_eq!(top.size, Vector2(4.0, 0.5));
+        assert_eq!(bottom, Rect::new(Vector2(0.0, 1.5), Vector2(4.0, 1.5)));
+
+        let cells = r.split_grid(2, 2);
+        assert_eq!(cells.len(), 4);
+        assert_eq!(cells[3], Rect::new(Vector2(2.0, 2.0), Vector2(2.0, 1.0)));
+        assert!(r.split_grid(0, 3).is_empty());
+
+        assert_eq!(r.clamp_point(Vector2(10.0, -10.0)), Vector2(4.0, 1.0));
+    }
+
+    #[test]
+    fn matrix3_mul() {
+        let a = Matrix3([[1.0, 2.0, 0.0], [3.0, 4.0, 0.0], [5.0, 6.0, 1.0]]);
+        let b = Matrix3([[7.0, 8.0, 0.0], [9.0, 10.0, 0.0], [11.0, 12.0, 1.0]]);
+        assert_mat3_eq(
+            a * b,
+            Matrix3([[31.0, 46.0, 0.0], [39.0, 58.0, 0.0], [52.0, 76.0, 1.0]]),
+        );
+        assert_mat3_eq(a * Matrix3::IDENTITY, a);
+        assert_mat3_eq(Matrix3::IDENTITY * a, a);
+        assert_vec3_eq(a * Vector3(1.0, 1.0, 1.0), Vector3(9.0, 12.0, 1.0));
+    }
+
+    #[test]
+    fn matrix3_mul_order() {
+        // The right side applies first
+        let m = Matrix3::translation(Vector2(2.0, 3.0)) * Matrix3::scale(Vector2(2.0, 4.0));
+        assert_vec2_eq(m.transform_point(Vector2(1.0, 1.0)), Vector2(4.0, 7.0));
+        let m = Matrix3::scale(Vector2(2.0, 4.0)) * Matrix3::translation(Vector2(2.0, 3.0));
+        assert_vec2_eq(m.transform_point(Vector2(1.0, 1.0)), Vector2(6.0, 16.0));
+    }
+
+    #[test]
+    fn matrix3_constructors() {
+        let p = Vector2(3.0, -2.0);
+        assert_vec2_eq(
+            Matrix3::translation(Vector2(1.0, 5.0)).transform_point(p),
+            Vector2(4.0, 3.0),
+        );
+        assert_vec2_eq(
+            Matrix3::scale(Vector2(2.0, -3.0)).transform_point(p),
+            Vector2(6.0, 6.0),
+        );
+        // Clockwise on screen with y going down
+        assert_vec2_eq(
+            Matrix3::rotation(FRAC_PI_2).transform_point(Vector2(1.0, 0.0)),
+            Vector2(0.0, 1.0),
+        );
+        assert_vec2_eq(Matrix3::rotation(PI).transform_point(p), Vector2(-3.0, 2.0));
+
+        let m = Matrix3::from_scale_rotation_translation(
+            Vector2(2.0, 2.0),
+            FRAC_PI_2,
+            Vector2(10.0, 20.0),
+        );
+        assert_vec2_eq(m.transform_point(Vector2(1.0, 0.0)), Vector2(10.0, 22.0));
+        assert_eq!(Matrix3::default(), Matrix3::IDENTITY);
+    }
+
+    #[test]
+    fn matrix3_transform_vector_ignores_translation() {
+        let m = Matrix3::translation(Vector2(5.0, 5.0)) * Matrix3::scale(Vector2(2.0, 3.0));
+        assert_vec2_eq(m.transform_vector(Vector2(1.0, 1.0)), Vector2(2.0, 3.0));
+        assert_vec2_eq(m.transform_point(Vector2(1.0, 1.0)), Vector2(7.0, 8.0));
+    }
+
+    #[test]
+    fn matrix3_gpu_layout() {
+        // WGSL's mat3x3<f32> is 48 bytes, each column padded to 16
+        assert_eq!(std::mem::size_of::<GpuMatrix3>(), 48);
+
+        let m = Matrix3([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+        let gpu = m.to_gpu();
+        let floats: &[f32] = bytemuck::cast_slice(std::slice::from_ref(&gpu));
+        assert_eq!(
+            floats,
+            [1.0, 2.0, 3.0, 0.0, 4.0, 5.0, 6.0, 0.0, 7.0, 8.0, 9.0, 0.0]
+        );
+        assert_eq!(Matrix3::from(m.to_gpu()), m);
+    }
+
+    #[test]
+    fn matrix3_transpose() {
+        let a = Matrix3([[1.0, 2.0, 0.0], [3.0, 4.0, 0.0], [5.0, 6.0, 1.0]]);
+        assert_eq!(
+            a.transpose(),
+            Matrix3([[1.0, 3.0, 5.0], [2.0, 4.0, 6.0], [0.0, 0.0, 1.0]])
+        );
+        assert_eq!(a.transpose().transpose(), a);
+        assert_eq!(a.row(2), Vector3(0.0, 0.0, 1.0));
+        assert_eq!(a.col(2), Vector3(5.0, 6.0, 1.0));
+    }
+
+    #[test]
+    fn matrix3_inverse() {
+        let m =
+            Matrix3::from_scale_rotation_translation(Vector2(2.0, 3.0), 0.7, Vector2(5.0, -4.0));
+        let inverse = m.inverse().unwrap();
+        assert_mat3_eq(m * inverse, Matrix3::IDENTITY);
+        assert_mat3_eq(inverse * m, Matrix3::IDENTITY);
+
+        let p = Vector2(1.5, -2.5);
+        assert_vec2_eq(inverse.transform_point(m.transform_point(p)), p);
+
+        assert_mat3_eq(
+            Matrix3::translation(Vector2(2.0, 3.0)).inverse().unwrap(),
+            Matrix3::translation(Vector2(-2.0, -3.0)),
+        );
+        assert!((m.determinant() - 6.0).abs() <= EPSILON);
+    }
+
+    #[test]
+    fn matrix3_singular_inverse() {
+        assert_eq!(Matrix3::scale(Vector2(0.0, 1.0)).inverse(), None);
+        // Both columns point the same way
+        let m = Matrix3([[1.0, 2.0, 0.0], [2.0, 4.0, 0.0], [0.0, 0.0, 1.0]]);
+        assert_eq!(m.determinant(), 0.0);
+        assert_eq!(m.inverse(), None);
+        assert_eq!(Matrix3::scale(Vector2(f32::NAN, 1.0)).inverse(), None);
+    }
+
+    #[test]
+    fn matrix3_orthographic() {
+        let m = Matrix3::orthographic(0.0, 800.0, 600.0, 0.0);
+        assert_mat3_eq(
+            m,
+            Matrix3([
+                [2.0 / 800.0, 0.0, 0.0],
+                [0.0, -2.0 / 600.0, 0.0],
+                [-1.0, 1.0, 1.0],
+            ]),
+        );
+        assert_vec2_eq(m.transform_point(Vector2(0.0, 0.0)), Vector2(-1.0, 1.0));
+        assert_vec2_eq(m.transform_point(Vector2(800.0, 600.0)), Vector2(1.0, -1.0));
+        assert_vec2_eq(m.transform_point(Vector2(400.0, 300.0)), Vector2(0.0, 0.0));
+    }
+
+    #[test]
+    fn matrix4_mul() {
+        let a = Matrix4([
+            [1.0, 2.0, 3.0, 4.0],
+            [5.0, 6.0, 7.0, 8.0],
+            [9.0, 10.0, 11.0, 12.0],
+            [13.0, 14.0, 15.0, 16.0],
+        ]);
+        let b =
+            Matrix4::scale(Vector3(2.0, 3.0, 4.0)) * Matrix4::translation(Vector3(1.0, 1.0, 1.0));
+        // Column-major, so `a * b` is `a` applied to each column of `b`
+        let expected = Matrix4([
+            [2.0, 4.0, 6.0, 8.0],
+            [15.0, 18.0, 21.0, 24.0],
+            [36.0, 40.0, 44.0, 48.0],
+            [66.0, 76.0, 86.0, 96.0],
+        ]);
+        assert_mat4_eq(a * b, expected);
+        assert_mat4_eq(a * Matrix4::IDENTITY, a);
+        assert_mat4_eq(Matrix4::IDENTITY * a, a);
+        assert_eq!(
+            a * Vector4(1.0, 0.0, 0.0, 1.0),
+            Vector4(14.0, 16.0, 18.0, 20.0)
+        );
+    }
+
+    #[test]
+    fn matrix4_constructors() {
+        let p = Vector3(1.0, 2.0, 3.0);
+        assert_vec3_eq(
+            Matrix4::translation(Vector3(1.0, -1.0, 2.0)).transform_point(p),
+            Vector3(2.0, 1.0, 5.0),
+        );
+        assert_vec3_eq(
+            Matrix4::scale(Vector3(2.0, 3.0, -1.0)).transform_point(p),
+            Vector3(2.0, 6.0, -3.0),
+        );
+
+        // Right-handed, counterclockwise looking down the axis
+        let x = Vector3(1.0, 0.0, 0.0);
+        let y = Vector3(0.0, 1.0, 0.0);
+        let z = Vector3(0.0, 0.0, 1.0);
+        assert_vec3_eq(Matrix4::rotation_x(FRAC_PI_2).transform_point(y), z);
+        assert_vec3_eq(Matrix4::rotation_y(FRAC_PI_2).transform_point(z), x);
+        assert_vec3_eq(Matrix4::rotation_z(FRAC_PI_2).transform_point(x), y);
+
+        assert_mat4_eq(Matrix4::rotation(x, 0.3), Matrix4::rotation_x(0.3));
+        assert_mat4_eq(Matrix4::rotation(y * 5.0, 0.3), Matrix4::rotation_y(0.3));
+        assert_mat4_eq(Matrix4::rotation(z, -1.2), Matrix4::rotation_z(-1.2));
+
+        let m = Matrix4::from_scale_rotation_translation(
+            Vector3(2.0, 2.0, 2.0),
+            Quaternion::from_rotation_z(FRAC_PI_2),
+            Vector3(0.0, 0.0, 5.0),
+        );
+        assert_vec3_eq(m.transform_point(x), Vector3(0.0, 2.0, 5.0));
+        assert_eq!(Matrix4::default(), Matrix4::IDENTITY);
+    }
+
+    #[test]
+    fn matrix4_transform_vector_ignores_translation() {
+        let m =
+            Matrix4::translation(Vector3(5.0, 5.0, 5.0)) * Matrix4::scale(Vector3(2.0, 3.0, 4.0));
+        assert_vec3_eq(
+            m.transform_vector(Vector3(1.0, 1.0, 1.0)),
+            Vector3(2.0, 3.0, 4.0),
+        );
+        assert_vec3_eq(
+            m.transform_point(Vector3(1.0, 1.0, 1.0)),
+            Vector3(7.0, 8.0, 9.0),
+        );
+    }
+
+    #[test]
+    fn matrix4_transpose() {
+        let a = Matrix4([
+            [1.0, 2.0, 3.0, 4.0],
+            [5.0, 6.0, 7.0, 8.0],
+            [9.0, 10.0, 11.0, 12.0],
+            [13.0, 14.0, 15.0, 16.0],
+        ]);
+        assert_eq!(
+            a.transpose(),
+            Matrix4([
+                [1.0, 5.0, 9.0, 13.0],
+                [2.0, 6.0, 10.0, 14.0],
+                [3.0, 7.0, 11.0, 15.0],
+                [4.0, 8.0, 12.0, 16.0],
+            ])
+        );
+        assert_eq!(a.transpose().transpose(), a);
+        assert_eq!(a.row(1), Vector4(2.0, 6.0, 10.0, 14.0));
+    }
+
+    #[test]
+    fn matrix4_inverse() {
+        assert_mat4_eq(
+            Matrix4::scale(Vector3(2.0, 4.0, 5.0)).inverse().unwrap(),
+            Matrix4::scale(Vector3(0.5, 0.25, 0.2)),
+        );
+
+        // Not affine, so every minor matters
+        let m = Matrix4([
+            [2.0, 0.0, 1.0, 0.0],
+            [1.0, 3.0, 0.0, 0.0],
+            [0.0, 1.0, 4.0, 1.0],
+            [1.0, 0.0, 0.0, 2.0],
+        ]);
+        let inverse = m.inverse().unwrap();
+        assert_mat4_eq(m * inverse, Matrix4::IDENTITY);
+        assert_mat4_eq(inverse * m, Matrix4::IDENTITY);
+        assert!((m.determinant() - 53.0).abs() <= EPSILON);
+
+        let m = Matrix4::from_scale_rotation_translation(
+            Vector3(1.0, 2.0, 3.0),
+            Quaternion::from_axis_angle(Vector3(1.0, 1.0, 0.0), 0.8),
+            Vector3(4.0, -5.0, 6.0),
+        );
+        let p = Vector3(0.5, -1.0, 2.0);
+        assert_vec3_eq(
+            m.inverse().unwrap().transform_point(m.transform_point(p)),
+            p,
+        );
+    }
+
+    #[test]
+    fn matrix4_singular_inverse() {
+        assert_eq!(Matrix4::scale(Vector3(1.0, 0.0, 1.0)).inverse(), None);
+        let m = Matrix4([
+            [1.0, 2.0, 3.0, 4.0],
+            [5.0, 6.0, 7.0, 8.0],
+            [9.0, 10.0, 11.0, 12.0],
+            [13.0, 14.0, 15.0, 16.0],
+        ]);
+        assert_eq!(m.determinant(), 0.0);
+        assert_eq!(m.inverse(), None);
+    }
+
+    #[test]
+    fn matrix4_orthographic() {
+        let rh = Matrix4::orthographic_rh(0.0, 4.0, 0.0, 2.0, 1.0, 3.0);
+        assert_mat4_eq(
+            rh,
+            Matrix4([
+                [0.5, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, -0.5, 0.0],
+                [-1.0, -1.0, -0.5, 1.0],
+            ]),
+        );
+        // Depth goes from 0 at the near plane to 1 at the far one
+        assert_vec3_eq(
+            rh.transform_point(Vector3(0.0, 0.0, -1.0)),
+            Vector3(-1.0, -1.0, 0.0),
+        );
+        assert_vec3_eq(
+            rh.transform_point(Vector3(4.0, 2.0, -3.0)),
+            Vector3(1.0, 1.0, 1.0),
+        );
+
+        let lh = Matrix4::orthographic_lh(0.0, 4.0, 0.0, 2.0, 1.0, 3.0);
+        assert_mat4_eq(
+            lh,
+            Matrix4([
+                [0.5, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 0.5, 0.0],
+                [-1.0, -1.0, -0.5, 1.0],
+            ]),
+        );
+        assert_vec3_eq(
+            lh.transform_point(Vector3(4.0, 2.0, 3.0)),
+            Vector3(1.0, 1.0, 1.0),
+        );
+    }
+
+    #[test]
+    fn matrix4_perspective() {
+        let rh = Matrix4::perspective_rh(FRAC_PI_2, 2.0, 1.0, 10.0);
+        assert_mat4_eq(
+            rh,
+            Matrix4([
+                [0.5, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, -10.0 / 9.0, -1.0],
+                [0.0, 0.0, -10.0 / 9.0, 0.0],
+            ]),
+        );
+        assert_vec3_eq(
+            rh.project_point(Vector3(0.0, 0.0, -1.0)),
+            Vector3(0.0, 0.0, 0.0),
+        );
+        assert_vec3_eq(
+            rh.project_point(Vector3(0.0, 0.0, -10.0)),
+            Vector3(0.0, 0.0, 1.0),
+        );
+        // The edge of the 90 degree view
+        assert_vec3_eq(
+            rh.project_point(Vector3(10.0, 5.0, -5.0)),
+            Vector3(1.0, 1.0, 1.0 - 1.0 / 9.0),
+        );
+
+        let lh = Matrix4::perspective_lh(FRAC_PI_2, 2.0, 1.0, 10.0);
+        assert_mat4_eq(
+            lh,
+            Matrix4([
+                [0.5, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 10.0 / 9.0, 1.0],
+                [0.0, 0.0, -10.0 / 9.0, 0.0],
+            ]),
+        );
+        assert_vec3_eq(
+            lh.project_point(Vector3(0.0, 0.0, 10.0)),
+            Vector3(0.0, 0.0, 1.0),
+        );
+    }
+
+    #[test]
+    fn matrix4_look_at() {
+        let up = Vector3(0.0, 1.0, 0.0);
+        assert_mat4_eq(
+            Matrix4::look_at_rh(Vector3(1.0, 2.0, 3.0), Vector3(1.0, 2.0, 0.0), up),
+            Matrix4::translation(Vector3(-1.0, -2.0, -3.0)),
+        );
+
+        let rh = Matrix4::look_at_rh(Vector3(5.0, 0.0, 0.0), Vector3::zero(), up);
+        assert_mat4_eq(
+            rh,
+            Matrix4([
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [-1.0, 0.0, 0.0, 0.0],
+                [0.0, 0.0, -5.0, 1.0],
+            ]),
+        );
+        // In front of a right-handed camera is -z
+        assert_vec3_eq(rh.transform_point(Vector3::zero()), Vector3(0.0, 0.0, -5.0));
+
+        let lh = Matrix4::look_at_lh(Vector3(5.0, 0.0, 0.0), Vector3::zero(), up);
+        assert_vec3_eq(lh.transform_point(Vector3::zero()), Vector3(0.0, 0.0, 5.0));
+        assert_vec3_eq(lh.transform_point(Vector3(5.0, 0.0, 0.0)), Vector3::zero());
+    }
+
+    #[test]
+    fn matrix4_from_matrix3() {
+        let m3 =
+            Matrix3::from_scale_rotation_translation(Vector2(2.0, 3.0), 0.4, Vector2(-1.0, 7.0));
+        let m4 = Matrix4::from(m3);
+        let p = Vector2(3.0, 4.0);
+        let q = m3.transform_point(p);
+        assert_vec3_eq(
+            m4.transform_point(Vector3(p.0, p.1, 0.5)),
+            Vector3(q.0, q.1, 0.5),
+        );
+    }
+
+    #[test]
+    fn quaternion_rotates_like_matrix() {
+        let v = Vector3(1.0, -2.0, 0.5);
+        let rotations = [
+            Quaternion::IDENTITY,
+            Quaternion::from_rotation_x(0.3),
+            Quaternion::from_rotation_y(-2.0),
+            Quaternion::from_rotation_z(PI),
+            Quaternion::from_axis_angle(Vector3(1.0, 2.0, 3.0), 1.1),
+        ];
+        for q in rotations {
+            assert_vec3_eq(q * v, Matrix4::from(q).transform_vector(v));
+        }
+        assert_vec3_eq(
+            Quaternion::from_rotation_z(FRAC_PI_2) * Vector3(1.0, 0.0, 0.0),
+            Vector3(0.0, 1.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn quaternion_matrix_round_trip() {
+        // Covers every branch of `from_rotation_matrix`, including half turns
+        let rotations = [
+            Quaternion::IDENTITY,
+            Quaternion::from_rotation_x(PI),
+            Quaternion::from_rotation_y(PI),
+            Quaternion::from_rotation_z(PI),
+            Quaternion::from_rotation_x(2.5),
+            Quaternion::from_rotation_y(-2.5),
+            Quaternion::from_rotation_z(3.0),
+            Quaternion::from_axis_angle(Vector3(1.0, 2.0, 3.0), 1.1),
+            Quaternion::from_axis_angle(Vector3(-3.0, 0.5, 1.0), 2.9),
+        ];
+        for q in rotations {
+            let m = Matrix4::from(q);
+            let back = Quaternion::from_rotation_matrix(m);
+            assert_quat_eq(back, q);
+            assert_mat4_eq(Matrix4::from(back), m);
+        }
+
+        let m = Matrix4::rotation_y(0.6) * Matrix4::rotation_x(-1.3);
+        assert_mat4_eq(Matrix4::from(Quaternion::from_rotation_matrix(m)), m);
+    }
+
+    #[test]
+    fn quaternion_mul() {
+        let a = Quaternion::from_rotation_x(0.7);
+        let b = Quaternion::from_axis_angle(Vector3(0.0, 1.0, 1.0), -1.4);
+        let v = Vector3(2.0, 0.0, -1.0);
+        // `b` applies first
+        assert_vec3_eq((a * b) * v, a * (b * v));
+        assert_mat4_eq(Matrix4::from(a * b), Matrix4::from(a) * Matrix4::from(b));
+        assert_quat_eq(
+            Quaternion::from_rotation_z(0.5) * Quaternion::from_rotation_z(0.25),
+            Quaternion::from_rotation_z(0.75),
+        );
+    }
+
+    #[test]
+    fn quaternion_inverse_and_axis_angle() {
+        let q = Quaternion::from_axis_angle(Vector3(1.0, 2.0, 2.0), 1.2);
+        assert_quat_eq(q * q.inverse(), Quaternion::IDENTITY);
+        assert_quat_eq(q.inverse(), q.conjugate());
+
+        let (axis, angle) = q.to_axis_angle();
+        assert_vec3_eq(axis, Vector3(1.0, 2.0, 2.0) / 3.0);
+        assert!((angle - 1.2).abs() <= EPSILON);
+        assert_eq!(Quaternion::IDENTITY.to_axis_angle(), (Vector3::zero(), 0.0));
+    }
+
+    #[test]
+    fn quaternion_slerp() {
+        let a = Quaternion::IDENTITY;
+        let b = Quaternion::from_rotation_z(FRAC_PI_2);
+        assert_quat_eq(a.slerp(b, 0.0), a);
+        assert_quat_eq(a.slerp(b, 1.0), b);
+        assert_quat_eq(a.slerp(b, 0.5), Quaternion::from_rotation_z(FRAC_PI_4));
+        // -b is the same rotation, and still takes the short way
+        let neg_b = Quaternion(-b.0, -b.1, -b.2, -b.3);
+        assert_quat_eq(a.slerp(neg_b, 0.5), Quaternion::from_rotation_z(FRAC_PI_4));
+    }
+
+    #[test]
+    fn vector_rotate() {
+        assert_vec2_eq(Vector2(1.0, 0.0).rotate(FRAC_PI_2), Vector2(0.0, 1.0));
+        assert_vec2_eq(Vector2(3.0, 4.0).rotate(PI), Vector2(-3.0, -4.0));
+        assert_vec2_eq(Vector2(3.0, 4.0).rotate(0.0), Vector2(3.0, 4.0));
+        // Keeps the length
+        assert!((Vector2(3.0, 4.0).rotate(1.3).length() - 5.0).abs() <= EPSILON);
+        assert_vec2_eq(Vector2(1.0, 2.0).perpendicular(), Vector2(-2.0, 1.0));
+        assert_vec2_eq(
+            Vector2(1.0, 2.0).perpendicular(),
+            Vector2(1.0, 2.0).rotate(FRAC_PI_2),
+        );
+    }
+
+    #[test]
+    fn vector_angle() {
+        assert_eq!(Vector2(1.0, 0.0).angle(), 0.0);
+        assert!((Vector2(0.0, 1.0).angle() - FRAC_PI_2).abs() <= EPSILON);
+        assert!((Vector2(-1.0, 0.0).angle() - PI).abs() <= EPSILON);
+        assert!((Vector2(1.0, -1.0).angle() + FRAC_PI_4).abs() <= EPSILON);
+
+        // Signed for 2D, unsigned for 3D
+        let x = Vector2(1.0, 0.0);
+        assert!((x.angle_to(Vector2(0.0, 1.0)) - FRAC_PI_2).abs() <= EPSILON);
+        assert!((x.angle_to(Vector2(0.0, -1.0)) + FRAC_PI_2).abs() <= EPSILON);
+        let angle = Vector3(1.0, 0.0, 0.0).angle_to(Vector3(0.0, 0.0, -2.0));
+        assert!((angle - FRAC_PI_2).abs() <= EPSILON);
+    }
+
+    #[test]
+    fn vector_reflect() {
+        let up = Vector2(0.0, -1.0);
+        assert_vec2_eq(Vector2(1.0, 1.0).reflect(up), Vector2(1.0, -1.0));
+        assert_vec2_eq(Vector2(3.0, 0.0).reflect(up), Vector2(3.0, 0.0));
+        let normal = Vector3(1.0, 1.0, 0.0).normalized();
+        assert_vec3_eq(
+            Vector3(-1.0, 0.0, 2.0).reflect(normal),
+            Vector3(0.0, 1.0, 2.0),
+        );
+    }
+
+    #[test]
+    fn vector_project() {
+        assert_vec2_eq(
+            Vector2(3.0, 4.0).project(Vector2(2.0, 0.0)),
+            Vector2(3.0, 0.0),
+        );
+        assert_vec3_eq(
+            Vector3(1.0, 2.0, 3.0).project(Vector3(0.0, 0.0, -5.0)),
+            Vector3(0.0, 0.0, 3.0),
+        );
+        // Perpendicular vectors have nothing in common
+        assert_vec2_eq(
+            Vector2(0.0, 4.0).project(Vector2(1.0, 0.0)),
+            Vector2::zero(),
+        );
+        // Projecting onto zero gives zero instead of NaN
+        assert_eq!(
+            Vector4(1.0, 2.0, 3.0, 4.0).project(Vector4::zero()),
+            Vector4::zero()
+        );
+    }
+
+    #[test]
+    fn vector_clamp_length() {
+        let v = Vector2(3.0, 4.0);
+        assert_vec2_eq(v.clamp_length(0.0, 1.0), Vector2(0.6, 0.8));
+        assert_vec2_eq(v.clamp_length(10.0, 20.0), Vector2(6.0, 8.0));
+        assert_vec2_eq(v.clamp_length(1.0, 10.0), v);
+        assert!((Vector3(1.0, 2.0, 2.0).clamp_length(0.0, 1.5).length() - 1.5).abs() <= EPSILON);
+
+        // No direction to grow along, so it stays zero
+        assert_eq!(Vector2::zero().clamp_length(1.0, 2.0), Vector2::zero());
+        assert_eq!(Vector3::zero().clamp_length(1.0, 2.0), Vector3::zero());
+        assert_eq!(Vector2::zero().normalized(), Vector2::zero());
+    }
+
+    #[test]
+    fn vector_component_helpers() {
+        let a = Vector3(1.5, -2.5, 3.0);
+        let b = Vector3(2.0, -3.0, 1.0);
+        assert_eq!(a.min(b), Vector3(1.5, -3.0, 1.0));
+        assert_eq!(a.max(b), Vector3(2.0, -2.5, 3.0));
+        assert_eq!(a.abs(), Vector3(1.5, 2.5, 3.0));
+        assert_eq!(a.floor(), Vector3(1.0, -3.0, 3.0));
+        assert_eq!(a.ceil(), Vector3(2.0, -2.0, 3.0));
+        assert_eq!(
+            a.clamp(Vector3::zero(), Vector3(1.0, 1.0, 1.0)),
+            Vector3(1.0, 0.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn vector_swizzles() {
+        let v3 = Vector3(1.0, 2.0, 3.0);
+        let v4 = Vector4(1.0, 2.0, 3.0, 4.0);
+        assert_eq!(v3.xy(), Vector2(1.0, 2.0));
+        assert_eq!(v4.xy(), Vector2(1.0, 2.0));
+        assert_eq!(v4.xyz(), v3);
+        assert_eq!(Vector2(1.0, 2.0).extend(3.0), v3);
+        assert_eq!(v3.extend(4.0), v4);
+        assert_eq!(v4.xyz().xy().extend(3.0).extend(4.0), v4);
+    }
+
+    #[test]
+    fn vector_operators() {
+        let a = Vector2(6.0, -4.0);
+        let b = Vector2(2.0, 4.0);
+        assert_eq!(a + b, Vector2(8.0, 0.0));
+        assert_eq!(a - b, Vector2(4.0, -8.0));
+        assert_eq!(a * b, Vector2(12.0, -16.0));
+        assert_eq!(a / b, Vector2(3.0, -1.0));
+        assert_eq!(a * 0.5, Vector2(3.0, -2.0));
+        assert_eq!(0.5 * a, Vector2(3.0, -2.0));
+        assert_eq!(a / 2.0, Vector2(3.0, -2.0));
+        assert_eq!(-a, Vector2(-6.0, 4.0));
+
+        let mut c = Vector4(1.0, 2.0, 3.0, 4.0);
+        c += Vector4(1.0, 1.0, 1.0, 1.0);
+        c -= Vector4(0.0, 1.0, 0.0, 1.0);
+        c *= 2.0;
+        c *= Vector4(1.0, 0.5, 1.0, 0.5);
+        c /= Vector4(2.0, 1.0, 2.0, 1.0);
+        c /= 0.5;
+        assert_eq!(c, Vector4(4.0, 4.0, 8.0, 8.0));
+    }
+
+    #[test]
+    fn vector_sum() {
+        let points = [Vector2(1.0, 2.0), Vector2(3.0, 4.0), Vector2(-1.0, 0.5)];
+        assert_eq!(points.iter().sum::<Vector2>(), Vector2(3.0, 6.5));
+        assert_eq!(points.into_iter().sum::<Vector2>(), Vector2(3.0, 6.5));
+        assert_eq!(
+            std::iter::empty::<Vector3>().sum::<Vector3>(),
+            Vector3::zero()
+        );
+        assert_eq!(
+            [Vector4(1.0, 2.0, 3.0, 4.0); 3].iter().sum::<Vector4>(),
+            Vector4(3.0, 6.0, 9.0, 12.0)
+        );
+    }
+
+    #[test]
+    fn vector_index() {
+        let mut v = Vector3(1.0, 2.0, 3.0);
+        assert_eq!((v[0], v[1], v[2]), (1.0, 2.0, 3.0));
+        v[1] = 5.0;
+        assert_eq!(v, Vector3(1.0, 5.0, 3.0));
+
+        let mut v = Vector4(1.0, 2.0, 3.0, 4.0);
+        v[3] += 1.0;
+        assert_eq!(v[3], 5.0);
+        assert_eq!(Vector2(7.0, 8.0)[1], 8.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 2 out of range for Vector2")]
+    fn vector_index_out_of_range() {
+        let _ = Vector2(1.0, 2.0)[2];
+    }
+
+    #[test]
+    #[should_panic(expected = "index 4 out of range for Vector4")]
+    fn vector_index_mut_out_of_range() {
+        let mut v = Vector4::zero();
+        v[4] = 1.0;
+    }
+
+    #[test]
+    fn vector_approx_eq() {
+        let a = Vector3(1.0, 2.0, 3.0);
+        assert!(a.approx_eq(Vector3(1.0005, 1.9995, 3.0), 1e-3));
+        assert!(!a.approx_eq(Vector3(1.0, 2.0, 3.01), 1e-3));
+        // Every component has to be close, not just the total
+        assert!(!Vector2(0.0, 0.0).approx_eq(Vector2(0.0, 1.0), 0.5));
+        assert!(a.approx_eq(a, 0.0));
+        assert!(!Vector2(f32::NAN, 0.0).approx_eq(Vector2(f32::NAN, 0.0), 1.0));
     }
 }